@@ -168,6 +168,25 @@ pub fn compare_ropes(before: &Rope, after: &Rope) -> Transaction {
     res
 }
 
+/// Compares the lines of `before` and `after` and returns the changed line ranges of
+/// each hunk as `(before, after)` pairs.
+pub fn compare_rope_lines(before: &Rope, after: &Rope) -> Vec<(Range<usize>, Range<usize>)> {
+    let file = InternedInput::new(RopeLines(before.slice(..)), RopeLines(after.slice(..)));
+    let mut diff = Diff::compute(Algorithm::Histogram, &file);
+    diff.postprocess_with_heuristic(
+        &file,
+        IndentHeuristic::new(|token| IndentLevel::for_ascii_line(file.interner[token].bytes(), 4)),
+    );
+    diff.hunks()
+        .map(|hunk| {
+            (
+                hunk.before.start as usize..hunk.before.end as usize,
+                hunk.after.start as usize..hunk.after.end as usize,
+            )
+        })
+        .collect()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -188,6 +207,17 @@ fn test_compare_ropes(a: String, b: String) -> bool {
         }
     }
 
+    #[test]
+    fn line_hunks() {
+        let before = Rope::from("a\nb\nc\nd\n");
+        let after = Rope::from("a\nB\nc\nd\ne\n");
+        assert_eq!(
+            compare_rope_lines(&before, &after),
+            vec![(1..2, 1..2), (4..4, 4..5)]
+        );
+        assert!(compare_rope_lines(&before, &before).is_empty());
+    }
+
     #[test]
     fn equal_files() {
         test_identity("foo", "foo");
@@ -174,6 +174,7 @@ fn default() -> Self {
 pub struct DocumentFormatter<'t> {
     text_fmt: &'t TextFormat,
     annotations: &'t TextAnnotations<'t>,
+    text: RopeSlice<'t>,
 
     /// The visual position at the end of the last yielded word boundary
     visual_pos: Position,
@@ -213,13 +214,18 @@ pub fn new_at_prev_checkpoint(
         char_idx: usize,
     ) -> Self {
         // TODO divide long lines into blocks to avoid bad performance for long lines
-        let block_line_idx = text.char_to_line(char_idx.min(text.len_chars()));
+        let mut block_line_idx = text.char_to_line(char_idx.min(text.len_chars()));
+        // folded text is never rendered, start at the line the fold collapses into instead
+        if let Some(fold) = annotations.fold_containing(text.line_to_char(block_line_idx)) {
+            block_line_idx = text.char_to_line(fold.start).saturating_sub(1);
+        }
         let block_char_idx = text.line_to_char(block_line_idx);
         annotations.reset_pos(block_char_idx);
 
         DocumentFormatter {
             text_fmt,
             annotations,
+            text,
             visual_pos: Position { row: 0, col: 0 },
             graphemes: text.slice(block_char_idx..).graphemes(),
             char_pos: block_char_idx,
@@ -420,6 +426,23 @@ fn advance_to_next_word(&mut self) {
         }
     }
 
+    /// Skips the folded text starting at the current position (if any).
+    ///
+    /// Folds always start at the beginning of a line so this is only called after a
+    /// line break. Line breaks are word boundaries, so no graphemes past the line break
+    /// have been buffered at this point.
+    fn skip_fold(&mut self) {
+        let Some(fold) = self.annotations.fold_at(self.char_pos) else {
+            return;
+        };
+        debug_assert!(self.peeked_grapheme.is_none());
+        let end = fold.end.min(self.text.len_chars());
+        self.char_pos = end;
+        self.line_pos = self.text.char_to_line(end);
+        self.graphemes = self.text.slice(end..).graphemes();
+        self.annotations.skip_fold(end);
+    }
+
     /// returns the char index at the end of the last yielded grapheme
     pub fn next_char_pos(&self) -> usize {
         self.char_pos
@@ -471,6 +494,7 @@ fn next(&mut self) -> Option<Self::Item> {
             self.visual_pos.col = 0;
             if !grapheme.is_virtual() {
                 self.line_pos += 1;
+                self.skip_fold();
             }
         } else {
             self.visual_pos.col += grapheme.width();
@@ -1,3 +1,5 @@
+use std::ops::Range;
+
 use crate::doc_formatter::{DocumentFormatter, TextFormat};
 use crate::text_annotations::{InlineAnnotation, Overlay, TextAnnotations};
 
@@ -211,3 +213,42 @@ fn annotation_and_overlay() {
         "fooo  bar "
     );
 }
+
+fn fold_text(text: &str, char_pos: usize, softwrap: bool, fold: Range<usize>) -> String {
+    DocumentFormatter::new_at_prev_checkpoint(
+        text.into(),
+        &TextFormat::new_test(softwrap),
+        TextAnnotations::default().set_folds(std::slice::from_ref(&fold)),
+        char_pos,
+    )
+    .collect_to_str()
+}
+
+#[test]
+fn fold() {
+    let text = "a\nb\nc\nd\n";
+    assert_eq!(fold_text(text, 0, false, 2..6), "a \nd \n ");
+    assert_eq!(fold_text(text, 0, true, 2..6), "a \nd \n ");
+    // folds at the end of the document hide the last line
+    assert_eq!(fold_text(text, 0, false, 4..8), "a \nb \n ");
+    // formatting from within a fold starts at the line it collapses into
+    assert_eq!(fold_text(text, 4, false, 2..6), "a \nd \n ");
+}
+
+#[test]
+fn fold_skips_annotations() {
+    let annotations = [InlineAnnotation::new(2, "x"), InlineAnnotation::new(6, "y")];
+    let fold = 2..6;
+    assert_eq!(
+        DocumentFormatter::new_at_prev_checkpoint(
+            "a\nb\nc\nd\n".into(),
+            &TextFormat::new_test(false),
+            TextAnnotations::default()
+                .add_inline_annotations(annotations.as_slice(), None)
+                .set_folds(std::slice::from_ref(&fold)),
+            0,
+        )
+        .collect_to_str(),
+        "a \nyd \n "
+    );
+}
@@ -70,7 +70,9 @@ pub fn move_vertically_visual(
     text_fmt: &TextFormat,
     annotations: &mut TextAnnotations,
 ) -> Range {
-    if !text_fmt.soft_wrap {
+    // folded lines are skipped by the document formatter, so the visual
+    // computation is also required when there are folds
+    if !text_fmt.soft_wrap && !annotations.has_folds() {
         return move_vertically(slice, range, dir, count, behaviour, text_fmt, annotations);
     }
     annotations.clear_line_annotations();
@@ -749,6 +751,38 @@ fn test_vertical_move() {
         );
     }
 
+    #[test]
+    fn test_vertical_move_skips_folds() {
+        let text = Rope::from("abcd\nefg\nwrs\nxyz");
+        let slice = text.slice(..);
+        let fold = slice.line_to_char(1)..slice.line_to_char(3);
+        let mut annotations = TextAnnotations::default();
+        annotations.set_folds(std::slice::from_ref(&fold));
+
+        let pos = pos_at_coords(slice, (0, 2).into(), true);
+        let range = move_vertically_visual(
+            slice,
+            Range::point(pos),
+            Direction::Forward,
+            1,
+            Movement::Move,
+            &TextFormat::default(),
+            &mut annotations,
+        );
+        assert_eq!(coords_at_pos(slice, range.head), (3, 2).into());
+
+        let range = move_vertically_visual(
+            slice,
+            range,
+            Direction::Backward,
+            1,
+            Movement::Move,
+            &TextFormat::default(),
+            &mut annotations,
+        );
+        assert_eq!(coords_at_pos(slice, range.head), (0, 2).into());
+    }
+
     #[test]
     fn horizontal_moves_through_single_line_text() {
         let text = Rope::from(SINGLE_LINE_SAMPLE);
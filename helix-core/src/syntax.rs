@@ -44,6 +44,7 @@ pub struct LanguageData {
     textobject_query: OnceCell<Option<TextObjectQuery>>,
     tag_query: OnceCell<Option<TagQuery>>,
     rainbow_query: OnceCell<Option<RainbowQuery>>,
+    fold_query: OnceCell<Option<FoldQuery>>,
 }
 
 impl LanguageData {
@@ -55,6 +56,7 @@ fn new(config: LanguageConfiguration) -> Self {
             textobject_query: OnceCell::new(),
             tag_query: OnceCell::new(),
             rainbow_query: OnceCell::new(),
+            fold_query: OnceCell::new(),
         }
     }
 
@@ -231,6 +233,36 @@ fn rainbow_query(&self, loader: &Loader) -> Option<&RainbowQuery> {
             .as_ref()
     }
 
+    /// Compiles the folds.scm query for a language.
+    /// This function should only be used by this module or the xtask crate.
+    pub fn compile_fold_query(
+        grammar: Grammar,
+        config: &LanguageConfiguration,
+    ) -> Result<Option<FoldQuery>> {
+        let name = &config.language_id;
+        let text = read_query(name, "folds.scm");
+        if text.is_empty() {
+            return Ok(None);
+        }
+        let query = Query::new(grammar, &text, |_, _| Ok(()))
+            .with_context(|| format!("Failed to compile folds.scm query for '{name}'"))?;
+        Ok(Some(FoldQuery::new(query)))
+    }
+
+    fn fold_query(&self, loader: &Loader) -> Option<&FoldQuery> {
+        self.fold_query
+            .get_or_init(|| {
+                let grammar = self.syntax_config(loader)?.grammar;
+                Self::compile_fold_query(grammar, &self.config)
+                    .map_err(|err| {
+                        log::error!("{err}");
+                    })
+                    .ok()
+                    .flatten()
+            })
+            .as_ref()
+    }
+
     fn reconfigure(&self, scopes: &[String]) {
         if let Some(Some(config)) = self.syntax.get() {
             reconfigure_highlights(config, scopes);
@@ -424,6 +456,10 @@ fn rainbow_query(&self, lang: Language) -> Option<&RainbowQuery> {
         self.language(lang).rainbow_query(self)
     }
 
+    fn fold_query(&self, lang: Language) -> Option<&FoldQuery> {
+        self.language(lang).fold_query(self)
+    }
+
     pub fn language_server_configs(&self) -> &HashMap<String, LanguageServerConfiguration> {
         &self.language_server_configs
     }
@@ -682,6 +718,59 @@ struct RainbowScope<'tree> {
 
         OverlayHighlights::Heterogenous { highlights }
     }
+
+    /// Returns the line ranges captured by the `@fold` capture of the `folds.scm`
+    /// queries as `(start_line, end_line)` pairs, both inclusive.
+    ///
+    /// All nodes captured by a single match (for example `(comment)+ @fold`) form a
+    /// single range. Ranges spanning a single line are ignored. The ranges are sorted
+    /// by their start line and don't contain duplicates.
+    pub fn folding_ranges(&self, source: RopeSlice, loader: &Loader) -> Vec<(usize, usize)> {
+        let mut matches: HashMap<(Layer, u32), ops::Range<u32>> = HashMap::new();
+        let mut query_iter = self.query_iter::<_, (), _>(
+            source,
+            |lang| loader.fold_query(lang).map(|q| &q.query),
+            ..,
+        );
+
+        while let Some(event) = query_iter.next() {
+            let QueryIterEvent::Match(mat) = event else {
+                continue;
+            };
+            let fold_query = loader
+                .fold_query(query_iter.current_language())
+                .expect("language must have a fold query to emit matches");
+            if Some(mat.capture) != fold_query.fold_capture {
+                continue;
+            }
+
+            let byte_range = mat.node.byte_range();
+            matches
+                .entry((query_iter.current_layer(), mat.match_id))
+                .and_modify(|range| {
+                    range.start = range.start.min(byte_range.start);
+                    range.end = range.end.max(byte_range.end);
+                })
+                .or_insert(byte_range);
+        }
+
+        let mut ranges: Vec<_> = matches
+            .into_values()
+            .filter_map(|byte_range| {
+                let start_line = source.byte_to_line(byte_range.start as usize);
+                let end = byte_range.end as usize;
+                let mut end_line = source.byte_to_line(end);
+                // nodes that end with a line break end at the start of the next line
+                if end_line > start_line && source.line_to_byte(end_line) == end {
+                    end_line -= 1;
+                }
+                (start_line < end_line).then_some((start_line, end_line))
+            })
+            .collect();
+        ranges.sort_unstable();
+        ranges.dedup();
+        ranges
+    }
 }
 
 pub type Highlighter<'a> = highlighter::Highlighter<'a, 'a, Loader>;
@@ -1052,6 +1141,21 @@ pub fn capture_nodes_any<'a>(
     }
 }
 
+#[derive(Debug)]
+pub struct FoldQuery {
+    query: Query,
+    fold_capture: Option<Capture>,
+}
+
+impl FoldQuery {
+    pub fn new(query: Query) -> Self {
+        Self {
+            fold_capture: query.get_capture("fold"),
+            query,
+        }
+    }
+}
+
 #[derive(Debug)]
 pub struct TagQuery {
     pub query: Query,
@@ -16,7 +16,7 @@
 #[serde(rename_all = "kebab-case")]
 pub struct Configuration {
     pub language: Vec<LanguageConfiguration>,
-    #[serde(default)]
+    #[serde(default, deserialize_with = "deserialize_language_servers")]
     pub language_server: HashMap<String, LanguageServerConfiguration>,
 }
 
@@ -103,6 +103,9 @@ pub struct LanguageConfiguration {
     pub persistent_diagnostic_sources: Vec<String>,
     /// Overrides the `editor.rainbow-brackets` config key for the language.
     pub rainbow_brackets: Option<bool>,
+    /// The source of the ranges used by `expand_selection` and `shrink_selection`.
+    #[serde(default)]
+    pub selection_range_provider: SelectionRangeProvider,
 }
 
 impl LanguageConfiguration {
@@ -274,6 +277,17 @@ pub enum LanguageServerFeature {
     RenameSymbol,
     InlayHints,
     DocumentColors,
+    SemanticTokens,
+    CodeLens,
+    FoldingRange,
+    CallHierarchy,
+    TypeHierarchy,
+    SelectionRange,
+    LinkedEditingRange,
+    DocumentLinks,
+    OnTypeFormatting,
+    InlineCompletion,
+    InlineValues,
 }
 
 impl Display for LanguageServerFeature {
@@ -299,6 +313,17 @@ fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
             RenameSymbol => "rename-symbol",
             InlayHints => "inlay-hints",
             DocumentColors => "document-colors",
+            SemanticTokens => "semantic-tokens",
+            CodeLens => "code-lens",
+            FoldingRange => "folding-range",
+            CallHierarchy => "call-hierarchy",
+            TypeHierarchy => "type-hierarchy",
+            SelectionRange => "selection-range",
+            LinkedEditingRange => "linked-editing-range",
+            DocumentLinks => "document-links",
+            OnTypeFormatting => "on-type-formatting",
+            InlineCompletion => "inline-completion",
+            InlineValues => "inline-values",
         };
         write!(f, "{feature}",)
     }
@@ -397,9 +422,29 @@ fn deserialize_required_root_patterns<'de, D>(deserializer: D) -> Result<Option<
     builder.build().map(Some).map_err(serde::de::Error::custom)
 }
 
+fn deserialize_language_servers<'de, D>(
+    deserializer: D,
+) -> Result<HashMap<String, LanguageServerConfiguration>, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    let language_servers =
+        HashMap::<String, LanguageServerConfiguration>::deserialize(deserializer)?;
+    for (name, config) in &language_servers {
+        if config.command.is_empty() && config.connect.is_none() {
+            return Err(serde::de::Error::custom(format!(
+                "language server '{name}' needs a `command` or a `connect` address"
+            )));
+        }
+    }
+    Ok(language_servers)
+}
+
 #[derive(Debug, Serialize, Deserialize)]
 #[serde(rename_all = "kebab-case")]
 pub struct LanguageServerConfiguration {
+    /// The language server binary, or an optional helper command when `connect` is set.
+    #[serde(default)]
     pub command: String,
     #[serde(default)]
     #[serde(skip_serializing_if = "Vec::is_empty")]
@@ -416,6 +461,28 @@ pub struct LanguageServerConfiguration {
         deserialize_with = "deserialize_required_root_patterns"
     )]
     pub required_root_patterns: Option<GlobSet>,
+    /// Connect to a language server listening on a socket instead of communicating with
+    /// `command` over stdio.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub connect: Option<LanguageServerConnection>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum LanguageServerConnection {
+    /// A `host:port` address.
+    Tcp(String),
+    /// The path to a Unix domain socket.
+    Unix(PathBuf),
+}
+
+impl Display for LanguageServerConnection {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::Tcp(address) => write!(f, "tcp://{address}"),
+            Self::Unix(path) => write!(f, "unix://{}", path.display()),
+        }
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -587,6 +654,19 @@ pub struct WordCompletion {
     pub trigger_length: Option<NonZeroU8>,
 }
 
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum SelectionRangeProvider {
+    /// Only use the tree-sitter syntax tree.
+    #[default]
+    TreeSitter,
+    /// Only use the `textDocument/selectionRange` request of a language server.
+    Lsp,
+    /// Use a language server if one supports `textDocument/selectionRange` and fall back to
+    /// the syntax tree otherwise.
+    LspFirst,
+}
+
 fn deserialize_regex<'de, D>(deserializer: D) -> Result<Option<rope::Regex>, D::Error>
 where
     D: serde::Deserializer<'de>,
@@ -279,6 +279,7 @@ pub struct TextAnnotations<'a> {
     inline_annotations: Vec<Layer<'a, InlineAnnotation, Option<Highlight>>>,
     overlays: Vec<Layer<'a, Overlay, Option<Highlight>>>,
     line_annotations: Vec<(Cell<usize>, RawBox<dyn LineAnnotation + 'a>)>,
+    folds: &'a [Range<usize>],
 }
 
 impl Debug for TextAnnotations<'_> {
@@ -286,6 +287,7 @@ fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         f.debug_struct("TextAnnotations")
             .field("inline_annotations", &self.inline_annotations)
             .field("overlays", &self.overlays)
+            .field("folds", &self.folds)
             .finish_non_exhaustive()
     }
 }
@@ -364,6 +366,43 @@ pub fn add_line_annotation(&mut self, layer: Box<dyn LineAnnotation + 'a>) -> &m
         self
     }
 
+    /// Set the folded (hidden) ranges of the document.
+    ///
+    /// The folds **must be sorted** and **must not overlap**. Each fold must
+    /// start at the beginning of a line (never the first line of the document)
+    /// and end at the beginning of a line or the end of the document. The
+    /// document formatter skips the folded text entirely so folded lines
+    /// collapse into the line before them.
+    pub fn set_folds(&mut self, folds: &'a [Range<usize>]) -> &mut Self {
+        self.folds = folds;
+        self
+    }
+
+    /// Returns whether any text is folded.
+    pub fn has_folds(&self) -> bool {
+        !self.folds.is_empty()
+    }
+
+    /// Returns the fold that hides `char_idx`, if any.
+    pub fn fold_containing(&self, char_idx: usize) -> Option<&Range<usize>> {
+        let idx = self.folds.partition_point(|fold| fold.start <= char_idx);
+        self.folds[..idx].last().filter(|fold| char_idx < fold.end)
+    }
+
+    /// Returns the fold that starts at `char_idx`, if any.
+    pub(crate) fn fold_at(&self, char_idx: usize) -> Option<&Range<usize>> {
+        self.folds
+            .binary_search_by_key(&char_idx, |fold| fold.start)
+            .ok()
+            .map(|idx| &self.folds[idx])
+    }
+
+    /// Moves the inline annotations and overlays past folded text that was skipped.
+    pub(crate) fn skip_fold(&self, fold_end: usize) {
+        reset_pos(&self.inline_annotations, fold_end, |annot| annot.char_idx);
+        reset_pos(&self.overlays, fold_end, |annot| annot.char_idx);
+    }
+
     /// Removes all line annotations, useful for vertical motions
     /// so that virtual text lines are automatically skipped.
     pub fn clear_line_annotations(&mut self) {
@@ -6,13 +6,16 @@
 
 /// A generic pointer to a file location.
 ///
-/// Currently this type only supports paths to local files.
+/// This is either a path to a local file or a URL with another scheme, for example `jdt://`,
+/// whose contents are provided by a language server.
 ///
 /// Cloning this type is cheap: the internal representation uses an Arc.
 #[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
 #[non_exhaustive]
 pub enum Uri {
     File(Arc<Path>),
+    /// A document that doesn't exist on disk and is read from a language server.
+    Virtual(Arc<url::Url>),
 }
 
 impl Uri {
@@ -21,14 +24,20 @@ impl Uri {
     pub fn to_url(&self) -> Result<url::Url, ()> {
         match self {
             Uri::File(path) => url::Url::from_file_path(path),
+            Uri::Virtual(url) => Ok(url.as_ref().clone()),
         }
     }
 
     pub fn as_path(&self) -> Option<&Path> {
         match self {
             Self::File(path) => Some(path),
+            Self::Virtual(_) => None,
         }
     }
+
+    pub fn is_virtual(&self) -> bool {
+        matches!(self, Self::Virtual(_))
+    }
 }
 
 impl From<PathBuf> for Uri {
@@ -41,6 +50,7 @@ impl fmt::Display for Uri {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
             Self::File(path) => write!(f, "{}", path.display()),
+            Self::Virtual(url) => write!(f, "{url}"),
         }
     }
 }
@@ -53,21 +63,12 @@ pub struct UrlConversionError {
 
 #[derive(Debug)]
 pub enum UrlConversionErrorKind {
-    UnsupportedScheme,
     UnableToConvert,
 }
 
 impl fmt::Display for UrlConversionError {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self.kind {
-            UrlConversionErrorKind::UnsupportedScheme => {
-                write!(
-                    f,
-                    "unsupported scheme '{}' in URL {}",
-                    self.source.scheme(),
-                    self.source
-                )
-            }
             UrlConversionErrorKind::UnableToConvert => {
                 write!(f, "unable to convert URL to file path: {}", self.source)
             }
@@ -83,7 +84,7 @@ fn convert_url_to_uri(url: &url::Url) -> Result<Uri, UrlConversionErrorKind> {
             .map(|path| Uri::File(helix_stdx::path::normalize(path).into()))
             .map_err(|_| UrlConversionErrorKind::UnableToConvert)
     } else {
-        Err(UrlConversionErrorKind::UnsupportedScheme)
+        Ok(Uri::Virtual(Arc::new(url.clone())))
     }
 }
 
@@ -114,12 +115,10 @@ mod test {
     #[test]
     fn unknown_scheme() {
         let url = Url::parse("csharp:/metadata/foo/bar/Baz.cs").unwrap();
-        assert!(matches!(
-            Uri::try_from(url),
-            Err(UrlConversionError {
-                kind: UrlConversionErrorKind::UnsupportedScheme,
-                ..
-            })
-        ));
+        let uri = Uri::try_from(&url).unwrap();
+        assert!(uri.is_virtual());
+        assert_eq!(uri.as_path(), None);
+        assert_eq!(uri.to_url(), Ok(url));
+        assert_eq!(uri.to_string(), "csharp:/metadata/foo/bar/Baz.cs");
     }
 }
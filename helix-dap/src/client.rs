@@ -16,7 +16,10 @@
     net::{IpAddr, Ipv4Addr, SocketAddr},
     path::PathBuf,
     process::Stdio,
-    sync::atomic::{AtomicU64, Ordering},
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc,
+    },
 };
 use tokio::{
     io::{AsyncBufRead, AsyncWrite, BufReader, BufWriter},
@@ -26,12 +29,118 @@
     time,
 };
 
+/// The values of the variables used on a source line of the active stack frame.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InlineValue {
+    /// The 0-indexed line the values are displayed after.
+    pub line: usize,
+    pub text: String,
+}
+
+/// Sends requests to a debugger. Unlike the [`Client`] it can be moved into a future, so that
+/// requests depending on the responses of other requests can be sent from a job.
+#[derive(Debug, Clone)]
+pub struct Requester {
+    server_tx: UnboundedSender<Payload>,
+    request_counter: Arc<AtomicU64>,
+}
+
+impl Requester {
+    fn next_request_id(&self) -> u64 {
+        self.request_counter.fetch_add(1, Ordering::Relaxed)
+    }
+
+    /// Execute a RPC request on the debugger.
+    pub fn call<R: crate::types::Request>(
+        &self,
+        arguments: R::Arguments,
+    ) -> impl Future<Output = Result<Value>>
+    where
+        R::Arguments: serde::Serialize,
+    {
+        let server_tx = self.server_tx.clone();
+        let id = self.next_request_id();
+
+        async move {
+            use std::time::Duration;
+            use tokio::time::timeout;
+
+            let arguments = Some(serde_json::to_value(arguments)?);
+
+            let (callback_tx, mut callback_rx) = channel(1);
+
+            let req = Request {
+                back_ch: Some(callback_tx),
+                seq: id,
+                command: R::COMMAND.to_string(),
+                arguments,
+            };
+
+            server_tx
+                .send(Payload::Request(req))
+                .map_err(|e| Error::Other(e.into()))?;
+
+            // TODO: specifiable timeout, delay other calls until initialize success
+            timeout(Duration::from_secs(20), callback_rx.recv())
+                .await
+                .map_err(|_| Error::Timeout(id))? // return Timeout
+                .ok_or(Error::StreamClosed)?
+                .map(|response| response.body.unwrap_or_default())
+            // TODO: check response.success
+        }
+    }
+
+    pub fn scopes(&self, frame_id: usize) -> impl Future<Output = Result<Vec<Scope>>> {
+        let args = requests::ScopesArguments { frame_id };
+
+        let call = self.call::<requests::Scopes>(args);
+        async move {
+            let response: requests::ScopesResponse = serde_json::from_value(call.await?)?;
+            Ok(response.scopes)
+        }
+    }
+
+    pub fn variables(
+        &self,
+        variables_reference: usize,
+    ) -> impl Future<Output = Result<Vec<Variable>>> {
+        let args = requests::VariablesArguments {
+            variables_reference,
+            filter: None,
+            start: None,
+            count: None,
+            format: None,
+        };
+
+        let call = self.call::<requests::Variables>(args);
+        async move {
+            let response: requests::VariablesResponse = serde_json::from_value(call.await?)?;
+            Ok(response.variables)
+        }
+    }
+
+    pub fn eval(
+        &self,
+        expression: String,
+        frame_id: Option<usize>,
+    ) -> impl Future<Output = Result<requests::EvaluateResponse>> {
+        let args = requests::EvaluateArguments {
+            expression,
+            frame_id,
+            context: None,
+            format: None,
+        };
+
+        let call = self.call::<requests::Evaluate>(args);
+        async move { Ok(serde_json::from_value(call.await?)?) }
+    }
+}
+
 #[derive(Debug)]
 pub struct Client {
     id: DebugAdapterId,
     _process: Option<Child>,
-    server_tx: UnboundedSender<Payload>,
-    request_counter: AtomicU64,
+    requester: Requester,
     connection_type: Option<ConnectionType>,
     starting_request_args: Option<Value>,
     /// The socket address of the debugger, if using TCP transport.
@@ -43,6 +152,8 @@ pub struct Client {
     pub thread_id: Option<ThreadId>,
     /// Currently active frame for the current thread.
     pub active_frame: Option<usize>,
+    /// Values displayed at the end of the source lines of the active frame.
+    pub inline_values: Vec<InlineValue>,
     pub quirks: DebuggerQuirks,
     /// The config which was used to start this debugger.
     pub config: Option<DebugAdapterConfig>,
@@ -81,8 +192,10 @@ pub fn streams(
         let client = Self {
             id,
             _process: process,
-            server_tx,
-            request_counter: AtomicU64::new(0),
+            requester: Requester {
+                server_tx,
+                request_counter: Arc::new(AtomicU64::new(0)),
+            },
             caps: None,
             connection_type: None,
             starting_request_args: None,
@@ -91,6 +204,7 @@ pub fn streams(
             thread_states: HashMap::new(),
             thread_id: None,
             active_frame: None,
+            inline_values: Vec::new(),
             quirks: DebuggerQuirks::default(),
             config: None,
         };
@@ -230,8 +344,9 @@ pub fn connection_type(&self) -> Option<ConnectionType> {
         self.connection_type
     }
 
-    fn next_request_id(&self) -> u64 {
-        self.request_counter.fetch_add(1, Ordering::Relaxed)
+    /// A handle sending requests to the debugger, see [`Requester`].
+    pub fn requester(&self) -> Requester {
+        self.requester.clone()
     }
 
     // Internal, called by specific DAP commands when resuming
@@ -242,6 +357,7 @@ pub fn resume_application(&mut self) {
         }
         self.active_frame = None;
         self.thread_id = None;
+        self.inline_values.clear();
     }
 
     /// Execute a RPC request on the debugger.
@@ -252,36 +368,7 @@ pub fn call<R: crate::types::Request>(
     where
         R::Arguments: serde::Serialize,
     {
-        let server_tx = self.server_tx.clone();
-        let id = self.next_request_id();
-
-        async move {
-            use std::time::Duration;
-            use tokio::time::timeout;
-
-            let arguments = Some(serde_json::to_value(arguments)?);
-
-            let (callback_tx, mut callback_rx) = channel(1);
-
-            let req = Request {
-                back_ch: Some(callback_tx),
-                seq: id,
-                command: R::COMMAND.to_string(),
-                arguments,
-            };
-
-            server_tx
-                .send(Payload::Request(req))
-                .map_err(|e| Error::Other(e.into()))?;
-
-            // TODO: specifiable timeout, delay other calls until initialize success
-            timeout(Duration::from_secs(20), callback_rx.recv())
-                .await
-                .map_err(|_| Error::Timeout(id))? // return Timeout
-                .ok_or(Error::StreamClosed)?
-                .map(|response| response.body.unwrap_or_default())
-            // TODO: check response.success
-        }
+        self.requester.call::<R>(arguments)
     }
 
     pub async fn request<R: crate::types::Request>(&self, params: R::Arguments) -> Result<R::Result>
@@ -301,7 +388,7 @@ pub fn reply(
         command: &str,
         result: core::result::Result<Value, Error>,
     ) -> impl Future<Output = Result<()>> {
-        let server_tx = self.server_tx.clone();
+        let server_tx = self.requester.server_tx.clone();
         let command = command.to_string();
 
         async move {
@@ -419,6 +506,56 @@ pub async fn set_breakpoints(
         Ok(response.breakpoints)
     }
 
+    pub async fn set_function_breakpoints(
+        &self,
+        breakpoints: Vec<FunctionBreakpoint>,
+    ) -> Result<Vec<Breakpoint>> {
+        let args = requests::SetFunctionBreakpointsArguments { breakpoints };
+
+        let response = self
+            .request::<requests::SetFunctionBreakpoints>(args)
+            .await?;
+        Ok(response.breakpoints)
+    }
+
+    /// Asks whether a data breakpoint can be set on the variable or expression `name`.
+    pub async fn data_breakpoint_info(
+        &self,
+        name: String,
+        variables_reference: Option<usize>,
+        frame_id: Option<usize>,
+    ) -> Result<requests::DataBreakpointInfoResponse> {
+        let args = requests::DataBreakpointInfoArguments {
+            variables_reference,
+            name,
+            frame_id,
+        };
+
+        self.request::<requests::DataBreakpointInfo>(args).await
+    }
+
+    pub async fn set_data_breakpoints(
+        &self,
+        breakpoints: Vec<DataBreakpoint>,
+    ) -> Result<Vec<Breakpoint>> {
+        let args = requests::SetDataBreakpointsArguments { breakpoints };
+
+        let response = self.request::<requests::SetDataBreakpoints>(args).await?;
+        Ok(response.breakpoints)
+    }
+
+    pub async fn set_instruction_breakpoints(
+        &self,
+        breakpoints: Vec<InstructionBreakpoint>,
+    ) -> Result<Vec<Breakpoint>> {
+        let args = requests::SetInstructionBreakpointsArguments { breakpoints };
+
+        let response = self
+            .request::<requests::SetInstructionBreakpoints>(args)
+            .await?;
+        Ok(response.breakpoints)
+    }
+
     pub async fn configuration_done(&self) -> Result<()> {
         self.request::<requests::ConfigurationDone>(()).await
     }
@@ -448,24 +585,49 @@ pub fn threads(&self) -> impl Future<Output = Result<Value>> {
         self.call::<requests::Threads>(())
     }
 
-    pub async fn scopes(&self, frame_id: usize) -> Result<Vec<Scope>> {
-        let args = requests::ScopesArguments { frame_id };
+    pub fn scopes(&self, frame_id: usize) -> impl Future<Output = Result<Vec<Scope>>> {
+        self.requester.scopes(frame_id)
+    }
 
-        let response = self.request::<requests::Scopes>(args).await?;
-        Ok(response.scopes)
+    pub fn variables(
+        &self,
+        variables_reference: usize,
+    ) -> impl Future<Output = Result<Vec<Variable>>> {
+        self.requester.variables(variables_reference)
     }
 
-    pub async fn variables(&self, variables_reference: usize) -> Result<Vec<Variable>> {
-        let args = requests::VariablesArguments {
+    pub fn set_variable(
+        &self,
+        variables_reference: usize,
+        name: String,
+        value: String,
+    ) -> impl Future<Output = Result<requests::SetVariableResponse>> {
+        let args = requests::SetVariableArguments {
             variables_reference,
-            filter: None,
-            start: None,
-            count: None,
+            name,
+            value,
             format: None,
         };
 
-        let response = self.request::<requests::Variables>(args).await?;
-        Ok(response.variables)
+        let call = self.call::<requests::SetVariable>(args);
+        async move { Ok(serde_json::from_value(call.await?)?) }
+    }
+
+    pub fn set_expression(
+        &self,
+        expression: String,
+        value: String,
+        frame_id: Option<usize>,
+    ) -> impl Future<Output = Result<requests::SetExpressionResponse>> {
+        let args = requests::SetExpressionArguments {
+            expression,
+            value,
+            frame_id,
+            format: None,
+        };
+
+        let call = self.call::<requests::SetExpression>(args);
+        async move { Ok(serde_json::from_value(call.await?)?) }
     }
 
     pub fn step_in(&self, thread_id: ThreadId) -> impl Future<Output = Result<Value>> {
@@ -502,19 +664,48 @@ pub fn pause(&self, thread_id: ThreadId) -> impl Future<Output = Result<Value>>
         self.call::<requests::Pause>(args)
     }
 
-    pub async fn eval(
+    pub fn eval(
         &self,
         expression: String,
         frame_id: Option<usize>,
-    ) -> Result<requests::EvaluateResponse> {
+    ) -> impl Future<Output = Result<requests::EvaluateResponse>> {
+        self.requester.eval(expression, frame_id)
+    }
+
+    /// Evaluates an expression entered in the debug console.
+    pub fn repl_eval(
+        &self,
+        expression: String,
+        frame_id: Option<usize>,
+    ) -> impl Future<Output = Result<Value>> {
         let args = requests::EvaluateArguments {
             expression,
             frame_id,
-            context: None,
+            context: Some("repl".to_string()),
             format: None,
         };
 
-        self.request::<requests::Evaluate>(args).await
+        self.call::<requests::Evaluate>(args)
+    }
+
+    pub fn completions(
+        &self,
+        text: String,
+        column: usize,
+        frame_id: Option<usize>,
+    ) -> impl Future<Output = Result<Vec<CompletionItem>>> {
+        let args = requests::CompletionsArguments {
+            frame_id,
+            text,
+            column,
+            line: None,
+        };
+
+        let call = self.call::<requests::Completions>(args);
+        async move {
+            let response: requests::CompletionsResponse = serde_json::from_value(call.await?)?;
+            Ok(response.targets)
+        }
     }
 
     pub fn set_exception_breakpoints(
@@ -0,0 +1,379 @@
+//! Debug configurations from the `.vscode/launch.json` file of a workspace.
+//!
+//! The file is JSON with comments and trailing commas. Configurations may refer to variables
+//! such as `${workspaceFolder}` or `${file}`, see [Variables].
+
+use std::io;
+use std::path::{Path, PathBuf};
+
+use anyhow::{anyhow, bail, Context};
+use helix_core::syntax::config::DebugAdapterConfig;
+use serde::Deserialize;
+use serde_json::{Map, Value};
+
+/// The debug adapters configured in `languages.toml` for the `type` of VS Code extensions
+/// with a different name.
+const ADAPTER_TYPES: &[(&str, &str)] = &[
+    ("lldb", "lldb-dap"),
+    ("cppdbg", "lldb-dap"),
+    ("coreclr", "netcoredbg"),
+    ("node", "js-debug-dap"),
+    ("pwa-node", "js-debug-dap"),
+    ("chrome", "js-debug-dap"),
+    ("pwa-chrome", "js-debug-dap"),
+    ("python", "debugpy"),
+];
+
+/// The key of the attributes which only apply to the current platform.
+const PLATFORM: &str = if cfg!(windows) {
+    "windows"
+} else if cfg!(target_os = "macos") {
+    "osx"
+} else {
+    "linux"
+};
+
+#[derive(Debug, Deserialize)]
+struct LaunchJson {
+    #[serde(default)]
+    configurations: Vec<Map<String, Value>>,
+}
+
+/// A configuration of `launch.json`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct LaunchConfiguration {
+    pub name: String,
+    /// The type of the debugger, named after the VS Code extension providing it.
+    pub ty: String,
+    /// Either `launch` or `attach`.
+    pub request: String,
+    /// The attributes of the configuration, which are sent as the arguments of the `launch` or
+    /// `attach` request.
+    pub args: Map<String, Value>,
+}
+
+impl LaunchConfiguration {
+    fn from_json(mut args: Map<String, Value>) -> anyhow::Result<Self> {
+        // Platform specific attributes override the others.
+        let overrides = args.remove(PLATFORM);
+        for platform in ["windows", "osx", "linux"] {
+            args.remove(platform);
+        }
+        if let Some(Value::Object(overrides)) = overrides {
+            args.extend(overrides);
+        }
+
+        let string = |key: &str| match args.get(key) {
+            Some(Value::String(value)) => Ok(value.clone()),
+            _ => Err(anyhow!("configuration is missing a '{key}'")),
+        };
+        Ok(Self {
+            name: string("name")?,
+            ty: string("type")?,
+            request: string("request")?,
+            args,
+        })
+    }
+
+    /// The tasks of the configuration, which helix doesn't run.
+    pub fn tasks(&self) -> impl Iterator<Item = (&'static str, &str)> {
+        ["preLaunchTask", "postDebugTask"]
+            .into_iter()
+            .filter_map(|key| Some((key, self.args.get(key)?.as_str()?)))
+    }
+
+    /// Finds the debug adapter for the `type` of the configuration among `adapters`: the one
+    /// with the same name or the one which replaces the VS Code extension.
+    pub fn adapter<'a>(
+        &self,
+        adapters: &[&'a DebugAdapterConfig],
+    ) -> Option<&'a DebugAdapterConfig> {
+        let alias = ADAPTER_TYPES
+            .iter()
+            .find(|(ty, _)| *ty == self.ty)
+            .map(|(_, name)| *name);
+        adapters
+            .iter()
+            .find(|adapter| adapter.name == self.ty)
+            .or_else(|| {
+                adapters
+                    .iter()
+                    .find(|adapter| Some(adapter.name.as_str()) == alias)
+            })
+            .copied()
+    }
+}
+
+/// The path of the `launch.json` file of `workspace`.
+pub fn path(workspace: &Path) -> PathBuf {
+    workspace.join(".vscode").join("launch.json")
+}
+
+/// Reads the configurations of the `launch.json` file of `workspace`. A workspace without the
+/// file has no configurations.
+pub fn load(workspace: &Path) -> anyhow::Result<Vec<LaunchConfiguration>> {
+    let path = path(workspace);
+    match std::fs::read_to_string(&path) {
+        Ok(text) => parse(&text).with_context(|| format!("failed to parse {}", path.display())),
+        Err(err) if err.kind() == io::ErrorKind::NotFound => Ok(Vec::new()),
+        Err(err) => Err(err).with_context(|| format!("failed to read {}", path.display())),
+    }
+}
+
+pub fn parse(text: &str) -> anyhow::Result<Vec<LaunchConfiguration>> {
+    let launch_json: LaunchJson =
+        serde_json::from_str(&strip_trailing_commas(&strip_comments(text)))?;
+    launch_json
+        .configurations
+        .into_iter()
+        .map(LaunchConfiguration::from_json)
+        .collect()
+}
+
+/// Replaces the comments of JSON with comments by whitespace.
+fn strip_comments(text: &str) -> String {
+    let mut output = String::with_capacity(text.len());
+    let mut chars = text.chars().peekable();
+    let mut in_string = false;
+    while let Some(c) = chars.next() {
+        if in_string {
+            output.push(c);
+            match c {
+                '\\' => output.extend(chars.next()),
+                '"' => in_string = false,
+                _ => (),
+            }
+            continue;
+        }
+        match (c, chars.peek()) {
+            ('"', _) => {
+                in_string = true;
+                output.push(c);
+            }
+            ('/', Some('/')) => {
+                for c in chars.by_ref() {
+                    if c == '\n' {
+                        output.push('\n');
+                        break;
+                    }
+                }
+            }
+            ('/', Some('*')) => {
+                chars.next();
+                let mut prev = None;
+                for c in chars.by_ref() {
+                    if prev == Some('*') && c == '/' {
+                        break;
+                    }
+                    // Keep the line numbers of parse errors.
+                    if c == '\n' {
+                        output.push('\n');
+                    }
+                    prev = Some(c);
+                }
+                output.push(' ');
+            }
+            _ => output.push(c),
+        }
+    }
+    output
+}
+
+/// Removes the commas after the last element of the arrays and objects of `text`, which must
+/// not contain comments.
+fn strip_trailing_commas(text: &str) -> String {
+    let mut output = String::with_capacity(text.len());
+    let mut in_string = false;
+    let mut chars = text.char_indices();
+    while let Some((i, c)) = chars.next() {
+        if in_string {
+            output.push(c);
+            match c {
+                '\\' => output.extend(chars.next().map(|(_, c)| c)),
+                '"' => in_string = false,
+                _ => (),
+            }
+            continue;
+        }
+        match c {
+            '"' => in_string = true,
+            ',' if matches!(text[i + 1..].trim_start().chars().next(), Some('}' | ']')) => continue,
+            _ => (),
+        }
+        output.push(c);
+    }
+    output
+}
+
+/// The values of the variables which configurations can refer to.
+#[derive(Debug, Default)]
+pub struct Variables {
+    pub workspace_folder: PathBuf,
+    pub cwd: PathBuf,
+    /// The path of the current document.
+    pub file: Option<PathBuf>,
+    /// The 1-based line of the cursor.
+    pub line_number: Option<usize>,
+    pub selected_text: Option<String>,
+}
+
+impl Variables {
+    fn file(&self, variable: &str) -> anyhow::Result<&Path> {
+        self.file
+            .as_deref()
+            .ok_or_else(|| anyhow!("'${{{variable}}}' requires a file to be open"))
+    }
+
+    fn resolve(&self, variable: &str) -> anyhow::Result<String> {
+        let path = |path: &Path| path.to_string_lossy().into_owned();
+        let relative = |path: &Path| {
+            path.strip_prefix(&self.workspace_folder)
+                .unwrap_or(path)
+                .to_path_buf()
+        };
+        let value = match variable {
+            "workspaceFolder" | "workspaceRoot" => path(&self.workspace_folder),
+            "workspaceFolderBasename" => path(Path::new(
+                self.workspace_folder.file_name().unwrap_or_default(),
+            )),
+            "cwd" => path(&self.cwd),
+            "file" => path(self.file(variable)?),
+            "relativeFile" => path(&relative(self.file(variable)?)),
+            "relativeFileDirname" => path(
+                relative(self.file(variable)?)
+                    .parent()
+                    .unwrap_or(Path::new("")),
+            ),
+            "fileBasename" => path(Path::new(
+                self.file(variable)?.file_name().unwrap_or_default(),
+            )),
+            "fileBasenameNoExtension" => path(Path::new(
+                self.file(variable)?.file_stem().unwrap_or_default(),
+            )),
+            "fileExtname" => match self.file(variable)?.extension() {
+                Some(extension) => format!(".{}", extension.to_string_lossy()),
+                None => String::new(),
+            },
+            "fileDirname" => path(self.file(variable)?.parent().unwrap_or(Path::new(""))),
+            "lineNumber" => self
+                .line_number
+                .ok_or_else(|| anyhow!("'${{lineNumber}}' requires a file to be open"))?
+                .to_string(),
+            "selectedText" => self.selected_text.clone().unwrap_or_default(),
+            "pathSeparator" | "/" => std::path::MAIN_SEPARATOR.to_string(),
+            "userHome" => path(&helix_stdx::path::home_dir()?),
+            _ => match variable.split_once(':') {
+                Some(("env", name)) => std::env::var(name).unwrap_or_default(),
+                _ => bail!("the variable '${{{variable}}}' is not supported"),
+            },
+        };
+        Ok(value)
+    }
+
+    /// Substitutes the variables in the strings of `value`, including the keys of objects.
+    pub fn substitute(&self, value: &mut Value) -> anyhow::Result<()> {
+        match value {
+            Value::String(string) => *string = self.substitute_str(string)?,
+            Value::Array(values) => {
+                for value in values {
+                    self.substitute(value)?;
+                }
+            }
+            Value::Object(map) => {
+                *map = std::mem::take(map)
+                    .into_iter()
+                    .map(|(key, mut value)| {
+                        self.substitute(&mut value)?;
+                        Ok((self.substitute_str(&key)?, value))
+                    })
+                    .collect::<anyhow::Result<_>>()?;
+            }
+            Value::Null | Value::Bool(_) | Value::Number(_) => (),
+        }
+        Ok(())
+    }
+
+    fn substitute_str(&self, mut string: &str) -> anyhow::Result<String> {
+        let mut output = String::with_capacity(string.len());
+        while let Some(start) = string.find("${") {
+            let Some(len) = string[start..].find('}') else {
+                break;
+            };
+            output.push_str(&string[..start]);
+            output.push_str(&self.resolve(&string[start + 2..start + len])?);
+            string = &string[start + len + 1..];
+        }
+        output.push_str(string);
+        Ok(output)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn parse_with_comments() {
+        let text = r#"{
+            // Use IntelliSense to learn about possible attributes.
+            "version": "0.2.0",
+            "configurations": [
+                {
+                    "name": "Debug // tests",
+                    "type": "lldb", /* the CodeLLDB extension */
+                    "request": "launch",
+                    "program": "${workspaceFolder}/target/debug/app",
+                    "args": ["--url", "http://localhost"],
+                    "linux": { "env": { "RUST_LOG": "debug" } },
+                    "windows": { "program": "app.exe" },
+                },
+            ],
+        }"#;
+        let configurations = parse(text).unwrap();
+        assert_eq!(configurations.len(), 1);
+        let configuration = &configurations[0];
+        assert_eq!(configuration.name, "Debug // tests");
+        assert_eq!(configuration.ty, "lldb");
+        assert_eq!(configuration.request, "launch");
+        assert_eq!(configuration.args["args"][1], "http://localhost");
+        assert!(!configuration.args.contains_key("linux"));
+        if cfg!(target_os = "linux") {
+            assert_eq!(configuration.args["env"]["RUST_LOG"], "debug");
+            assert_eq!(
+                configuration.args["program"],
+                "${workspaceFolder}/target/debug/app"
+            );
+        }
+
+        assert!(parse(r#"{ "configurations": [{ "name": "no type" }] }"#).is_err());
+    }
+
+    #[test]
+    fn substitute_variables() {
+        let variables = Variables {
+            workspace_folder: PathBuf::from("/work/app"),
+            cwd: PathBuf::from("/work/app"),
+            file: Some(PathBuf::from("/work/app/src/main.rs")),
+            line_number: Some(12),
+            selected_text: None,
+        };
+        let mut value = json!({
+            "program": "${workspaceFolder}/target/debug/${workspaceFolderBasename}",
+            "args": ["${relativeFile}:${lineNumber}", "${fileBasenameNoExtension}${fileExtname}"],
+            "cwd": "${fileDirname}",
+        });
+        variables.substitute(&mut value).unwrap();
+        assert_eq!(
+            value,
+            json!({
+                "program": "/work/app/target/debug/app",
+                "args": ["src/main.rs:12", "main.rs"],
+                "cwd": "/work/app/src",
+            })
+        );
+
+        let mut value = json!("${command:pickProcess}");
+        assert!(variables.substitute(&mut value).is_err());
+    }
+}
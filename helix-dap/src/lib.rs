@@ -1,9 +1,10 @@
 mod client;
+pub mod launch_json;
 pub mod registry;
 mod transport;
 mod types;
 
-pub use client::Client;
+pub use client::{Client, InlineValue, Requester};
 pub use transport::{Payload, Response, Transport};
 pub use types::*;
 
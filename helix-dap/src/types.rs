@@ -173,6 +173,71 @@ pub struct SourceBreakpoint {
     pub log_message: Option<String>,
 }
 
+#[derive(Debug, Default, PartialEq, Eq, Clone, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FunctionBreakpoint {
+    pub name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub condition: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub hit_condition: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum DataBreakpointAccessType {
+    Read,
+    Write,
+    ReadWrite,
+}
+
+impl std::fmt::Display for DataBreakpointAccessType {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            Self::Read => "read",
+            Self::Write => "write",
+            Self::ReadWrite => "readWrite",
+        })
+    }
+}
+
+impl std::str::FromStr for DataBreakpointAccessType {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "read" => Ok(Self::Read),
+            "write" => Ok(Self::Write),
+            "readWrite" => Ok(Self::ReadWrite),
+            _ => Err(format!("invalid data breakpoint access type '{s}'")),
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DataBreakpoint {
+    pub data_id: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub access_type: Option<DataBreakpointAccessType>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub condition: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub hit_condition: Option<String>,
+}
+
+#[derive(Debug, Default, PartialEq, Eq, Clone, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct InstructionBreakpoint {
+    pub instruction_reference: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub offset: Option<isize>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub condition: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub hit_condition: Option<String>,
+}
+
 #[derive(Debug, PartialEq, Eq, Clone, Deserialize, Serialize)]
 #[serde(rename_all = "camelCase")]
 pub struct Breakpoint {
@@ -332,6 +397,28 @@ pub struct Module {
     pub address_range: Option<String>,
 }
 
+#[derive(Debug, PartialEq, Eq, Clone, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CompletionItem {
+    pub label: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub text: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub sort_text: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub detail: Option<String>,
+    #[serde(rename = "type", skip_serializing_if = "Option::is_none")]
+    pub ty: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub start: Option<usize>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub length: Option<usize>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub selection_start: Option<usize>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub selection_length: Option<usize>,
+}
+
 fn from_number<'de, D>(deserializer: D) -> Result<String, D::Error>
 where
     D: Deserializer<'de>,
@@ -489,6 +576,99 @@ impl Request for SetBreakpoints {
         const COMMAND: &'static str = "setBreakpoints";
     }
 
+    #[derive(Debug, PartialEq, Eq, Clone, Deserialize, Serialize)]
+    #[serde(rename_all = "camelCase")]
+    pub struct SetFunctionBreakpointsArguments {
+        pub breakpoints: Vec<FunctionBreakpoint>,
+    }
+
+    #[derive(Debug, PartialEq, Eq, Clone, Deserialize, Serialize)]
+    #[serde(rename_all = "camelCase")]
+    pub struct SetFunctionBreakpointsResponse {
+        pub breakpoints: Vec<Breakpoint>,
+    }
+
+    #[derive(Debug)]
+    pub enum SetFunctionBreakpoints {}
+
+    impl Request for SetFunctionBreakpoints {
+        type Arguments = SetFunctionBreakpointsArguments;
+        type Result = SetFunctionBreakpointsResponse;
+        const COMMAND: &'static str = "setFunctionBreakpoints";
+    }
+
+    #[derive(Debug, PartialEq, Eq, Clone, Deserialize, Serialize)]
+    #[serde(rename_all = "camelCase")]
+    pub struct DataBreakpointInfoArguments {
+        #[serde(skip_serializing_if = "Option::is_none")]
+        pub variables_reference: Option<usize>,
+        pub name: String,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        pub frame_id: Option<usize>,
+    }
+
+    #[derive(Debug, PartialEq, Eq, Clone, Deserialize, Serialize)]
+    #[serde(rename_all = "camelCase")]
+    pub struct DataBreakpointInfoResponse {
+        pub data_id: Option<String>,
+        pub description: String,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        pub access_types: Option<Vec<DataBreakpointAccessType>>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        pub can_persist: Option<bool>,
+    }
+
+    #[derive(Debug)]
+    pub enum DataBreakpointInfo {}
+
+    impl Request for DataBreakpointInfo {
+        type Arguments = DataBreakpointInfoArguments;
+        type Result = DataBreakpointInfoResponse;
+        const COMMAND: &'static str = "dataBreakpointInfo";
+    }
+
+    #[derive(Debug, PartialEq, Eq, Clone, Deserialize, Serialize)]
+    #[serde(rename_all = "camelCase")]
+    pub struct SetDataBreakpointsArguments {
+        pub breakpoints: Vec<DataBreakpoint>,
+    }
+
+    #[derive(Debug, PartialEq, Eq, Clone, Deserialize, Serialize)]
+    #[serde(rename_all = "camelCase")]
+    pub struct SetDataBreakpointsResponse {
+        pub breakpoints: Vec<Breakpoint>,
+    }
+
+    #[derive(Debug)]
+    pub enum SetDataBreakpoints {}
+
+    impl Request for SetDataBreakpoints {
+        type Arguments = SetDataBreakpointsArguments;
+        type Result = SetDataBreakpointsResponse;
+        const COMMAND: &'static str = "setDataBreakpoints";
+    }
+
+    #[derive(Debug, PartialEq, Eq, Clone, Deserialize, Serialize)]
+    #[serde(rename_all = "camelCase")]
+    pub struct SetInstructionBreakpointsArguments {
+        pub breakpoints: Vec<InstructionBreakpoint>,
+    }
+
+    #[derive(Debug, PartialEq, Eq, Clone, Deserialize, Serialize)]
+    #[serde(rename_all = "camelCase")]
+    pub struct SetInstructionBreakpointsResponse {
+        pub breakpoints: Vec<Breakpoint>,
+    }
+
+    #[derive(Debug)]
+    pub enum SetInstructionBreakpoints {}
+
+    impl Request for SetInstructionBreakpoints {
+        type Arguments = SetInstructionBreakpointsArguments;
+        type Result = SetInstructionBreakpointsResponse;
+        const COMMAND: &'static str = "setInstructionBreakpoints";
+    }
+
     #[derive(Debug, Default, PartialEq, Eq, Clone, Deserialize, Serialize)]
     #[serde(rename_all = "camelCase")]
     pub struct ContinueArguments {
@@ -605,6 +785,79 @@ impl Request for Variables {
         const COMMAND: &'static str = "variables";
     }
 
+    #[derive(Debug, PartialEq, Eq, Clone, Deserialize, Serialize)]
+    #[serde(rename_all = "camelCase")]
+    pub struct SetVariableArguments {
+        pub variables_reference: usize,
+        pub name: String,
+        pub value: String,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        pub format: Option<ValueFormat>,
+    }
+
+    #[derive(Debug, PartialEq, Eq, Clone, Deserialize, Serialize)]
+    #[serde(rename_all = "camelCase")]
+    pub struct SetVariableResponse {
+        pub value: String,
+        #[serde(rename = "type", skip_serializing_if = "Option::is_none")]
+        pub ty: Option<String>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        pub variables_reference: Option<usize>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        pub named_variables: Option<usize>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        pub indexed_variables: Option<usize>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        pub memory_reference: Option<String>,
+    }
+
+    #[derive(Debug)]
+    pub enum SetVariable {}
+
+    impl Request for SetVariable {
+        type Arguments = SetVariableArguments;
+        type Result = SetVariableResponse;
+        const COMMAND: &'static str = "setVariable";
+    }
+
+    #[derive(Debug, PartialEq, Eq, Clone, Deserialize, Serialize)]
+    #[serde(rename_all = "camelCase")]
+    pub struct SetExpressionArguments {
+        pub expression: String,
+        pub value: String,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        pub frame_id: Option<usize>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        pub format: Option<ValueFormat>,
+    }
+
+    #[derive(Debug, PartialEq, Eq, Clone, Deserialize, Serialize)]
+    #[serde(rename_all = "camelCase")]
+    pub struct SetExpressionResponse {
+        pub value: String,
+        #[serde(rename = "type", skip_serializing_if = "Option::is_none")]
+        pub ty: Option<String>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        pub presentation_hint: Option<VariablePresentationHint>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        pub variables_reference: Option<usize>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        pub named_variables: Option<usize>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        pub indexed_variables: Option<usize>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        pub memory_reference: Option<String>,
+    }
+
+    #[derive(Debug)]
+    pub enum SetExpression {}
+
+    impl Request for SetExpression {
+        type Arguments = SetExpressionArguments;
+        type Result = SetExpressionResponse;
+        const COMMAND: &'static str = "setExpression";
+    }
+
     #[derive(Debug, PartialEq, Eq, Clone, Deserialize, Serialize)]
     #[serde(rename_all = "camelCase")]
     pub struct StepInArguments {
@@ -711,6 +964,32 @@ impl Request for Evaluate {
         const COMMAND: &'static str = "evaluate";
     }
 
+    #[derive(Debug, PartialEq, Eq, Clone, Deserialize, Serialize)]
+    #[serde(rename_all = "camelCase")]
+    pub struct CompletionsArguments {
+        #[serde(skip_serializing_if = "Option::is_none")]
+        pub frame_id: Option<usize>,
+        pub text: String,
+        pub column: usize,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        pub line: Option<usize>,
+    }
+
+    #[derive(Debug, PartialEq, Eq, Clone, Deserialize, Serialize)]
+    #[serde(rename_all = "camelCase")]
+    pub struct CompletionsResponse {
+        pub targets: Vec<CompletionItem>,
+    }
+
+    #[derive(Debug)]
+    pub enum Completions {}
+
+    impl Request for Completions {
+        type Arguments = CompletionsArguments;
+        type Result = CompletionsResponse;
+        const COMMAND: &'static str = "completions";
+    }
+
     #[derive(Debug, PartialEq, Eq, Clone, Deserialize, Serialize)]
     #[serde(rename_all = "camelCase")]
     pub struct SetExceptionBreakpointsArguments {
@@ -1042,3 +1321,14 @@ fn test_deserialize_module_id_from_string() {
     let module: Module = serde_json::from_str(raw).expect("Error!");
     assert_eq!(module.id, "0");
 }
+
+#[test]
+fn test_data_breakpoint_access_type() {
+    let access_type: DataBreakpointAccessType = "readWrite".parse().expect("Error!");
+    assert_eq!(access_type, DataBreakpointAccessType::ReadWrite);
+    assert_eq!(
+        serde_json::to_value(access_type).expect("Error!"),
+        serde_json::json!("readWrite")
+    );
+    assert!("readwrite".parse::<DataBreakpointAccessType>().is_err());
+}
@@ -179,6 +179,11 @@ fn try_from(value: &str) -> Result<Self, Self::Error> {
 mod signature_help;
 pub use signature_help::*;
 
+#[cfg(feature = "proposed")]
+mod text_document_content;
+#[cfg(feature = "proposed")]
+pub use text_document_content::*;
+
 mod type_hierarchy;
 pub use type_hierarchy::*;
 
@@ -1363,6 +1368,13 @@ pub struct WorkspaceClientCapabilities {
     /// since 3.17.0
     #[serde(skip_serializing_if = "Option::is_none")]
     pub diagnostic: Option<DiagnosticWorkspaceClientCapabilities>,
+
+    /// Capabilities specific to the `workspace/textDocumentContent` request.
+    ///
+    /// @since 3.18.0
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[cfg(feature = "proposed")]
+    pub text_document_content: Option<TextDocumentContentClientCapabilities>,
 }
 
 #[derive(Debug, Eq, PartialEq, Clone, Default, Deserialize, Serialize)]
@@ -2024,6 +2036,12 @@ pub struct ServerCapabilities {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub call_hierarchy_provider: Option<CallHierarchyServerCapability>,
 
+    /// Type hierarchy provider capabilities.
+    ///
+    /// @since 3.17.0
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub type_hierarchy_provider: Option<TypeHierarchyServerCapabilities>,
+
     /// Semantic tokens server capabilities.
     #[serde(skip_serializing_if = "Option::is_none")]
     pub semantic_tokens_provider: Option<SemanticTokensServerCapabilities>,
@@ -2077,6 +2095,14 @@ pub struct WorkspaceServerCapabilities {
 
     #[serde(skip_serializing_if = "Option::is_none")]
     pub file_operations: Option<WorkspaceFileOperationsServerCapabilities>,
+
+    /// The server supports the `workspace/textDocumentContent` request.
+    ///
+    /// @since 3.18.0
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[cfg(feature = "proposed")]
+    pub text_document_content:
+        Option<OneOf<TextDocumentContentOptions, TextDocumentContentRegistrationOptions>>,
 }
 
 /// General parameters to to register for a capability.
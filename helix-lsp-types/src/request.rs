@@ -671,6 +671,21 @@ impl Request for InlineCompletionRequest {
     const METHOD: &'static str = "textDocument/inlineCompletion";
 }
 
+/// The `workspace/textDocumentContent` request is sent from the client to the server to request
+/// the content of a text document, e.g. for a URI with a scheme that is not `file`.
+///
+/// @since 3.18.0
+#[derive(Debug)]
+#[cfg(feature = "proposed")]
+pub enum TextDocumentContentRequest {}
+
+#[cfg(feature = "proposed")]
+impl Request for TextDocumentContentRequest {
+    type Params = TextDocumentContentParams;
+    type Result = TextDocumentContentResult;
+    const METHOD: &'static str = "workspace/textDocumentContent";
+}
+
 /// The workspace/workspaceFolders request is sent from the server to the client to fetch the current open list of
 /// workspace folders. Returns null in the response if only a single file is open in the tool.
 /// Returns an empty array if a workspace is open but no folders are configured.
@@ -871,7 +886,7 @@ pub enum InlineValueRequest {}
 
 impl Request for InlineValueRequest {
     type Params = InlineValueParams;
-    type Result = Option<InlineValue>;
+    type Result = Option<Vec<InlineValue>>;
     const METHOD: &'static str = "textDocument/inlineValue";
 }
 
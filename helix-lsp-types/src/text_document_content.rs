@@ -0,0 +1,59 @@
+use serde::{Deserialize, Serialize};
+
+use crate::{StaticRegistrationOptions, Url};
+
+/// Client capabilities for a text document content provider.
+///
+/// @since 3.18.0
+#[derive(Debug, Eq, PartialEq, Clone, Default, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TextDocumentContentClientCapabilities {
+    /// Text document content provider supports dynamic registration.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub dynamic_registration: Option<bool>,
+}
+
+/// Text document content provider options.
+///
+/// @since 3.18.0
+#[derive(Debug, Eq, PartialEq, Clone, Default, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TextDocumentContentOptions {
+    /// The schemes for which the server provides content.
+    pub schemes: Vec<String>,
+}
+
+/// Parameters for the `workspace/textDocumentContent` request.
+///
+/// @since 3.18.0
+#[derive(Debug, Eq, PartialEq, Clone, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TextDocumentContentParams {
+    /// The uri of the text document.
+    pub uri: Url,
+}
+
+/// Result of the `workspace/textDocumentContent` request.
+///
+/// @since 3.18.0
+#[derive(Debug, Eq, PartialEq, Clone, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TextDocumentContentResult {
+    /// The text content of the text document. Please note, that the content of any subsequent
+    /// open notifications for the text document might differ from the returned content due to
+    /// whitespace and line ending normalizations done on the client.
+    pub text: String,
+}
+
+/// Text document content provider registration options.
+///
+/// @since 3.18.0
+#[derive(Debug, Eq, PartialEq, Clone, Default, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TextDocumentContentRegistrationOptions {
+    #[serde(flatten)]
+    pub text_document_content_options: TextDocumentContentOptions,
+
+    #[serde(flatten)]
+    pub static_registration_options: StaticRegistrationOptions,
+}
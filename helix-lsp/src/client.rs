@@ -1,7 +1,7 @@
 use crate::{
     file_operations::FileOperationsInterest,
     find_lsp_workspace, jsonrpc,
-    transport::{Payload, Transport},
+    transport::{Payload, TrafficLog, Transport},
     Call, Error, LanguageServerId, OffsetEncoding, Result,
 };
 
@@ -10,7 +10,12 @@
     DidChangeWorkspaceFoldersParams, OneOf, PositionEncodingKind, SignatureHelp, Url,
     WorkspaceFolder, WorkspaceFoldersChangeEvent,
 };
-use helix_core::{find_workspace, syntax::config::LanguageServerFeature, ChangeSet, Rope};
+use futures_util::future::{BoxFuture, FutureExt};
+use helix_core::{
+    find_workspace,
+    syntax::config::{LanguageServerConnection, LanguageServerFeature},
+    ChangeSet, Rope,
+};
 use helix_loader::VERSION_AND_GIT_HASH;
 use helix_stdx::path;
 use parking_lot::Mutex;
@@ -25,9 +30,10 @@
     },
 };
 use std::{future::Future, sync::OnceLock};
-use std::{path::Path, process::Stdio};
+use std::{path::Path, process::Stdio, time::Duration};
 use tokio::{
-    io::{BufReader, BufWriter},
+    io::{AsyncBufRead, AsyncRead, AsyncWrite, BufReader, BufWriter, DuplexStream},
+    net::TcpStream,
     process::{Child, Command},
     sync::{
         mpsc::{channel, UnboundedReceiver, UnboundedSender},
@@ -35,6 +41,81 @@
     },
 };
 
+trait Socket: AsyncRead + AsyncWrite + Unpin + Send {}
+
+impl<T: AsyncRead + AsyncWrite + Unpin + Send> Socket for T {}
+
+async fn open_socket(connection: &LanguageServerConnection) -> std::io::Result<Box<dyn Socket>> {
+    match connection {
+        LanguageServerConnection::Tcp(address) => Ok(Box::new(TcpStream::connect(address).await?)),
+        #[cfg(unix)]
+        LanguageServerConnection::Unix(path) => {
+            Ok(Box::new(tokio::net::UnixStream::connect(path).await?))
+        }
+        #[cfg(not(unix))]
+        LanguageServerConnection::Unix(_) => Err(std::io::Error::new(
+            std::io::ErrorKind::Unsupported,
+            "Unix sockets are not supported on this platform",
+        )),
+    }
+}
+
+/// Connects to a language server listening on `connection` and forwards `stream` to it.
+///
+/// When `retry_timeout` is set the connection is retried until the timeout elapses, giving a
+/// helper command time to start listening. `stream` is dropped if the connection fails which
+/// closes the transport like an exited language server process.
+async fn connect_socket(
+    name: String,
+    connection: LanguageServerConnection,
+    retry_timeout: Option<Duration>,
+    mut stream: DuplexStream,
+) {
+    let deadline = retry_timeout.map(|timeout| tokio::time::Instant::now() + timeout);
+    let mut socket = loop {
+        match open_socket(&connection).await {
+            Ok(socket) => break socket,
+            Err(err) if deadline.is_some_and(|deadline| tokio::time::Instant::now() < deadline) => {
+                log::debug!("{name}: retrying connection to {connection}: {err}");
+                tokio::time::sleep(Duration::from_millis(100)).await;
+            }
+            Err(err) => {
+                log::error!("{name}: failed to connect to {connection}: {err}");
+                return;
+            }
+        }
+    };
+    log::info!("{name}: connected to {connection}");
+    if let Err(err) = tokio::io::copy_bidirectional(&mut stream, &mut socket).await {
+        log::error!("{name}: connection to {connection} closed: {err}");
+    }
+}
+
+/// `java/classFileContents`: the decompiled source of a `jdt://` URI provided by jdtls.
+enum ClassFileContents {}
+
+impl lsp::request::Request for ClassFileContents {
+    type Params = lsp::TextDocumentIdentifier;
+    type Result = Option<String>;
+    const METHOD: &'static str = "java/classFileContents";
+}
+
+#[derive(Debug, Deserialize, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+struct VirtualTextDocumentParams {
+    text_document: lsp::TextDocumentIdentifier,
+}
+
+/// `deno/virtualTextDocument`: the contents of a `deno:/` URI provided by the deno language
+/// server.
+enum VirtualTextDocument {}
+
+impl lsp::request::Request for VirtualTextDocument {
+    type Params = VirtualTextDocumentParams;
+    type Result = Option<String>;
+    const METHOD: &'static str = "deno/virtualTextDocument";
+}
+
 fn workspace_for_uri(uri: lsp::Url) -> WorkspaceFolder {
     lsp::WorkspaceFolder {
         name: uri
@@ -46,11 +127,72 @@ fn workspace_for_uri(uri: lsp::Url) -> WorkspaceFolder {
     }
 }
 
+/// The URI of a file or directory in the params of `workspace/*Files` requests and notifications.
+fn file_operation_uri(path: &Path, is_dir: bool) -> Option<String> {
+    let url = if is_dir {
+        Url::from_directory_path(path)
+    } else {
+        Url::from_file_path(path)
+    };
+    Some(url.ok()?.to_string())
+}
+
+/// Semantic token types advertised to servers. Servers may still send any type in their legend.
+const SEMANTIC_TOKEN_TYPES: &[lsp::SemanticTokenType] = &[
+    lsp::SemanticTokenType::NAMESPACE,
+    lsp::SemanticTokenType::TYPE,
+    lsp::SemanticTokenType::CLASS,
+    lsp::SemanticTokenType::ENUM,
+    lsp::SemanticTokenType::INTERFACE,
+    lsp::SemanticTokenType::STRUCT,
+    lsp::SemanticTokenType::TYPE_PARAMETER,
+    lsp::SemanticTokenType::PARAMETER,
+    lsp::SemanticTokenType::VARIABLE,
+    lsp::SemanticTokenType::PROPERTY,
+    lsp::SemanticTokenType::ENUM_MEMBER,
+    lsp::SemanticTokenType::EVENT,
+    lsp::SemanticTokenType::FUNCTION,
+    lsp::SemanticTokenType::METHOD,
+    lsp::SemanticTokenType::MACRO,
+    lsp::SemanticTokenType::KEYWORD,
+    lsp::SemanticTokenType::MODIFIER,
+    lsp::SemanticTokenType::COMMENT,
+    lsp::SemanticTokenType::STRING,
+    lsp::SemanticTokenType::NUMBER,
+    lsp::SemanticTokenType::REGEXP,
+    lsp::SemanticTokenType::OPERATOR,
+    lsp::SemanticTokenType::DECORATOR,
+];
+
+const SEMANTIC_TOKEN_MODIFIERS: &[lsp::SemanticTokenModifier] = &[
+    lsp::SemanticTokenModifier::DECLARATION,
+    lsp::SemanticTokenModifier::DEFINITION,
+    lsp::SemanticTokenModifier::READONLY,
+    lsp::SemanticTokenModifier::STATIC,
+    lsp::SemanticTokenModifier::DEPRECATED,
+    lsp::SemanticTokenModifier::ABSTRACT,
+    lsp::SemanticTokenModifier::ASYNC,
+    lsp::SemanticTokenModifier::MODIFICATION,
+    lsp::SemanticTokenModifier::DOCUMENTATION,
+    lsp::SemanticTokenModifier::DEFAULT_LIBRARY,
+];
+
+fn semantic_tokens_options(
+    capabilities: &lsp::ServerCapabilities,
+) -> Option<&lsp::SemanticTokensOptions> {
+    match capabilities.semantic_tokens_provider.as_ref()? {
+        lsp::SemanticTokensServerCapabilities::SemanticTokensOptions(options) => Some(options),
+        lsp::SemanticTokensServerCapabilities::SemanticTokensRegistrationOptions(options) => {
+            Some(&options.semantic_tokens_options)
+        }
+    }
+}
+
 #[derive(Debug)]
 pub struct Client {
     id: LanguageServerId,
     name: String,
-    _process: Child,
+    _process: Option<Child>,
     server_tx: UnboundedSender<Payload>,
     request_counter: AtomicU64,
     pub(crate) capabilities: OnceCell<lsp::ServerCapabilities>,
@@ -62,6 +204,7 @@ pub struct Client {
     initialize_notify: Arc<Notify>,
     /// workspace folders added while the server is still initializing
     req_timeout: u64,
+    traffic: Arc<TrafficLog>,
 }
 
 impl Client {
@@ -205,6 +348,7 @@ pub fn start(
         args: &[String],
         config: Option<Value>,
         server_environment: impl IntoIterator<Item = (impl AsRef<OsStr>, impl AsRef<OsStr>)>,
+        connection: Option<&LanguageServerConnection>,
         root_path: PathBuf,
         root_uri: Option<lsp::Url>,
         id: LanguageServerId,
@@ -215,29 +359,70 @@ pub fn start(
         UnboundedReceiver<(LanguageServerId, Call)>,
         Arc<Notify>,
     )> {
-        // Resolve path to the binary
-        let cmd = helix_stdx::env::which(cmd)?;
-
-        let process = Command::new(cmd)
-            .envs(server_environment)
-            .args(args)
-            .stdin(Stdio::piped())
-            .stdout(Stdio::piped())
-            .stderr(Stdio::piped())
-            .current_dir(&root_path)
-            // make sure the process is reaped on drop
-            .kill_on_drop(true)
-            .spawn();
-
-        let mut process = process?;
+        let traffic = Arc::new(TrafficLog::default());
+        let (process, reader, writer, stderr): (
+            _,
+            Box<dyn AsyncBufRead + Unpin + Send>,
+            Box<dyn AsyncWrite + Unpin + Send>,
+            Option<Box<dyn AsyncBufRead + Unpin + Send>>,
+        ) = match connection {
+            None => {
+                let mut process = Self::spawn(cmd, args, server_environment, &root_path, true)?;
+
+                // TODO: do we need bufreader/writer here? or do we use async wrappers on unblock?
+                let writer = BufWriter::new(process.stdin.take().expect("Failed to open stdin"));
+                let reader = BufReader::new(process.stdout.take().expect("Failed to open stdout"));
+                let stderr = BufReader::new(process.stderr.take().expect("Failed to open stderr"));
+                (
+                    Some(process),
+                    Box::new(reader),
+                    Box::new(writer),
+                    Some(Box::new(stderr)),
+                )
+            }
+            Some(connection) => {
+                // The helper command is optional: the server may already be listening.
+                let mut process = if cmd.is_empty() {
+                    None
+                } else {
+                    Some(Self::spawn(
+                        cmd,
+                        args,
+                        server_environment,
+                        &root_path,
+                        false,
+                    )?)
+                };
+                let stderr = process
+                    .as_mut()
+                    .and_then(|process| process.stderr.take())
+                    .map(|stderr| {
+                        Box::new(BufReader::new(stderr)) as Box<dyn AsyncBufRead + Unpin + Send>
+                    });
 
-        // TODO: do we need bufreader/writer here? or do we use async wrappers on unblock?
-        let writer = BufWriter::new(process.stdin.take().expect("Failed to open stdin"));
-        let reader = BufReader::new(process.stdout.take().expect("Failed to open stdout"));
-        let stderr = BufReader::new(process.stderr.take().expect("Failed to open stderr"));
+                // Connecting may take a while if the helper needs to start listening first so
+                // the transport talks to an in-memory pipe that is connected to the socket in
+                // the background.
+                let (stream, socket_stream) = tokio::io::duplex(64 * 1024);
+                let retry_timeout = process.is_some().then(|| Duration::from_secs(req_timeout));
+                tokio::spawn(connect_socket(
+                    name.clone(),
+                    connection.clone(),
+                    retry_timeout,
+                    socket_stream,
+                ));
+                let (reader, writer) = tokio::io::split(stream);
+                (
+                    process,
+                    Box::new(BufReader::new(reader)),
+                    Box::new(BufWriter::new(writer)),
+                    stderr,
+                )
+            }
+        };
 
         let (server_rx, server_tx, initialize_notify) =
-            Transport::start(reader, writer, stderr, id, name.clone());
+            Transport::start(reader, writer, stderr, id, name.clone(), traffic.clone());
 
         let workspace_folders = root_uri
             .clone()
@@ -258,11 +443,38 @@ pub fn start(
             root_uri,
             workspace_folders: Mutex::new(workspace_folders),
             initialize_notify: initialize_notify.clone(),
+            traffic,
         };
 
         Ok((client, server_rx, initialize_notify))
     }
 
+    /// Spawns the language server, or the helper command of a socket connection when `stdio`
+    /// is false.
+    fn spawn(
+        cmd: &str,
+        args: &[String],
+        server_environment: impl IntoIterator<Item = (impl AsRef<OsStr>, impl AsRef<OsStr>)>,
+        root_path: &Path,
+        stdio: bool,
+    ) -> Result<Child> {
+        // Resolve path to the binary
+        let cmd = helix_stdx::env::which(cmd)?;
+
+        let process = Command::new(cmd)
+            .envs(server_environment)
+            .args(args)
+            .stdin(if stdio { Stdio::piped() } else { Stdio::null() })
+            .stdout(if stdio { Stdio::piped() } else { Stdio::null() })
+            .stderr(Stdio::piped())
+            .current_dir(root_path)
+            // make sure the process is reaped on drop
+            .kill_on_drop(true)
+            .spawn()?;
+
+        Ok(process)
+    }
+
     pub fn name(&self) -> &str {
         &self.name
     }
@@ -271,6 +483,15 @@ pub fn id(&self) -> LanguageServerId {
         self.id
     }
 
+    pub fn root_path(&self) -> &Path {
+        &self.root_path
+    }
+
+    /// The latest JSON-RPC messages exchanged with the server.
+    pub fn traffic(&self) -> &TrafficLog {
+        &self.traffic
+    }
+
     fn next_request_id(&self) -> jsonrpc::Id {
         let id = self.request_counter.fetch_add(1, Ordering::Relaxed);
         jsonrpc::Id::Num(id)
@@ -389,6 +610,61 @@ pub fn supports_feature(&self, feature: LanguageServerFeature) -> bool {
                         | ColorProviderCapability::Options(_)
                 )
             ),
+            LanguageServerFeature::SemanticTokens => {
+                capabilities.semantic_tokens_provider.is_some()
+            }
+            LanguageServerFeature::CodeLens => capabilities.code_lens_provider.is_some(),
+            LanguageServerFeature::FoldingRange => matches!(
+                capabilities.folding_range_provider,
+                Some(
+                    FoldingRangeProviderCapability::Simple(true)
+                        | FoldingRangeProviderCapability::FoldingProvider(_)
+                        | FoldingRangeProviderCapability::Options(_)
+                )
+            ),
+            LanguageServerFeature::CallHierarchy => matches!(
+                capabilities.call_hierarchy_provider,
+                Some(
+                    CallHierarchyServerCapability::Simple(true)
+                        | CallHierarchyServerCapability::Options(_)
+                )
+            ),
+            LanguageServerFeature::TypeHierarchy => matches!(
+                capabilities.type_hierarchy_provider,
+                Some(
+                    TypeHierarchyServerCapabilities::Simple(true)
+                        | TypeHierarchyServerCapabilities::Options(_)
+                        | TypeHierarchyServerCapabilities::RegistrationOptions(_)
+                )
+            ),
+            LanguageServerFeature::SelectionRange => matches!(
+                capabilities.selection_range_provider,
+                Some(
+                    SelectionRangeProviderCapability::Simple(true)
+                        | SelectionRangeProviderCapability::Options(_)
+                        | SelectionRangeProviderCapability::RegistrationOptions(_)
+                )
+            ),
+            LanguageServerFeature::LinkedEditingRange => matches!(
+                capabilities.linked_editing_range_provider,
+                Some(
+                    LinkedEditingRangeServerCapabilities::Simple(true)
+                        | LinkedEditingRangeServerCapabilities::Options(_)
+                        | LinkedEditingRangeServerCapabilities::RegistrationOptions(_)
+                )
+            ),
+            LanguageServerFeature::DocumentLinks => capabilities.document_link_provider.is_some(),
+            LanguageServerFeature::OnTypeFormatting => {
+                capabilities.document_on_type_formatting_provider.is_some()
+            }
+            LanguageServerFeature::InlineCompletion => matches!(
+                capabilities.inline_completion_provider,
+                Some(OneOf::Left(true) | OneOf::Right(_))
+            ),
+            LanguageServerFeature::InlineValues => matches!(
+                capabilities.inline_value_provider,
+                Some(OneOf::Left(true) | OneOf::Right(_))
+            ),
         }
     }
 
@@ -583,6 +859,12 @@ pub(crate) async fn initialize(&self, enable_snippets: bool) -> Result<lsp::Init
                     inlay_hint: Some(lsp::InlayHintWorkspaceClientCapabilities {
                         refresh_support: Some(false),
                     }),
+                    semantic_tokens: Some(lsp::SemanticTokensWorkspaceClientCapabilities {
+                        refresh_support: Some(true),
+                    }),
+                    code_lens: Some(lsp::CodeLensWorkspaceClientCapabilities {
+                        refresh_support: Some(true),
+                    }),
                     workspace_edit: Some(lsp::WorkspaceEditClientCapabilities {
                         document_changes: Some(true),
                         resource_operations: Some(vec![
@@ -599,13 +881,20 @@ pub(crate) async fn initialize(&self, enable_snippets: bool) -> Result<lsp::Init
                         relative_pattern_support: Some(false),
                     }),
                     file_operations: Some(lsp::WorkspaceFileOperationsClientCapabilities {
+                        will_create: Some(true),
+                        did_create: Some(true),
                         will_rename: Some(true),
                         did_rename: Some(true),
+                        will_delete: Some(true),
+                        did_delete: Some(true),
                         ..Default::default()
                     }),
                     diagnostic: Some(lsp::DiagnosticWorkspaceClientCapabilities {
                         refresh_support: Some(true),
                     }),
+                    text_document_content: Some(lsp::TextDocumentContentClientCapabilities {
+                        dynamic_registration: Some(false),
+                    }),
                     ..Default::default()
                 }),
                 text_document: Some(lsp::TextDocumentClientCapabilities {
@@ -657,6 +946,9 @@ pub(crate) async fn initialize(&self, enable_snippets: bool) -> Result<lsp::Init
                     formatting: Some(lsp::DocumentFormattingClientCapabilities {
                         dynamic_registration: Some(false),
                     }),
+                    on_type_formatting: Some(lsp::DocumentOnTypeFormattingClientCapabilities {
+                        dynamic_registration: Some(false),
+                    }),
                     code_action: Some(lsp::CodeActionClientCapabilities {
                         code_action_literal_support: Some(lsp::CodeActionLiteralSupport {
                             code_action_kind: lsp::CodeActionKindLiteralSupport {
@@ -701,6 +993,50 @@ pub(crate) async fn initialize(&self, enable_snippets: bool) -> Result<lsp::Init
                         dynamic_registration: Some(false),
                         resolve_support: None,
                     }),
+                    code_lens: Some(lsp::CodeLensClientCapabilities {
+                        dynamic_registration: Some(false),
+                    }),
+                    folding_range: Some(lsp::FoldingRangeClientCapabilities {
+                        dynamic_registration: Some(false),
+                        line_folding_only: Some(true),
+                        ..Default::default()
+                    }),
+                    call_hierarchy: Some(lsp::CallHierarchyClientCapabilities {
+                        dynamic_registration: Some(false),
+                    }),
+                    type_hierarchy: Some(lsp::TypeHierarchyClientCapabilities {
+                        dynamic_registration: Some(false),
+                    }),
+                    selection_range: Some(lsp::SelectionRangeClientCapabilities {
+                        dynamic_registration: Some(false),
+                    }),
+                    linked_editing_range: Some(lsp::LinkedEditingRangeClientCapabilities {
+                        dynamic_registration: Some(false),
+                    }),
+                    document_link: Some(lsp::DocumentLinkClientCapabilities {
+                        dynamic_registration: Some(false),
+                        tooltip_support: Some(false),
+                    }),
+                    inline_completion: Some(lsp::InlineCompletionClientCapabilities {
+                        dynamic_registration: Some(false),
+                    }),
+                    inline_value: Some(lsp::InlineValueClientCapabilities {
+                        dynamic_registration: Some(false),
+                    }),
+                    semantic_tokens: Some(lsp::SemanticTokensClientCapabilities {
+                        dynamic_registration: Some(false),
+                        requests: lsp::SemanticTokensClientCapabilitiesRequests {
+                            range: Some(true),
+                            full: Some(lsp::SemanticTokensFullOptions::Delta { delta: Some(true) }),
+                        },
+                        token_types: SEMANTIC_TOKEN_TYPES.to_vec(),
+                        token_modifiers: SEMANTIC_TOKEN_MODIFIERS.to_vec(),
+                        formats: vec![lsp::TokenFormat::RELATIVE],
+                        overlapping_token_support: Some(false),
+                        multiline_token_support: Some(false),
+                        server_cancel_support: Some(true),
+                        augments_syntax_tokens: Some(true),
+                    }),
                     ..Default::default()
                 }),
                 window: Some(lsp::WindowClientCapabilities {
@@ -737,6 +1073,13 @@ pub fn exit(&self) {
         self.notify::<lsp::notification::Exit>(())
     }
 
+    /// Sets the verbosity of the `$/logTrace` notifications sent by the server. The messages
+    /// exchanged with the server are recorded in its [`TrafficLog`] unless tracing is off.
+    pub fn set_trace(&self, value: lsp::TraceValue) {
+        self.traffic.set_enabled(value != lsp::TraceValue::Off);
+        self.notify::<lsp::notification::SetTrace>(lsp::SetTraceParams { value })
+    }
+
     /// Tries to shut down the language server but returns
     /// early if server responds with an error.
     pub async fn shutdown_and_exit(&self) -> Result<()> {
@@ -780,17 +1123,9 @@ pub fn will_rename(
         if !capabilities.will_rename.has_interest(old_path, is_dir) {
             return None;
         }
-        let url_from_path = |path| {
-            let url = if is_dir {
-                Url::from_directory_path(path)
-            } else {
-                Url::from_file_path(path)
-            };
-            Some(url.ok()?.to_string())
-        };
         let files = vec![lsp::FileRename {
-            old_uri: url_from_path(old_path)?,
-            new_uri: url_from_path(new_path)?,
+            old_uri: file_operation_uri(old_path, is_dir)?,
+            new_uri: file_operation_uri(new_path, is_dir)?,
         }];
         Some(self.call_with_timeout::<lsp::request::WillRenameFiles>(
             &lsp::RenameFilesParams { files },
@@ -803,23 +1138,74 @@ pub fn did_rename(&self, old_path: &Path, new_path: &Path, is_dir: bool) -> Opti
         if !capabilities.did_rename.has_interest(new_path, is_dir) {
             return None;
         }
-        let url_from_path = |path| {
-            let url = if is_dir {
-                Url::from_directory_path(path)
-            } else {
-                Url::from_file_path(path)
-            };
-            Some(url.ok()?.to_string())
-        };
-
         let files = vec![lsp::FileRename {
-            old_uri: url_from_path(old_path)?,
-            new_uri: url_from_path(new_path)?,
+            old_uri: file_operation_uri(old_path, is_dir)?,
+            new_uri: file_operation_uri(new_path, is_dir)?,
         }];
         self.notify::<lsp::notification::DidRenameFiles>(lsp::RenameFilesParams { files });
         Some(())
     }
 
+    pub fn will_create(
+        &self,
+        path: &Path,
+        is_dir: bool,
+    ) -> Option<impl Future<Output = Result<Option<lsp::WorkspaceEdit>>>> {
+        let capabilities = self.file_operations_intests();
+        if !capabilities.will_create.has_interest(path, is_dir) {
+            return None;
+        }
+        let files = vec![lsp::FileCreate {
+            uri: file_operation_uri(path, is_dir)?,
+        }];
+        Some(self.call_with_timeout::<lsp::request::WillCreateFiles>(
+            &lsp::CreateFilesParams { files },
+            5,
+        ))
+    }
+
+    pub fn did_create(&self, path: &Path, is_dir: bool) -> Option<()> {
+        let capabilities = self.file_operations_intests();
+        if !capabilities.did_create.has_interest(path, is_dir) {
+            return None;
+        }
+        let files = vec![lsp::FileCreate {
+            uri: file_operation_uri(path, is_dir)?,
+        }];
+        self.notify::<lsp::notification::DidCreateFiles>(lsp::CreateFilesParams { files });
+        Some(())
+    }
+
+    pub fn will_delete(
+        &self,
+        path: &Path,
+        is_dir: bool,
+    ) -> Option<impl Future<Output = Result<Option<lsp::WorkspaceEdit>>>> {
+        let capabilities = self.file_operations_intests();
+        if !capabilities.will_delete.has_interest(path, is_dir) {
+            return None;
+        }
+        let files = vec![lsp::FileDelete {
+            uri: file_operation_uri(path, is_dir)?,
+        }];
+        Some(self.call_with_timeout::<lsp::request::WillDeleteFiles>(
+            &lsp::DeleteFilesParams { files },
+            5,
+        ))
+    }
+
+    pub fn did_delete(&self, path: &Path, is_dir: bool) -> Option<()> {
+        let capabilities = self.file_operations_intests();
+        if !capabilities.did_delete.has_interest(path, is_dir) {
+            return None;
+        }
+        let files = vec![lsp::FileDelete {
+            uri: file_operation_uri(path, is_dir)?,
+        }];
+        self.notify::<lsp::notification::DidDeleteFiles>(lsp::DeleteFilesParams { files });
+        Some(())
+    }
+
     // -------------------------------------------------------------------------------------------
     // Text document
     // -------------------------------------------------------------------------------------------
@@ -1061,6 +1447,60 @@ pub fn completion(
         Some(self.call::<lsp::request::Completion>(params))
     }
 
+    pub fn inline_completion(
+        &self,
+        text_document: lsp::TextDocumentIdentifier,
+        position: lsp::Position,
+        trigger_kind: lsp::InlineCompletionTriggerKind,
+        work_done_token: Option<lsp::ProgressToken>,
+    ) -> Option<impl Future<Output = Result<Option<lsp::InlineCompletionResponse>>>> {
+        let capabilities = self.capabilities.get().unwrap();
+
+        // Return early if the server does not support inline completion.
+        match capabilities.inline_completion_provider {
+            Some(OneOf::Left(true) | OneOf::Right(_)) => (),
+            _ => return None,
+        }
+
+        let params = lsp::InlineCompletionParams {
+            work_done_progress_params: lsp::WorkDoneProgressParams { work_done_token },
+            text_document_position: lsp::TextDocumentPositionParams {
+                text_document,
+                position,
+            },
+            context: lsp::InlineCompletionContext {
+                trigger_kind,
+                selected_completion_info: None,
+            },
+        };
+
+        Some(self.call::<lsp::request::InlineCompletionRequest>(params))
+    }
+
+    pub fn text_document_inline_value(
+        &self,
+        text_document: lsp::TextDocumentIdentifier,
+        range: lsp::Range,
+        context: lsp::InlineValueContext,
+    ) -> Option<impl Future<Output = Result<Option<Vec<lsp::InlineValue>>>>> {
+        let capabilities = self.capabilities.get().unwrap();
+
+        // Return early if the server does not support inline values.
+        match capabilities.inline_value_provider {
+            Some(OneOf::Left(true) | OneOf::Right(_)) => (),
+            _ => return None,
+        }
+
+        let params = lsp::InlineValueParams {
+            work_done_progress_params: lsp::WorkDoneProgressParams::default(),
+            text_document,
+            range,
+            context,
+        };
+
+        Some(self.call::<lsp::request::InlineValueRequest>(params))
+    }
+
     pub fn resolve_completion_item(
         &self,
         completion_item: &lsp::CompletionItem,
@@ -1086,6 +1526,105 @@ pub fn resolve_code_action(
         Some(self.call_with_ref::<lsp::request::CodeActionResolveRequest>(code_action))
     }
 
+    pub fn code_lens(
+        &self,
+        text_document: lsp::TextDocumentIdentifier,
+        work_done_token: Option<lsp::ProgressToken>,
+    ) -> Option<impl Future<Output = Result<Option<Vec<lsp::CodeLens>>>>> {
+        let capabilities = self.capabilities.get().unwrap();
+
+        // Return early if the server does not support code lenses.
+        capabilities.code_lens_provider.as_ref()?;
+
+        let params = lsp::CodeLensParams {
+            text_document,
+            work_done_progress_params: lsp::WorkDoneProgressParams { work_done_token },
+            partial_result_params: lsp::PartialResultParams::default(),
+        };
+
+        Some(self.call::<lsp::request::CodeLensRequest>(params))
+    }
+
+    pub fn resolve_code_lens(
+        &self,
+        code_lens: &lsp::CodeLens,
+    ) -> Option<impl Future<Output = Result<lsp::CodeLens>>> {
+        let capabilities = self.capabilities.get().unwrap();
+
+        // Return early if the server does not support resolving code lenses.
+        match capabilities.code_lens_provider {
+            Some(lsp::CodeLensOptions {
+                resolve_provider: Some(true),
+            }) => (),
+            _ => return None,
+        }
+
+        Some(self.call_with_ref::<lsp::request::CodeLensResolve>(code_lens))
+    }
+
+    pub fn text_document_folding_range(
+        &self,
+        text_document: lsp::TextDocumentIdentifier,
+        work_done_token: Option<lsp::ProgressToken>,
+    ) -> Option<impl Future<Output = Result<Option<Vec<lsp::FoldingRange>>>>> {
+        let capabilities = self.capabilities.get().unwrap();
+
+        // Return early if the server does not support folding ranges.
+        match capabilities.folding_range_provider {
+            Some(
+                lsp::FoldingRangeProviderCapability::Simple(true)
+                | lsp::FoldingRangeProviderCapability::FoldingProvider(_)
+                | lsp::FoldingRangeProviderCapability::Options(_),
+            ) => (),
+            _ => return None,
+        }
+
+        let params = lsp::FoldingRangeParams {
+            text_document,
+            work_done_progress_params: lsp::WorkDoneProgressParams { work_done_token },
+            partial_result_params: lsp::PartialResultParams::default(),
+        };
+
+        Some(self.call::<lsp::request::FoldingRangeRequest>(params))
+    }
+
+    pub fn document_link(
+        &self,
+        text_document: lsp::TextDocumentIdentifier,
+        work_done_token: Option<lsp::ProgressToken>,
+    ) -> Option<impl Future<Output = Result<Option<Vec<lsp::DocumentLink>>>>> {
+        let capabilities = self.capabilities.get().unwrap();
+
+        // Return early if the server does not support document links.
+        capabilities.document_link_provider.as_ref()?;
+
+        let params = lsp::DocumentLinkParams {
+            text_document,
+            work_done_progress_params: lsp::WorkDoneProgressParams { work_done_token },
+            partial_result_params: lsp::PartialResultParams::default(),
+        };
+
+        Some(self.call::<lsp::request::DocumentLinkRequest>(params))
+    }
+
+    pub fn resolve_document_link(
+        &self,
+        document_link: &lsp::DocumentLink,
+    ) -> Option<impl Future<Output = Result<lsp::DocumentLink>>> {
+        let capabilities = self.capabilities.get().unwrap();
+
+        // Return early if the server does not support resolving document links.
+        match capabilities.document_link_provider {
+            Some(lsp::DocumentLinkOptions {
+                resolve_provider: Some(true),
+                ..
+            }) => (),
+            _ => return None,
+        }
+
+        Some(self.call_with_ref::<lsp::request::DocumentLinkResolve>(document_link))
+    }
+
     pub fn text_document_signature_help(
         &self,
         text_document: lsp::TextDocumentIdentifier,
@@ -1154,6 +1693,83 @@ pub fn text_document_document_color(
         Some(self.call::<lsp::request::DocumentColor>(params))
     }
 
+    pub fn text_document_semantic_tokens_full(
+        &self,
+        text_document: lsp::TextDocumentIdentifier,
+        work_done_token: Option<lsp::ProgressToken>,
+    ) -> Option<impl Future<Output = Result<Option<lsp::SemanticTokensResult>>>> {
+        let capabilities = self.capabilities.get().unwrap();
+
+        // Return early if the server does not support full document semantic tokens.
+        match semantic_tokens_options(capabilities)?.full {
+            Some(
+                lsp::SemanticTokensFullOptions::Bool(true)
+                | lsp::SemanticTokensFullOptions::Delta { .. },
+            ) => (),
+            _ => return None,
+        }
+
+        let params = lsp::SemanticTokensParams {
+            text_document,
+            work_done_progress_params: lsp::WorkDoneProgressParams { work_done_token },
+            partial_result_params: lsp::PartialResultParams::default(),
+        };
+
+        Some(self.call::<lsp::request::SemanticTokensFullRequest>(params))
+    }
+
+    pub fn text_document_semantic_tokens_full_delta(
+        &self,
+        text_document: lsp::TextDocumentIdentifier,
+        previous_result_id: String,
+        work_done_token: Option<lsp::ProgressToken>,
+    ) -> Option<impl Future<Output = Result<Option<lsp::SemanticTokensFullDeltaResult>>>> {
+        let capabilities = self.capabilities.get().unwrap();
+
+        // Return early if the server does not support semantic token deltas.
+        match semantic_tokens_options(capabilities)?.full {
+            Some(lsp::SemanticTokensFullOptions::Delta { delta: Some(true) }) => (),
+            _ => return None,
+        }
+
+        let params = lsp::SemanticTokensDeltaParams {
+            text_document,
+            previous_result_id,
+            work_done_progress_params: lsp::WorkDoneProgressParams { work_done_token },
+            partial_result_params: lsp::PartialResultParams::default(),
+        };
+
+        Some(self.call::<lsp::request::SemanticTokensFullDeltaRequest>(params))
+    }
+
+    pub fn text_document_semantic_tokens_range(
+        &self,
+        text_document: lsp::TextDocumentIdentifier,
+        range: lsp::Range,
+        work_done_token: Option<lsp::ProgressToken>,
+    ) -> Option<impl Future<Output = Result<Option<lsp::SemanticTokensRangeResult>>>> {
+        let capabilities = self.capabilities.get().unwrap();
+
+        // Return early if the server does not support range semantic tokens.
+        if semantic_tokens_options(capabilities)?.range != Some(true) {
+            return None;
+        }
+
+        let params = lsp::SemanticTokensRangeParams {
+            text_document,
+            range,
+            work_done_progress_params: lsp::WorkDoneProgressParams { work_done_token },
+            partial_result_params: lsp::PartialResultParams::default(),
+        };
+
+        Some(self.call::<lsp::request::SemanticTokensRangeRequest>(params))
+    }
+
+    /// The legend the server uses to encode semantic token types and modifiers.
+    pub fn semantic_tokens_legend(&self) -> Option<&lsp::SemanticTokensLegend> {
+        semantic_tokens_options(self.capabilities.get()?).map(|options| &options.legend)
+    }
+
     pub fn text_document_hover(
         &self,
         text_document: lsp::TextDocumentIdentifier,
@@ -1237,6 +1853,42 @@ pub fn text_document_range_formatting(
         Some(self.call::<lsp::request::RangeFormatting>(params))
     }
 
+    pub fn text_document_on_type_formatting(
+        &self,
+        text_document: lsp::TextDocumentIdentifier,
+        position: lsp::Position,
+        ch: String,
+        options: lsp::FormattingOptions,
+    ) -> Option<impl Future<Output = Result<Option<Vec<lsp::TextEdit>>>>> {
+        let capabilities = self.capabilities.get().unwrap();
+
+        // Return early if the server does not support on-type formatting or the character
+        // is not one of its trigger characters.
+        let provider = capabilities.document_on_type_formatting_provider.as_ref()?;
+        if provider.first_trigger_character != ch
+            && !provider
+                .more_trigger_character
+                .iter()
+                .flatten()
+                .any(|trigger| *trigger == ch)
+        {
+            return None;
+        }
+
+        let options = self.get_merged_formatting_options(options);
+
+        let params = lsp::DocumentOnTypeFormattingParams {
+            text_document_position: lsp::TextDocumentPositionParams {
+                text_document,
+                position,
+            },
+            ch,
+            options,
+        };
+
+        Some(self.call::<lsp::request::OnTypeFormatting>(params))
+    }
+
     pub fn text_document_diagnostic(
         &self,
         text_document: lsp::TextDocumentIdentifier,
@@ -1263,6 +1915,65 @@ pub fn text_document_diagnostic(
         Some(self.call::<lsp::request::DocumentDiagnosticRequest>(params))
     }
 
+    /// Whether the server supports pulling the diagnostics of the whole workspace.
+    pub fn supports_workspace_diagnostics(&self) -> bool {
+        self.capabilities()
+            .diagnostic_provider
+            .as_ref()
+            .is_some_and(|diagnostic_provider| match diagnostic_provider {
+                lsp::DiagnosticServerCapabilities::Options(options) => {
+                    options.workspace_diagnostics
+                }
+                lsp::DiagnosticServerCapabilities::RegistrationOptions(options) => {
+                    options.diagnostic_options.workspace_diagnostics
+                }
+            })
+    }
+
+    /// Requests the diagnostics of all documents in the workspace.
+    ///
+    /// Servers may keep the request open until the diagnostics change, so it does not time out
+    /// as quickly as other requests. Reports are streamed through `$/progress` notifications
+    /// with `partial_result_token`.
+    pub fn workspace_diagnostic(
+        &self,
+        previous_result_ids: Vec<lsp::PreviousResultId>,
+        partial_result_token: Option<lsp::ProgressToken>,
+    ) -> Option<impl Future<Output = Result<lsp::WorkspaceDiagnosticReportResult>>> {
+        const WORKSPACE_DIAGNOSTIC_TIMEOUT_SECS: u64 = 60 * 60;
+
+        let capabilities = self.capabilities();
+
+        // Return early if the server does not support workspace pull diagnostics.
+        let identifier = match capabilities.diagnostic_provider.as_ref()? {
+            lsp::DiagnosticServerCapabilities::Options(cap) if cap.workspace_diagnostics => {
+                cap.identifier.clone()
+            }
+            lsp::DiagnosticServerCapabilities::RegistrationOptions(cap)
+                if cap.diagnostic_options.workspace_diagnostics =>
+            {
+                cap.diagnostic_options.identifier.clone()
+            }
+            _ => return None,
+        };
+
+        let params = lsp::WorkspaceDiagnosticParams {
+            identifier: identifier.map(|identifier| identifier.to_string()),
+            previous_result_ids,
+            work_done_progress_params: lsp::WorkDoneProgressParams::default(),
+            partial_result_params: lsp::PartialResultParams {
+                partial_result_token,
+            },
+        };
+
+        Some(
+            self.call_with_timeout::<lsp::request::WorkspaceDiagnosticRequest>(
+                &params,
+                WORKSPACE_DIAGNOSTIC_TIMEOUT_SECS,
+            ),
+        )
+    }
+
     pub fn text_document_document_highlight(
         &self,
         text_document: lsp::TextDocumentIdentifier,
@@ -1442,6 +2153,218 @@ pub fn goto_reference(
         Some(self.call::<lsp::request::References>(params))
     }
 
+    pub fn prepare_call_hierarchy(
+        &self,
+        text_document: lsp::TextDocumentIdentifier,
+        position: lsp::Position,
+        work_done_token: Option<lsp::ProgressToken>,
+    ) -> Option<impl Future<Output = Result<Option<Vec<lsp::CallHierarchyItem>>>>> {
+        let capabilities = self.capabilities.get().unwrap();
+
+        // Return early if the server does not support call hierarchies.
+        match capabilities.call_hierarchy_provider {
+            Some(
+                lsp::CallHierarchyServerCapability::Simple(true)
+                | lsp::CallHierarchyServerCapability::Options(_),
+            ) => (),
+            _ => return None,
+        }
+
+        let params = lsp::CallHierarchyPrepareParams {
+            text_document_position_params: lsp::TextDocumentPositionParams {
+                text_document,
+                position,
+            },
+            work_done_progress_params: lsp::WorkDoneProgressParams { work_done_token },
+        };
+
+        Some(self.call::<lsp::request::CallHierarchyPrepare>(params))
+    }
+
+    pub fn call_hierarchy_incoming_calls(
+        &self,
+        item: lsp::CallHierarchyItem,
+    ) -> Option<impl Future<Output = Result<Option<Vec<lsp::CallHierarchyIncomingCall>>>>> {
+        let capabilities = self.capabilities.get().unwrap();
+
+        // Return early if the server does not support call hierarchies.
+        match capabilities.call_hierarchy_provider {
+            Some(
+                lsp::CallHierarchyServerCapability::Simple(true)
+                | lsp::CallHierarchyServerCapability::Options(_),
+            ) => (),
+            _ => return None,
+        }
+
+        let params = lsp::CallHierarchyIncomingCallsParams {
+            item,
+            work_done_progress_params: lsp::WorkDoneProgressParams::default(),
+            partial_result_params: lsp::PartialResultParams::default(),
+        };
+
+        Some(self.call::<lsp::request::CallHierarchyIncomingCalls>(params))
+    }
+
+    pub fn call_hierarchy_outgoing_calls(
+        &self,
+        item: lsp::CallHierarchyItem,
+    ) -> Option<impl Future<Output = Result<Option<Vec<lsp::CallHierarchyOutgoingCall>>>>> {
+        let capabilities = self.capabilities.get().unwrap();
+
+        // Return early if the server does not support call hierarchies.
+        match capabilities.call_hierarchy_provider {
+            Some(
+                lsp::CallHierarchyServerCapability::Simple(true)
+                | lsp::CallHierarchyServerCapability::Options(_),
+            ) => (),
+            _ => return None,
+        }
+
+        let params = lsp::CallHierarchyOutgoingCallsParams {
+            item,
+            work_done_progress_params: lsp::WorkDoneProgressParams::default(),
+            partial_result_params: lsp::PartialResultParams::default(),
+        };
+
+        Some(self.call::<lsp::request::CallHierarchyOutgoingCalls>(params))
+    }
+
+    pub fn prepare_type_hierarchy(
+        &self,
+        text_document: lsp::TextDocumentIdentifier,
+        position: lsp::Position,
+        work_done_token: Option<lsp::ProgressToken>,
+    ) -> Option<impl Future<Output = Result<Option<Vec<lsp::TypeHierarchyItem>>>>> {
+        let capabilities = self.capabilities.get().unwrap();
+
+        // Return early if the server does not support type hierarchies.
+        match capabilities.type_hierarchy_provider {
+            Some(
+                lsp::TypeHierarchyServerCapabilities::Simple(true)
+                | lsp::TypeHierarchyServerCapabilities::Options(_)
+                | lsp::TypeHierarchyServerCapabilities::RegistrationOptions(_),
+            ) => (),
+            _ => return None,
+        }
+
+        let params = lsp::TypeHierarchyPrepareParams {
+            text_document_position_params: lsp::TextDocumentPositionParams {
+                text_document,
+                position,
+            },
+            work_done_progress_params: lsp::WorkDoneProgressParams { work_done_token },
+        };
+
+        Some(self.call::<lsp::request::TypeHierarchyPrepare>(params))
+    }
+
+    pub fn type_hierarchy_supertypes(
+        &self,
+        item: lsp::TypeHierarchyItem,
+    ) -> Option<impl Future<Output = Result<Option<Vec<lsp::TypeHierarchyItem>>>>> {
+        let capabilities = self.capabilities.get().unwrap();
+
+        // Return early if the server does not support type hierarchies.
+        match capabilities.type_hierarchy_provider {
+            Some(
+                lsp::TypeHierarchyServerCapabilities::Simple(true)
+                | lsp::TypeHierarchyServerCapabilities::Options(_)
+                | lsp::TypeHierarchyServerCapabilities::RegistrationOptions(_),
+            ) => (),
+            _ => return None,
+        }
+
+        let params = lsp::TypeHierarchySupertypesParams {
+            item,
+            work_done_progress_params: lsp::WorkDoneProgressParams::default(),
+            partial_result_params: lsp::PartialResultParams::default(),
+        };
+
+        Some(self.call::<lsp::request::TypeHierarchySupertypes>(params))
+    }
+
+    pub fn type_hierarchy_subtypes(
+        &self,
+        item: lsp::TypeHierarchyItem,
+    ) -> Option<impl Future<Output = Result<Option<Vec<lsp::TypeHierarchyItem>>>>> {
+        let capabilities = self.capabilities.get().unwrap();
+
+        // Return early if the server does not support type hierarchies.
+        match capabilities.type_hierarchy_provider {
+            Some(
+                lsp::TypeHierarchyServerCapabilities::Simple(true)
+                | lsp::TypeHierarchyServerCapabilities::Options(_)
+                | lsp::TypeHierarchyServerCapabilities::RegistrationOptions(_),
+            ) => (),
+            _ => return None,
+        }
+
+        let params = lsp::TypeHierarchySubtypesParams {
+            item,
+            work_done_progress_params: lsp::WorkDoneProgressParams::default(),
+            partial_result_params: lsp::PartialResultParams::default(),
+        };
+
+        Some(self.call::<lsp::request::TypeHierarchySubtypes>(params))
+    }
+
+    pub fn text_document_selection_range(
+        &self,
+        text_document: lsp::TextDocumentIdentifier,
+        positions: Vec<lsp::Position>,
+        work_done_token: Option<lsp::ProgressToken>,
+    ) -> Option<impl Future<Output = Result<Option<Vec<lsp::SelectionRange>>>>> {
+        let capabilities = self.capabilities.get().unwrap();
+
+        // Return early if the server does not support selection ranges.
+        match capabilities.selection_range_provider {
+            Some(
+                lsp::SelectionRangeProviderCapability::Simple(true)
+                | lsp::SelectionRangeProviderCapability::Options(_)
+                | lsp::SelectionRangeProviderCapability::RegistrationOptions(_),
+            ) => (),
+            _ => return None,
+        }
+
+        let params = lsp::SelectionRangeParams {
+            text_document,
+            positions,
+            work_done_progress_params: lsp::WorkDoneProgressParams { work_done_token },
+            partial_result_params: lsp::PartialResultParams::default(),
+        };
+
+        Some(self.call::<lsp::request::SelectionRangeRequest>(params))
+    }
+
+    pub fn linked_editing_range(
+        &self,
+        text_document: lsp::TextDocumentIdentifier,
+        position: lsp::Position,
+        work_done_token: Option<lsp::ProgressToken>,
+    ) -> Option<impl Future<Output = Result<Option<lsp::LinkedEditingRanges>>>> {
+        let capabilities = self.capabilities.get().unwrap();
+
+        // Return early if the server does not support linked editing ranges.
+        match capabilities.linked_editing_range_provider {
+            Some(
+                lsp::LinkedEditingRangeServerCapabilities::Simple(true)
+                | lsp::LinkedEditingRangeServerCapabilities::Options(_)
+                | lsp::LinkedEditingRangeServerCapabilities::RegistrationOptions(_),
+            ) => (),
+            _ => return None,
+        }
+
+        let params = lsp::LinkedEditingRangeParams {
+            text_document_position_params: lsp::TextDocumentPositionParams {
+                text_document,
+                position,
+            },
+            work_done_progress_params: lsp::WorkDoneProgressParams { work_done_token },
+        };
+
+        Some(self.call::<lsp::request::LinkedEditingRange>(params))
+    }
+
     pub fn document_symbols(
         &self,
         text_document: lsp::TextDocumentIdentifier,
@@ -1580,9 +2503,87 @@ pub fn command(
         Some(self.call::<lsp::request::ExecuteCommand>(params))
     }
 
+    /// Requests the contents of a document that doesn't exist on disk, e.g. `jdt://...`.
+    ///
+    /// Uses `workspace/textDocumentContent` if the server supports it for the URI's scheme and
+    /// falls back to the server-specific requests of jdtls and deno. Returns `None` if the
+    /// server can't provide the contents.
+    pub fn text_document_content(
+        &self,
+        uri: lsp::Url,
+    ) -> Option<BoxFuture<'static, Result<String>>> {
+        let capabilities = self.capabilities.get()?;
+        let supports_scheme = capabilities
+            .workspace
+            .as_ref()
+            .and_then(|workspace| workspace.text_document_content.as_ref())
+            .is_some_and(|provider| {
+                let options = match provider {
+                    OneOf::Left(options) => options,
+                    OneOf::Right(options) => &options.text_document_content_options,
+                };
+                options.schemes.iter().any(|scheme| scheme == uri.scheme())
+            });
+        if supports_scheme {
+            let request = self.call::<lsp::request::TextDocumentContentRequest>(
+                lsp::TextDocumentContentParams { uri },
+            );
+            return Some(async move { Ok(request.await?.text) }.boxed());
+        }
+
+        let request = match uri.scheme() {
+            "jdt" => self
+                .call::<ClassFileContents>(lsp::TextDocumentIdentifier { uri })
+                .boxed(),
+            "deno" => self
+                .call::<VirtualTextDocument>(VirtualTextDocumentParams {
+                    text_document: lsp::TextDocumentIdentifier { uri },
+                })
+                .boxed(),
+            _ => return None,
+        };
+        Some(
+            async move {
+                request
+                    .await?
+                    .ok_or_else(|| anyhow::anyhow!("no content for virtual document").into())
+            }
+            .boxed(),
+        )
+    }
+
     pub fn did_change_watched_files(&self, changes: Vec<lsp::FileEvent>) {
         self.notify::<lsp::notification::DidChangeWatchedFiles>(lsp::DidChangeWatchedFilesParams {
             changes,
         })
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+    #[tokio::test]
+    async fn forward_tcp_connection() {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let address = listener.local_addr().unwrap().to_string();
+        let (mut stream, socket_stream) = tokio::io::duplex(64);
+        tokio::spawn(connect_socket(
+            "test".to_string(),
+            LanguageServerConnection::Tcp(address),
+            None,
+            socket_stream,
+        ));
+
+        let (mut socket, _) = listener.accept().await.unwrap();
+        stream.write_all(b"ping").await.unwrap();
+        let mut buf = [0; 4];
+        socket.read_exact(&mut buf).await.unwrap();
+        assert_eq!(&buf, b"ping");
+
+        socket.write_all(b"pong").await.unwrap();
+        stream.read_exact(&mut buf).await.unwrap();
+        assert_eq!(&buf, b"pong");
+    }
+}
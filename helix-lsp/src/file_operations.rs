@@ -79,13 +79,12 @@ pub(crate) fn has_interest(&self, path: &Path, is_dir: bool) -> bool {
 
 #[derive(Default, Debug)]
 pub(crate) struct FileOperationsInterest {
-    // TODO: support other notifications
-    // did_create: FileOperationFilter,
-    // will_create: FileOperationFilter,
+    pub did_create: FileOperationFilter,
+    pub will_create: FileOperationFilter,
     pub did_rename: FileOperationFilter,
     pub will_rename: FileOperationFilter,
-    // did_delete: FileOperationFilter,
-    // will_delete: FileOperationFilter,
+    pub did_delete: FileOperationFilter,
+    pub will_delete: FileOperationFilter,
 }
 
 impl FileOperationsInterest {
@@ -98,8 +97,12 @@ pub fn new(capabilities: &lsp::ServerCapabilities) -> FileOperationsInterest {
             return FileOperationsInterest::default();
         };
         FileOperationsInterest {
+            did_create: FileOperationFilter::new(capabilities.did_create.as_ref()),
+            will_create: FileOperationFilter::new(capabilities.will_create.as_ref()),
             did_rename: FileOperationFilter::new(capabilities.did_rename.as_ref()),
             will_rename: FileOperationFilter::new(capabilities.will_rename.as_ref()),
+            did_delete: FileOperationFilter::new(capabilities.did_delete.as_ref()),
+            will_delete: FileOperationFilter::new(capabilities.will_delete.as_ref()),
         }
     }
 }
@@ -10,6 +10,7 @@
 pub use helix_lsp_types as lsp;
 pub use jsonrpc::Call;
 pub use lsp::{Position, Url};
+pub use transport::{TraceEntry, TrafficDirection, TrafficEntry, TrafficKind, TrafficLog};
 
 use futures_util::stream::select_all::SelectAll;
 use helix_core::syntax::config::{
@@ -23,6 +24,7 @@
     collections::HashMap,
     path::{Path, PathBuf},
     sync::Arc,
+    time::{Duration, Instant},
 };
 
 use thiserror::Error;
@@ -270,6 +272,90 @@ pub fn lsp_range_to_range(
         Some(Range::new(start, end))
     }
 
+    /// Applies the edits of a `textDocument/semanticTokens/full/delta` response to the
+    /// previously received tokens.
+    ///
+    /// Edit offsets index into the flat integer array of the encoded tokens, five integers per
+    /// token, and are all relative to the original array.
+    pub fn apply_semantic_tokens_edits(
+        tokens: &mut Vec<lsp::SemanticToken>,
+        mut edits: Vec<lsp::SemanticTokensEdit>,
+    ) {
+        let mut data: Vec<u32> = tokens
+            .iter()
+            .flat_map(|token| {
+                [
+                    token.delta_line,
+                    token.delta_start,
+                    token.length,
+                    token.token_type,
+                    token.token_modifiers_bitset,
+                ]
+            })
+            .collect();
+
+        // Apply the edits back to front so the offsets of the remaining edits stay valid.
+        edits.sort_unstable_by_key(|edit| std::cmp::Reverse(edit.start));
+        for edit in edits {
+            let start = (edit.start as usize).min(data.len());
+            let end = start
+                .saturating_add(edit.delete_count as usize)
+                .min(data.len());
+            let insert = edit.data.unwrap_or_default().into_iter().flat_map(|token| {
+                [
+                    token.delta_line,
+                    token.delta_start,
+                    token.length,
+                    token.token_type,
+                    token.token_modifiers_bitset,
+                ]
+            });
+            data.splice(start..end, insert);
+        }
+
+        *tokens = data
+            .chunks_exact(5)
+            .map(|chunk| lsp::SemanticToken {
+                delta_line: chunk[0],
+                delta_start: chunk[1],
+                length: chunk[2],
+                token_type: chunk[3],
+                token_modifiers_bitset: chunk[4],
+            })
+            .collect();
+    }
+
+    /// Decodes relatively encoded [`lsp::SemanticToken`]s into char ranges in the document,
+    /// paired with the token type index and the token modifiers bitset.
+    ///
+    /// Tokens which can't be mapped into the document are skipped.
+    pub fn lsp_semantic_tokens_to_ranges<'a>(
+        doc: &'a Rope,
+        tokens: &'a [lsp::SemanticToken],
+        offset_encoding: OffsetEncoding,
+    ) -> impl Iterator<Item = (std::ops::Range<usize>, u32, u32)> + 'a {
+        let mut line = 0u32;
+        let mut character = 0u32;
+        tokens.iter().filter_map(move |token| {
+            if token.delta_line != 0 {
+                line = line.checked_add(token.delta_line)?;
+                character = token.delta_start;
+            } else {
+                character = character.checked_add(token.delta_start)?;
+            }
+            if line as usize >= doc.len_lines() {
+                return None;
+            }
+            let start = lsp_pos_to_pos(doc, lsp::Position::new(line, character), offset_encoding)?;
+            let end = lsp_pos_to_pos(
+                doc,
+                lsp::Position::new(line, character.saturating_add(token.length)),
+                offset_encoding,
+            )?;
+            (start < end).then_some((start..end, token.token_type, token.token_modifiers_bitset))
+        })
+    }
+
     /// If the LS did not provide a range for the completion or the range of the
     /// primary cursor can not be used for the secondary cursor, this function
     /// can be used to find the completion range for a cursor
@@ -464,6 +550,8 @@ pub enum MethodCall {
     UnregisterCapability(lsp::UnregistrationParams),
     ShowDocument(lsp::ShowDocumentParams),
     WorkspaceDiagnosticRefresh,
+    SemanticTokensRefresh,
+    CodeLensRefresh,
 }
 
 impl MethodCall {
@@ -496,6 +584,8 @@ pub fn parse(method: &str, params: jsonrpc::Params) -> Result<MethodCall> {
                 Self::ShowDocument(params)
             }
             lsp::request::WorkspaceDiagnosticRefresh::METHOD => Self::WorkspaceDiagnosticRefresh,
+            lsp::request::SemanticTokensRefresh::METHOD => Self::SemanticTokensRefresh,
+            lsp::request::CodeLensRefresh::METHOD => Self::CodeLensRefresh,
             _ => {
                 return Err(Error::Unhandled);
             }
@@ -513,7 +603,14 @@ pub enum Notification {
     PublishDiagnostics(lsp::PublishDiagnosticsParams),
     ShowMessage(lsp::ShowMessageParams),
     LogMessage(lsp::LogMessageParams),
+    LogTrace(lsp::LogTraceParams),
     ProgressMessage(lsp::ProgressParams),
+    /// A partial result of a request sent with a `partialResultToken`, which is reported through
+    /// `$/progress` as well.
+    PartialResult {
+        token: lsp::ProgressToken,
+        value: serde_json::Value,
+    },
 }
 
 impl Notification {
@@ -536,9 +633,22 @@ pub fn parse(method: &str, params: jsonrpc::Params) -> Result<Notification> {
                 let params: lsp::LogMessageParams = params.parse()?;
                 Self::LogMessage(params)
             }
+            lsp::notification::LogTrace::METHOD => {
+                let params: lsp::LogTraceParams = params.parse()?;
+                Self::LogTrace(params)
+            }
             lsp::notification::Progress::METHOD => {
-                let params: lsp::ProgressParams = params.parse()?;
-                Self::ProgressMessage(params)
+                #[derive(serde::Deserialize)]
+                struct RawProgressParams {
+                    token: lsp::ProgressToken,
+                    value: serde_json::Value,
+                }
+
+                let RawProgressParams { token, value } = params.parse()?;
+                match serde_json::from_value(value.clone()) {
+                    Ok(value) => Self::ProgressMessage(lsp::ProgressParams { token, value }),
+                    Err(_) => Self::PartialResult { token, value },
+                }
             }
             _ => {
                 return Err(Error::Unhandled);
@@ -549,11 +659,43 @@ pub fn parse(method: &str, params: jsonrpc::Params) -> Result<Notification> {
     }
 }
 
+/// How often crashed language servers are restarted, see [`Registry::handle_crash`].
+#[derive(Debug, Clone, Copy)]
+pub struct RestartPolicy {
+    /// The number of crashes within `window` after which a language server is not restarted.
+    pub max_restarts: usize,
+    pub window: Duration,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CrashAction {
+    /// Restart the instance of the language server with [`Registry::take_pending_restart`]
+    /// after `delay`.
+    Restart { attempt: usize, delay: Duration },
+    /// The language server crashed too often and is left stopped until it is restarted manually.
+    GiveUp { crashes: usize },
+}
+
+/// The delay before the first restart of a crashed language server. It doubles with every
+/// further crash within the restart window.
+const RESTART_BACKOFF: Duration = Duration::from_secs(1);
+const MAX_RESTART_BACKOFF: Duration = Duration::from_secs(30);
+
+fn restart_backoff(attempt: usize) -> Duration {
+    let exponent = attempt.saturating_sub(1).min(16) as u32;
+    (RESTART_BACKOFF * 2u32.pow(exponent)).min(MAX_RESTART_BACKOFF)
+}
+
 #[derive(Debug)]
 pub struct Registry {
     inner: SlotMap<LanguageServerId, Arc<Client>>,
     inner_by_name: HashMap<LanguageServerName, Vec<Arc<Client>>>,
     syn_loader: Arc<ArcSwap<helix_core::syntax::Loader>>,
+    /// The times at which language servers exited unexpectedly.
+    crashes: HashMap<LanguageServerName, Vec<Instant>>,
+    /// The crashed instances of language servers that are waiting to be restarted. No new
+    /// instance of these servers is started by `get` in the meantime.
+    pending_restarts: HashMap<LanguageServerId, LanguageServerName>,
     pub incoming: SelectAll<UnboundedReceiverStream<(LanguageServerId, Call)>>,
     pub file_event_handler: file_event::Handler,
 }
@@ -564,6 +706,8 @@ pub fn new(syn_loader: Arc<ArcSwap<helix_core::syntax::Loader>>) -> Self {
             inner: SlotMap::with_key(),
             inner_by_name: HashMap::new(),
             syn_loader,
+            crashes: HashMap::new(),
+            pending_restarts: HashMap::new(),
             incoming: SelectAll::new(),
             file_event_handler: file_event::Handler::new(),
         }
@@ -589,6 +733,64 @@ pub fn remove_by_id(&mut self, id: LanguageServerId) {
         }
     }
 
+    /// Removes the language server `id` after it exited and decides whether it should be
+    /// restarted.
+    ///
+    /// Returns `None` if the language server was not registered anymore, i.e. it was stopped or
+    /// restarted deliberately.
+    pub fn handle_crash(
+        &mut self,
+        id: LanguageServerId,
+        policy: RestartPolicy,
+    ) -> Option<(LanguageServerName, CrashAction)> {
+        let name = self.inner.get(id)?.name().to_string();
+        self.remove_by_id(id);
+        let action = self.record_crash(id, &name, policy);
+        Some((name, action))
+    }
+
+    fn record_crash(
+        &mut self,
+        id: LanguageServerId,
+        name: &str,
+        policy: RestartPolicy,
+    ) -> CrashAction {
+        let now = Instant::now();
+        let crashes = self.crashes.entry(name.to_owned()).or_default();
+        crashes.retain(|crash| now.duration_since(*crash) < policy.window);
+        crashes.push(now);
+        let crashes = crashes.len();
+
+        if crashes > policy.max_restarts {
+            log::error!("language server '{name}' crashed {crashes} times, not restarting it");
+            // Leave a tombstone so that `get` doesn't start the server again, see `stop`.
+            self.inner_by_name.entry(name.to_owned()).or_default();
+            return CrashAction::GiveUp { crashes };
+        }
+
+        let delay = restart_backoff(crashes);
+        log::info!("restarting crashed language server '{name}' in {delay:?}");
+        self.pending_restarts.insert(id, name.to_owned());
+        CrashAction::Restart {
+            attempt: crashes,
+            delay,
+        }
+    }
+
+    /// Returns whether the crashed language server `id` should be restarted now. This is not
+    /// the case anymore if it was stopped or restarted manually in the meantime.
+    pub fn take_pending_restart(&mut self, id: LanguageServerId) -> bool {
+        self.pending_restarts.remove(&id).is_some()
+    }
+
+    /// Forgets the crashes and pending restarts of the language server `name`, which is
+    /// restarted or stopped deliberately.
+    fn clear_crashes(&mut self, name: &str) {
+        self.crashes.remove(name);
+        self.pending_restarts
+            .retain(|_, pending_name| pending_name != name);
+    }
+
     fn start_client(
         &mut self,
         name: String,
@@ -630,6 +832,7 @@ pub fn restart_server(
         root_dirs: &[PathBuf],
         enable_snippets: bool,
     ) -> Option<Result<Arc<Client>>> {
+        self.clear_crashes(name);
         if let Some(old_clients) = self.inner_by_name.remove(name) {
             if old_clients.is_empty() {
                 log::info!("restarting client for '{name}' which was manually stopped");
@@ -662,6 +865,15 @@ pub fn restart_server(
     }
 
     pub fn stop(&mut self, name: &str) {
+        if self
+            .pending_restarts
+            .values()
+            .any(|pending_name| pending_name == name)
+        {
+            // The server crashed and is waiting to be restarted: leave a tombstone instead.
+            self.inner_by_name.entry(name.to_owned()).or_default();
+        }
+        self.clear_crashes(name);
         if let Some(clients) = self.inner_by_name.get_mut(name) {
             // Drain the clients vec so that the entry in `inner_by_name` remains
             // empty. We use the empty vec as a "tombstone" to mean that a server
@@ -706,6 +918,14 @@ pub fn get<'a>(
                         return Some((name.to_owned(), Ok(client.clone())));
                     }
                 }
+                // Crashed servers are restarted with a backoff, see `handle_crash`.
+                if self
+                    .pending_restarts
+                    .values()
+                    .any(|pending_name| pending_name == name)
+                {
+                    return None;
+                }
                 match self.start_client(
                     name.clone(),
                     language_config,
@@ -906,6 +1126,7 @@ fn start_client(
         &ls_config.args,
         ls_config.config.clone(),
         &ls_config.environment,
+        ls_config.connect.as_ref(),
         root_path,
         root_uri,
         id,
@@ -1003,8 +1224,28 @@ pub fn find_lsp_workspace(
 
 #[cfg(test)]
 mod tests {
-    use super::{lsp, util::*, OffsetEncoding};
-    use helix_core::Rope;
+    use super::{
+        lsp, restart_backoff, util::*, CrashAction, LanguageServerId, OffsetEncoding, Registry,
+        RestartPolicy,
+    };
+    use arc_swap::ArcSwap;
+    use helix_core::{syntax, Rope};
+    use slotmap::SlotMap;
+    use std::{sync::Arc, time::Duration};
+
+    fn registry() -> Registry {
+        let config = syntax::config::Configuration {
+            language: Vec::new(),
+            language_server: Default::default(),
+        };
+        let loader = syntax::Loader::new(config).unwrap();
+        Registry::new(Arc::new(ArcSwap::from_pointee(loader)))
+    }
+
+    fn server_ids(n: usize) -> Vec<LanguageServerId> {
+        let mut ids = SlotMap::<LanguageServerId, ()>::with_key();
+        (0..n).map(|_| ids.insert(())).collect()
+    }
 
     #[test]
     fn converts_lsp_pos_to_pos() {
@@ -1031,6 +1272,68 @@ macro_rules! test_case {
         test_case!("", (u32::MAX, u32::MAX) => Some(0));
     }
 
+    #[test]
+    fn decodes_semantic_tokens() {
+        let doc = Rope::from("fn main() {\n    let x = 1;\n}\n");
+        let tokens = [
+            lsp::SemanticToken {
+                delta_line: 0,
+                delta_start: 3,
+                length: 4,
+                token_type: 1,
+                token_modifiers_bitset: 0b10,
+            },
+            lsp::SemanticToken {
+                delta_line: 1,
+                delta_start: 8,
+                length: 1,
+                token_type: 2,
+                token_modifiers_bitset: 0,
+            },
+            // Out of bounds tokens are dropped.
+            lsp::SemanticToken {
+                delta_line: 10,
+                delta_start: 0,
+                length: 1,
+                token_type: 2,
+                token_modifiers_bitset: 0,
+            },
+        ];
+
+        let ranges: Vec<_> =
+            lsp_semantic_tokens_to_ranges(&doc, &tokens, OffsetEncoding::Utf16).collect();
+        assert_eq!(ranges, vec![(3..7, 1, 0b10), (20..21, 2, 0)]);
+    }
+
+    #[test]
+    fn applies_semantic_tokens_edits() {
+        let token = |delta_line, token_type| lsp::SemanticToken {
+            delta_line,
+            delta_start: 0,
+            length: 1,
+            token_type,
+            token_modifiers_bitset: 0,
+        };
+        let mut tokens = vec![token(0, 0), token(1, 1), token(1, 2)];
+
+        apply_semantic_tokens_edits(
+            &mut tokens,
+            vec![
+                lsp::SemanticTokensEdit {
+                    start: 0,
+                    delete_count: 0,
+                    data: Some(vec![token(0, 3)]),
+                },
+                lsp::SemanticTokensEdit {
+                    start: 5,
+                    delete_count: 5,
+                    data: None,
+                },
+            ],
+        );
+        assert_eq!(tokens, vec![token(0, 3), token(0, 0), token(1, 2)]);
+    }
+
     #[test]
     fn emoji_format_gh_4791() {
         use lsp::{Position, Range, TextEdit};
@@ -1070,4 +1373,101 @@ fn emoji_format_gh_4791() {
         assert!(transaction.apply(&mut source));
         assert_eq!(source, "[\n  \"🇺🇸\",\n  \"🎄\",\n]");
     }
+
+    #[test]
+    fn restart_backoff_doubles() {
+        let delays: Vec<_> = (1..=7).map(restart_backoff).collect();
+        assert_eq!(
+            delays,
+            [1, 2, 4, 8, 16, 30, 30].map(Duration::from_secs).to_vec()
+        );
+    }
+
+    #[tokio::test]
+    async fn crashes_are_restarted_until_the_limit() {
+        let mut registry = registry();
+        let ids = server_ids(4);
+        let policy = RestartPolicy {
+            max_restarts: 2,
+            window: Duration::from_secs(60),
+        };
+        assert_eq!(
+            registry.record_crash(ids[0], "rust-analyzer", policy),
+            CrashAction::Restart {
+                attempt: 1,
+                delay: Duration::from_secs(1)
+            }
+        );
+        assert_eq!(
+            registry.record_crash(ids[1], "rust-analyzer", policy),
+            CrashAction::Restart {
+                attempt: 2,
+                delay: Duration::from_secs(2)
+            }
+        );
+        assert_eq!(
+            registry.record_crash(ids[2], "rust-analyzer", policy),
+            CrashAction::GiveUp { crashes: 3 }
+        );
+        // The tombstone keeps `get` from starting the server again.
+        assert!(registry.inner_by_name["rust-analyzer"].is_empty());
+
+        // Crashes outside of the window don't count.
+        let policy = RestartPolicy {
+            max_restarts: 2,
+            window: Duration::ZERO,
+        };
+        assert_eq!(
+            registry.record_crash(ids[3], "taplo", policy),
+            CrashAction::Restart {
+                attempt: 1,
+                delay: Duration::from_secs(1)
+            }
+        );
+        assert_eq!(
+            registry.record_crash(ids[3], "taplo", policy),
+            CrashAction::Restart {
+                attempt: 1,
+                delay: Duration::from_secs(1)
+            }
+        );
+    }
+
+    #[tokio::test]
+    async fn pending_restarts_are_per_instance() {
+        let mut registry = registry();
+        let ids = server_ids(2);
+        let policy = RestartPolicy {
+            max_restarts: 5,
+            window: Duration::from_secs(60),
+        };
+        // Two instances of the same server with different roots crash.
+        registry.record_crash(ids[0], "rust-analyzer", policy);
+        registry.record_crash(ids[1], "rust-analyzer", policy);
+        assert!(registry.take_pending_restart(ids[0]));
+        assert!(!registry.take_pending_restart(ids[0]));
+        assert!(registry.take_pending_restart(ids[1]));
+    }
+
+    #[tokio::test]
+    async fn stop_clears_crashes() {
+        let mut registry = registry();
+        let ids = server_ids(2);
+        let policy = RestartPolicy {
+            max_restarts: 1,
+            window: Duration::from_secs(60),
+        };
+        registry.record_crash(ids[0], "rust-analyzer", policy);
+        registry.stop("rust-analyzer");
+        // The stopped server isn't restarted and its crashes are forgotten.
+        assert!(!registry.take_pending_restart(ids[0]));
+        assert!(registry.inner_by_name["rust-analyzer"].is_empty());
+        assert_eq!(
+            registry.record_crash(ids[1], "rust-analyzer", policy),
+            CrashAction::Restart {
+                attempt: 1,
+                delay: Duration::from_secs(1)
+            }
+        );
+    }
 }
@@ -7,11 +7,12 @@
 use log::{error, info};
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
-use std::collections::HashMap;
+use std::collections::{HashMap, VecDeque};
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
+use std::time::{Duration, Instant};
 use tokio::{
-    io::{AsyncBufRead, AsyncBufReadExt, AsyncReadExt, AsyncWriteExt, BufReader, BufWriter},
-    process::{ChildStderr, ChildStdin, ChildStdout},
+    io::{AsyncBufRead, AsyncBufReadExt, AsyncReadExt, AsyncWrite, AsyncWriteExt},
     sync::{
         mpsc::{unbounded_channel, Sender, UnboundedReceiver, UnboundedSender},
         Mutex, Notify,
@@ -39,20 +40,279 @@ enum ServerMessage {
     Call(jsonrpc::Call),
 }
 
+/// The maximum number of messages kept in a [`TrafficLog`].
+const MAX_TRAFFIC_ENTRIES: usize = 2000;
+/// The maximum size of the messages kept in a [`TrafficLog`].
+const MAX_TRAFFIC_BYTES: usize = 8 * 1024 * 1024;
+/// The size above which the content of a message is truncated.
+const MAX_TRAFFIC_ENTRY_BYTES: usize = 64 * 1024;
+/// The maximum number of `$/logTrace` notifications kept in a [`TrafficLog`].
+const MAX_TRACE_ENTRIES: usize = 2000;
+/// The maximum number of `window/logMessage` notifications kept in a [`TrafficLog`].
+const MAX_LOG_MESSAGES: usize = 50;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum TrafficDirection {
+    /// Sent from the server to the client.
+    Incoming,
+    /// Sent from the client to the server.
+    Outgoing,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TrafficKind {
+    Request,
+    Response,
+    Error,
+    Notification,
+}
+
+/// A JSON-RPC message exchanged with a language server.
+#[derive(Debug, Clone)]
+pub struct TrafficEntry {
+    pub direction: TrafficDirection,
+    pub kind: TrafficKind,
+    /// The method of the message. For responses this is the method of the request.
+    pub method: String,
+    pub id: Option<jsonrpc::Id>,
+    /// The time the message was sent or received at, relative to the start of the server.
+    pub time: Duration,
+    /// The time it took to respond to the request, only set for responses.
+    pub duration: Option<Duration>,
+    /// The JSON content of the message, truncated if it is very large.
+    pub content: String,
+}
+
+/// A `$/logTrace` notification sent by a language server.
+#[derive(Debug, Clone)]
+pub struct TraceEntry {
+    /// The time the notification was received at, relative to the start of the server.
+    pub time: Duration,
+    pub params: lsp::LogTraceParams,
+}
+
+/// Records the latest messages exchanged with a language server once enabled, and the latest
+/// traces and messages it logged.
+#[derive(Debug)]
+pub struct TrafficLog {
+    start: Instant,
+    enabled: AtomicBool,
+    inner: parking_lot::Mutex<TrafficLogInner>,
+}
+
+#[derive(Debug, Default)]
+struct TrafficLogInner {
+    entries: VecDeque<TrafficEntry>,
+    /// The total size of the content of `entries`.
+    bytes: usize,
+    /// The method and time of the requests which were not responded to yet.
+    pending_requests: HashMap<(TrafficDirection, jsonrpc::Id), (String, Instant)>,
+    /// Traces are kept apart so that the other messages don't evict them.
+    traces: VecDeque<TraceEntry>,
+    /// Log messages are always recorded, for the language server status.
+    log_messages: VecDeque<lsp::LogMessageParams>,
+}
+
+impl Default for TrafficLog {
+    fn default() -> Self {
+        Self {
+            start: Instant::now(),
+            enabled: AtomicBool::new(false),
+            inner: Default::default(),
+        }
+    }
+}
+
+impl TrafficLog {
+    /// Whether the messages are recorded. Recording is disabled by default since messages like
+    /// `textDocument/didOpen` contain whole documents.
+    pub fn is_enabled(&self) -> bool {
+        self.enabled.load(Ordering::Relaxed)
+    }
+
+    /// Starts or stops recording the messages. The recorded messages are dropped when recording
+    /// stops.
+    pub fn set_enabled(&self, enabled: bool) {
+        self.enabled.store(enabled, Ordering::Relaxed);
+        if !enabled {
+            let mut inner = self.inner.lock();
+            inner.entries.clear();
+            inner.bytes = 0;
+            inner.pending_requests.clear();
+        }
+    }
+
+    /// Returns the recorded messages, from oldest to newest.
+    pub fn entries(&self) -> Vec<TrafficEntry> {
+        self.inner.lock().entries.iter().cloned().collect()
+    }
+
+    /// Returns the recorded traces, from oldest to newest.
+    pub fn traces(&self) -> Vec<TraceEntry> {
+        self.inner.lock().traces.iter().cloned().collect()
+    }
+
+    /// Returns the latest `window/logMessage` notifications, from oldest to newest.
+    pub fn log_messages(&self) -> Vec<lsp::LogMessageParams> {
+        self.inner.lock().log_messages.iter().cloned().collect()
+    }
+
+    fn record_request(
+        &self,
+        direction: TrafficDirection,
+        method: &str,
+        id: &jsonrpc::Id,
+        content: &str,
+    ) {
+        if !self.is_enabled() {
+            return;
+        }
+        let now = Instant::now();
+        let mut inner = self.inner.lock();
+        inner
+            .pending_requests
+            .insert((direction, id.clone()), (method.to_string(), now));
+        self.push(
+            &mut inner,
+            TrafficEntry {
+                direction,
+                kind: TrafficKind::Request,
+                method: method.to_string(),
+                id: Some(id.clone()),
+                time: now - self.start,
+                duration: None,
+                content: truncate_content(content),
+            },
+        );
+    }
+
+    fn record_response(
+        &self,
+        direction: TrafficDirection,
+        id: &jsonrpc::Id,
+        is_error: bool,
+        content: &str,
+    ) {
+        if !self.is_enabled() {
+            return;
+        }
+        let now = Instant::now();
+        let mut inner = self.inner.lock();
+        // Responses answer requests sent in the other direction.
+        let request_direction = match direction {
+            TrafficDirection::Incoming => TrafficDirection::Outgoing,
+            TrafficDirection::Outgoing => TrafficDirection::Incoming,
+        };
+        let request = inner
+            .pending_requests
+            .remove(&(request_direction, id.clone()));
+        self.push(
+            &mut inner,
+            TrafficEntry {
+                direction,
+                kind: if is_error {
+                    TrafficKind::Error
+                } else {
+                    TrafficKind::Response
+                },
+                method: request
+                    .as_ref()
+                    .map(|(method, _)| method.clone())
+                    .unwrap_or_default(),
+                id: Some(id.clone()),
+                time: now - self.start,
+                duration: request.map(|(_, sent)| now - sent),
+                content: truncate_content(content),
+            },
+        );
+    }
+
+    fn record_notification(&self, direction: TrafficDirection, method: &str, content: &str) {
+        if !self.is_enabled() {
+            return;
+        }
+        let now = Instant::now();
+        let mut inner = self.inner.lock();
+        self.push(
+            &mut inner,
+            TrafficEntry {
+                direction,
+                kind: TrafficKind::Notification,
+                method: method.to_string(),
+                id: None,
+                time: now - self.start,
+                duration: None,
+                content: truncate_content(content),
+            },
+        );
+    }
+
+    fn record_trace(&self, mut params: lsp::LogTraceParams) {
+        params.message = truncate_content(&params.message);
+        params.verbose = params.verbose.as_deref().map(truncate_content);
+        let mut inner = self.inner.lock();
+        if inner.traces.len() == MAX_TRACE_ENTRIES {
+            inner.traces.pop_front();
+        }
+        inner.traces.push_back(TraceEntry {
+            time: self.start.elapsed(),
+            params,
+        });
+    }
+
+    fn record_log_message(&self, mut params: lsp::LogMessageParams) {
+        params.message = truncate_content(&params.message);
+        let mut inner = self.inner.lock();
+        if inner.log_messages.len() == MAX_LOG_MESSAGES {
+            inner.log_messages.pop_front();
+        }
+        inner.log_messages.push_back(params);
+    }
+
+    fn push(&self, inner: &mut TrafficLogInner, entry: TrafficEntry) {
+        inner.bytes += entry.content.len();
+        inner.entries.push_back(entry);
+        while inner.entries.len() > MAX_TRAFFIC_ENTRIES || inner.bytes > MAX_TRAFFIC_BYTES {
+            let Some(entry) = inner.entries.pop_front() else {
+                break;
+            };
+            inner.bytes -= entry.content.len();
+        }
+    }
+}
+
+/// Truncates `content` to [`MAX_TRAFFIC_ENTRY_BYTES`].
+fn truncate_content(content: &str) -> String {
+    if content.len() <= MAX_TRAFFIC_ENTRY_BYTES {
+        return content.to_string();
+    }
+    let mut end = MAX_TRAFFIC_ENTRY_BYTES;
+    while !content.is_char_boundary(end) {
+        end -= 1;
+    }
+    format!(
+        "{}… ({} bytes truncated)",
+        &content[..end],
+        content.len() - end
+    )
+}
+
 #[derive(Debug)]
 pub struct Transport {
     id: LanguageServerId,
     name: String,
     pending_requests: Mutex<HashMap<jsonrpc::Id, Sender<Result<Value>>>>,
+    traffic: Arc<TrafficLog>,
 }
 
 impl Transport {
     pub fn start(
-        server_stdout: BufReader<ChildStdout>,
-        server_stdin: BufWriter<ChildStdin>,
-        server_stderr: BufReader<ChildStderr>,
+        server_stdout: Box<dyn AsyncBufRead + Unpin + Send>,
+        server_stdin: Box<dyn AsyncWrite + Unpin + Send>,
+        server_stderr: Option<Box<dyn AsyncBufRead + Unpin + Send>>,
         id: LanguageServerId,
         name: String,
+        traffic: Arc<TrafficLog>,
     ) -> (
         UnboundedReceiver<(LanguageServerId, jsonrpc::Call)>,
         UnboundedSender<Payload>,
@@ -66,6 +326,7 @@ pub fn start(
             id,
             name,
             pending_requests: Mutex::new(HashMap::default()),
+            traffic,
         };
 
         let transport = Arc::new(transport);
@@ -75,7 +336,9 @@ pub fn start(
             server_stdout,
             client_tx.clone(),
         ));
-        tokio::spawn(Self::err(transport.clone(), server_stderr));
+        if let Some(stderr) = server_stderr {
+            tokio::spawn(Self::err(transport.clone(), stderr));
+        }
         tokio::spawn(Self::send(
             transport,
             server_stdin,
@@ -92,6 +355,7 @@ async fn recv_server_message(
         buffer: &mut String,
         content: &mut Vec<u8>,
         language_server_name: &str,
+        traffic: &TrafficLog,
     ) -> Result<ServerMessage> {
         let mut content_length = None;
         loop {
@@ -136,6 +400,36 @@ async fn recv_server_message(
         // try parsing as output (server response) or call (server request)
         let output: serde_json::Result<ServerMessage> = serde_json::from_str(msg);
 
+        let direction = TrafficDirection::Incoming;
+        match &output {
+            Ok(ServerMessage::Output(jsonrpc::Output::Success(jsonrpc::Success {
+                id, ..
+            }))) => traffic.record_response(direction, id, false, msg),
+            Ok(ServerMessage::Output(jsonrpc::Output::Failure(jsonrpc::Failure {
+                id, ..
+            }))) => traffic.record_response(direction, id, true, msg),
+            Ok(ServerMessage::Call(jsonrpc::Call::MethodCall(call))) => {
+                traffic.record_request(direction, &call.method, &call.id, msg)
+            }
+            Ok(ServerMessage::Call(jsonrpc::Call::Notification(notification))) => {
+                traffic.record_notification(direction, &notification.method, msg);
+                match notification.method.as_str() {
+                    lsp::notification::LogTrace::METHOD => {
+                        if let Ok(params) = notification.params.clone().parse() {
+                            traffic.record_trace(params);
+                        }
+                    }
+                    lsp::notification::LogMessage::METHOD => {
+                        if let Ok(params) = notification.params.clone().parse() {
+                            traffic.record_log_message(params);
+                        }
+                    }
+                    _ => (),
+                }
+            }
+            Ok(ServerMessage::Call(jsonrpc::Call::Invalid { .. })) | Err(_) => (),
+        }
+
         content.clear();
 
         Ok(output?)
@@ -157,20 +451,37 @@ async fn recv_server_error(
 
     async fn send_payload_to_server(
         &self,
-        server_stdin: &mut BufWriter<ChildStdin>,
+        server_stdin: &mut Box<dyn AsyncWrite + Unpin + Send>,
         payload: Payload,
     ) -> Result<()> {
         //TODO: reuse string
+        let direction = TrafficDirection::Outgoing;
         let json = match payload {
             Payload::Request { chan, value } => {
                 self.pending_requests
                     .lock()
                     .await
                     .insert(value.id.clone(), chan);
-                serde_json::to_string(&value)?
+                let json = serde_json::to_string(&value)?;
+                self.traffic
+                    .record_request(direction, &value.method, &value.id, &json);
+                json
+            }
+            Payload::Notification(value) => {
+                let json = serde_json::to_string(&value)?;
+                self.traffic
+                    .record_notification(direction, &value.method, &json);
+                json
+            }
+            Payload::Response(output) => {
+                let json = serde_json::to_string(&output)?;
+                let (id, is_error) = match &output {
+                    jsonrpc::Output::Success(jsonrpc::Success { id, .. }) => (id, false),
+                    jsonrpc::Output::Failure(jsonrpc::Failure { id, .. }) => (id, true),
+                };
+                self.traffic.record_response(direction, id, is_error, &json);
+                json
             }
-            Payload::Notification(value) => serde_json::to_string(&value)?,
-            Payload::Response(error) => serde_json::to_string(&error)?,
         };
         self.send_string_to_server(server_stdin, json, &self.name)
             .await
@@ -178,7 +489,7 @@ async fn send_payload_to_server(
 
     async fn send_string_to_server(
         &self,
-        server_stdin: &mut BufWriter<ChildStdin>,
+        server_stdin: &mut Box<dyn AsyncWrite + Unpin + Send>,
         request: String,
         language_server_name: &str,
     ) -> Result<()> {
@@ -252,7 +563,7 @@ async fn process_request_response(
 
     async fn recv(
         transport: Arc<Self>,
-        mut server_stdout: BufReader<ChildStdout>,
+        mut server_stdout: Box<dyn AsyncBufRead + Unpin + Send>,
         client_tx: UnboundedSender<(LanguageServerId, jsonrpc::Call)>,
     ) {
         let mut recv_buffer = String::new();
@@ -263,6 +574,7 @@ async fn recv(
                 &mut recv_buffer,
                 &mut content_buffer,
                 &transport.name,
+                &transport.traffic,
             )
             .await
             {
@@ -318,7 +630,7 @@ async fn recv(
         }
     }
 
-    async fn err(transport: Arc<Self>, mut server_stderr: BufReader<ChildStderr>) {
+    async fn err(transport: Arc<Self>, mut server_stderr: Box<dyn AsyncBufRead + Unpin + Send>) {
         let mut recv_buffer = String::new();
         loop {
             match Self::recv_server_error(&mut server_stderr, &mut recv_buffer, &transport.name)
@@ -335,7 +647,7 @@ async fn err(transport: Arc<Self>, mut server_stderr: BufReader<ChildStderr>) {
 
     async fn send(
         transport: Arc<Self>,
-        mut server_stdin: BufWriter<ChildStdin>,
+        mut server_stdin: Box<dyn AsyncWrite + Unpin + Send>,
         client_tx: UnboundedSender<(LanguageServerId, jsonrpc::Call)>,
         mut client_rx: UnboundedReceiver<Payload>,
         initialize_notify: Arc<Notify>,
@@ -433,3 +745,80 @@ fn is_shutdown(payload: &Payload) -> bool {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn traffic_log_matches_responses_to_requests() {
+        let traffic = TrafficLog::default();
+        traffic.set_enabled(true);
+        let id = jsonrpc::Id::Num(1);
+        traffic.record_request(TrafficDirection::Outgoing, "textDocument/hover", &id, "");
+        traffic.record_notification(TrafficDirection::Incoming, "$/logTrace", "");
+        // A request sent by the server with the same id is answered separately.
+        traffic.record_response(TrafficDirection::Outgoing, &id, false, "");
+        traffic.record_response(TrafficDirection::Incoming, &id, true, "");
+
+        let entries = traffic.entries();
+        assert_eq!(entries.len(), 4);
+        assert_eq!(entries[2].method, "");
+        assert_eq!(entries[2].duration, None);
+        assert_eq!(entries[3].kind, TrafficKind::Error);
+        assert_eq!(entries[3].method, "textDocument/hover");
+        assert!(entries[3].duration.is_some());
+    }
+
+    #[test]
+    fn traffic_log_is_bounded() {
+        let traffic = TrafficLog::default();
+        // Nothing is recorded until recording is enabled.
+        traffic.record_notification(TrafficDirection::Outgoing, "exit", "");
+        assert!(traffic.entries().is_empty());
+
+        traffic.set_enabled(true);
+        for _ in 0..MAX_TRAFFIC_ENTRIES + 1 {
+            traffic.record_notification(TrafficDirection::Outgoing, "exit", "");
+        }
+        assert_eq!(traffic.entries().len(), MAX_TRAFFIC_ENTRIES);
+
+        let content = "é".repeat(MAX_TRAFFIC_ENTRY_BYTES);
+        for _ in 0..MAX_TRAFFIC_BYTES / MAX_TRAFFIC_ENTRY_BYTES + 1 {
+            traffic.record_notification(
+                TrafficDirection::Outgoing,
+                "textDocument/didOpen",
+                &content,
+            );
+        }
+        let entries = traffic.entries();
+        let bytes: usize = entries.iter().map(|entry| entry.content.len()).sum();
+        assert!(bytes <= MAX_TRAFFIC_BYTES);
+        assert!(entries[0].content.len() < MAX_TRAFFIC_ENTRY_BYTES + 32);
+        assert!(entries[0].content.ends_with("bytes truncated)"));
+
+        // The traces aren't evicted by the other messages.
+        traffic.record_trace(lsp::LogTraceParams {
+            message: "Received request".to_string(),
+            verbose: None,
+        });
+        for _ in 0..MAX_TRAFFIC_ENTRIES {
+            traffic.record_notification(TrafficDirection::Outgoing, "exit", "");
+        }
+        assert_eq!(traffic.traces().len(), 1);
+
+        // Log messages are recorded while recording is disabled.
+        traffic.set_enabled(false);
+        for i in 0..MAX_LOG_MESSAGES + 1 {
+            traffic.record_log_message(lsp::LogMessageParams {
+                typ: lsp::MessageType::INFO,
+                message: i.to_string(),
+            });
+        }
+        let log_messages = traffic.log_messages();
+        assert_eq!(log_messages.len(), MAX_LOG_MESSAGES);
+        assert_eq!(log_messages[0].message, "1");
+
+        assert!(traffic.entries().is_empty());
+    }
+}
@@ -4,7 +4,7 @@
 use helix_lsp::{
     lsp::{self, notification::Notification},
     util::lsp_range_to_range,
-    LanguageServerId, LspProgressMap,
+    CrashAction, LanguageServerId,
 };
 use helix_stdx::path::get_relative_path;
 use helix_view::{
@@ -12,6 +12,7 @@
     document::{DocumentOpenError, DocumentSavedEventResult},
     editor::{ConfigEvent, EditorEvent},
     graphics::Rect,
+    handlers::lsp::{workspace_edit_items, ApplyEditError},
     theme,
     tree::Layout,
     Align, Editor,
@@ -21,10 +22,11 @@
 
 use crate::{
     args::Args,
+    commands,
     compositor::{Compositor, Event},
     config::Config,
     handlers,
-    job::Jobs,
+    job::{self, Jobs},
     keymap::Keymaps,
     ui::{self, overlay::overlaid},
 };
@@ -34,6 +36,7 @@
     io::{stdin, IsTerminal},
     path::Path,
     sync::Arc,
+    time::Duration,
 };
 
 #[cfg_attr(windows, allow(unused_imports))]
@@ -76,11 +79,50 @@ pub struct Application {
 
     signals: Signals,
     jobs: Jobs,
-    lsp_progress: LspProgressMap,
 
     theme_mode: Option<theme::Mode>,
 }
 
+fn apply_workspace_edit_response(
+    res: &Result<(), ApplyEditError>,
+) -> lsp::ApplyWorkspaceEditResponse {
+    lsp::ApplyWorkspaceEditResponse {
+        applied: res.is_ok(),
+        failure_reason: res.as_ref().err().map(|err| err.kind.to_string()),
+        failed_change: res.as_ref().err().map(|err| err.failed_change_idx as u32),
+    }
+}
+
+/// Restarts the instance `id` of the language server `name` that crashed if it is still
+/// supposed to run. The other instances of the server keep running.
+fn restart_crashed_language_server(editor: &mut Editor, id: LanguageServerId, name: &str) {
+    if !editor.language_servers.take_pending_restart(id) {
+        return;
+    }
+    // The documents that used the crashed instance start a new one, documents of other
+    // instances keep theirs. The server is started again once a document needs it otherwise.
+    let doc_ids: Vec<_> = editor
+        .documents()
+        .filter(|doc| {
+            doc.language_config()
+                .is_some_and(|config| config.language_servers.iter().any(|ls| ls.name == name))
+        })
+        .map(|doc| doc.id())
+        .collect();
+    for doc_id in doc_ids {
+        editor.refresh_language_servers(doc_id);
+    }
+    if editor
+        .language_servers
+        .iter_clients()
+        .any(|client| client.name() == name)
+    {
+        editor.set_status(format!("Restarted language server '{name}'"));
+    } else {
+        editor.set_error(format!("Failed to restart language server '{name}'"));
+    }
+}
+
 #[cfg(feature = "integration")]
 fn setup_integration_logging() {
     let level = std::env::var("HELIX_LOG_LEVEL")
@@ -144,6 +186,9 @@ pub fn new(args: Args, config: Config, lang_loader: syntax::Loader) -> Result<Se
             terminal.backend().supports_true_color(),
             theme_mode,
         );
+        // Integration tests must not change the breakpoints of the user.
+        #[cfg(not(feature = "integration"))]
+        editor.load_breakpoints(helix_loader::find_workspace().0);
 
         let keys = Box::new(Map::new(Arc::clone(&config), |config: &Config| {
             &config.keys
@@ -261,7 +306,6 @@ pub fn new(args: Args, config: Config, lang_loader: syntax::Loader) -> Result<Se
             config,
             signals,
             jobs: Jobs::new(),
-            lsp_progress: LspProgressMap::new(),
             theme_mode,
         };
 
@@ -840,6 +884,10 @@ macro_rules! language_server {
                     Notification::LogMessage(params) => {
                         log::info!("window/logMessage: {:?}", params);
                     }
+                    Notification::LogTrace(params) => {
+                        // The traces are displayed by `:lsp-trace-log`.
+                        log::debug!("$/logTrace: {}", params.message);
+                    }
                     Notification::ProgressMessage(params)
                         if !self
                             .compositor
@@ -869,8 +917,8 @@ macro_rules! language_server {
                                 if message.is_some() {
                                     (None, message, &None)
                                 } else {
-                                    self.lsp_progress.end_progress(server_id, &token);
-                                    if !self.lsp_progress.is_progressing(server_id) {
+                                    self.editor.lsp_progress.end_progress(server_id, &token);
+                                    if !self.editor.lsp_progress.is_progressing(server_id) {
                                         editor_view.spinners_mut().get_or_create(server_id).stop();
                                     }
                                     self.editor.clear_status();
@@ -883,7 +931,7 @@ macro_rules! language_server {
 
                         if self.editor.config().lsp.display_progress_messages {
                             let title =
-                                title.or_else(|| self.lsp_progress.title(server_id, &token));
+                                title.or_else(|| self.editor.lsp_progress.title(server_id, &token));
                             if title.is_some() || percentage.is_some() || message.is_some() {
                                 use std::fmt::Write as _;
                                 let mut status = format!("{}: ", language_server!().name());
@@ -905,16 +953,22 @@ macro_rules! language_server {
 
                         match work {
                             lsp::WorkDoneProgress::Begin(begin_status) => {
-                                self.lsp_progress
-                                    .begin(server_id, token.clone(), begin_status);
+                                self.editor.lsp_progress.begin(
+                                    server_id,
+                                    token.clone(),
+                                    begin_status,
+                                );
                             }
                             lsp::WorkDoneProgress::Report(report_status) => {
-                                self.lsp_progress
-                                    .update(server_id, token.clone(), report_status);
+                                self.editor.lsp_progress.update(
+                                    server_id,
+                                    token.clone(),
+                                    report_status,
+                                );
                             }
                             lsp::WorkDoneProgress::End(_) => {
-                                self.lsp_progress.end_progress(server_id, &token);
-                                if !self.lsp_progress.is_progressing(server_id) {
+                                self.editor.lsp_progress.end_progress(server_id, &token);
+                                if !self.editor.lsp_progress.is_progressing(server_id) {
                                     editor_view.spinners_mut().get_or_create(server_id).stop();
                                 };
                             }
@@ -923,9 +977,20 @@ macro_rules! language_server {
                     Notification::ProgressMessage(_params) => {
                         // do nothing
                     }
+                    Notification::PartialResult { token, value }
+                        if token
+                            == handlers::diagnostics::workspace_diagnostics_token(server_id) =>
+                    {
+                        handlers::diagnostics::handle_workspace_diagnostics_partial_result(
+                            &mut self.editor,
+                            server_id,
+                            value,
+                        );
+                    }
+                    Notification::PartialResult { token, .. } => {
+                        log::warn!("Discarding partial result with unknown token {token:?}");
+                    }
                     Notification::Exit => {
-                        self.editor.set_status("Language server exited");
-
                         // LSPs may produce diagnostics for files that haven't been opened in helix,
                         // we need to clear those and remove the entries from the list if this leads to
                         // an empty diagnostic list for said files
@@ -947,8 +1012,42 @@ macro_rules! language_server {
                             server_id,
                         });
 
-                        // Remove the language server from the registry.
-                        self.editor.language_servers.remove_by_id(server_id);
+                        let lsp_config = &self.editor.config().lsp;
+                        let policy = helix_lsp::RestartPolicy {
+                            max_restarts: lsp_config.max_restarts,
+                            window: Duration::from_secs(lsp_config.restart_window),
+                        };
+                        let crash = if lsp_config.auto_restart {
+                            // Servers that were stopped or restarted on purpose are already
+                            // removed from the registry.
+                            self.editor.language_servers.handle_crash(server_id, policy)
+                        } else {
+                            // Remove the language server from the registry.
+                            self.editor.language_servers.remove_by_id(server_id);
+                            None
+                        };
+                        match crash {
+                            None => self.editor.set_status("Language server exited"),
+                            Some((name, CrashAction::GiveUp { crashes })) => {
+                                self.editor.set_error(format!(
+                                    "Language server '{name}' crashed {crashes} times, not restarting it. Use :lsp-restart to start it again"
+                                ));
+                            }
+                            Some((name, CrashAction::Restart { attempt, delay })) => {
+                                self.editor.set_status(format!(
+                                    "Language server '{name}' exited unexpectedly, restarting in {}s (attempt {attempt} of {})",
+                                    delay.as_secs(),
+                                    policy.max_restarts,
+                                ));
+                                tokio::spawn(async move {
+                                    tokio::time::sleep(delay).await;
+                                    job::dispatch(move |editor, _| {
+                                        restart_crashed_language_server(editor, server_id, &name)
+                                    })
+                                    .await;
+                                });
+                            }
+                        }
                     }
                 }
             }
@@ -981,7 +1080,7 @@ macro_rules! language_server {
                         })
                     }
                     Ok(MethodCall::WorkDoneProgressCreate(params)) => {
-                        self.lsp_progress.create(server_id, params.token);
+                        self.editor.lsp_progress.create(server_id, params.token);
 
                         let editor_view = self
                             .compositor
@@ -998,18 +1097,45 @@ macro_rules! language_server {
                         let language_server = language_server!();
                         if language_server.is_initialized() {
                             let offset_encoding = language_server.offset_encoding();
+                            if commands::needs_workspace_edit_preview(&self.editor, &params.edit) {
+                                // Reply once the user applied or rejected the edit.
+                                let preview = ui::WorkspaceEditPreview::new(
+                                    workspace_edit_items(&params.edit),
+                                    offset_encoding,
+                                    move |editor, res| {
+                                        let Some(language_server) =
+                                            editor.language_server_by_id(server_id)
+                                        else {
+                                            return;
+                                        };
+                                        let response = match res {
+                                            Some(res) => apply_workspace_edit_response(&res),
+                                            None => lsp::ApplyWorkspaceEditResponse {
+                                                applied: false,
+                                                failure_reason: Some(
+                                                    "rejected by the user".to_string(),
+                                                ),
+                                                failed_change: None,
+                                            },
+                                        };
+                                        if let Err(err) =
+                                            language_server.reply(id, Ok(json!(response)))
+                                        {
+                                            log::error!(
+                                                "Failed to send reply to server '{}': {err}",
+                                                language_server.name()
+                                            );
+                                        }
+                                    },
+                                );
+                                self.compositor.push(Box::new(overlaid(preview)));
+                                return;
+                            }
+
                             let res = self
                                 .editor
                                 .apply_workspace_edit(offset_encoding, &params.edit);
-
-                            Ok(json!(lsp::ApplyWorkspaceEditResponse {
-                                applied: res.is_ok(),
-                                failure_reason: res.as_ref().err().map(|err| err.kind.to_string()),
-                                failed_change: res
-                                    .as_ref()
-                                    .err()
-                                    .map(|err| err.failed_change_idx as u32),
-                            }))
+                            Ok(json!(apply_workspace_edit_response(&res)))
                         } else {
                             Err(helix_lsp::jsonrpc::Error {
                                 code: helix_lsp::jsonrpc::ErrorCode::InvalidRequest,
@@ -1120,6 +1246,47 @@ macro_rules! language_server {
                                 document,
                             );
                         }
+                        handlers::diagnostics::request_workspace_diagnostics(
+                            &mut self.editor,
+                            language_server,
+                        );
+
+                        Ok(serde_json::Value::Null)
+                    }
+                    Ok(MethodCall::SemanticTokensRefresh) => {
+                        let language_server = language_server!().id();
+
+                        let documents: Vec<_> = self
+                            .editor
+                            .documents
+                            .values()
+                            .filter(|x| x.supports_language_server(language_server))
+                            .map(|x| x.id())
+                            .collect();
+
+                        for document in documents {
+                            handlers::semantic_tokens::request_semantic_tokens(
+                                &mut self.editor,
+                                document,
+                            );
+                        }
+
+                        Ok(serde_json::Value::Null)
+                    }
+                    Ok(MethodCall::CodeLensRefresh) => {
+                        let language_server = language_server!().id();
+
+                        let documents: Vec<_> = self
+                            .editor
+                            .documents
+                            .values()
+                            .filter(|x| x.supports_language_server(language_server))
+                            .map(|x| x.id())
+                            .collect();
+
+                        for document in documents {
+                            handlers::code_lens::request_code_lenses(&mut self.editor, document);
+                        }
 
                         Ok(serde_json::Value::Null)
                     }
@@ -1166,20 +1333,30 @@ fn handle_show_document(
                 return lsp::ShowDocumentResult { success: false };
             }
         };
-        // If `Uri` gets another variant other than `Path` this may not be valid.
-        let path = uri.as_path().expect("URIs are valid paths");
-
         let action = match take_focus {
             Some(true) => helix_view::editor::Action::Replace,
             _ => helix_view::editor::Action::VerticalSplit,
         };
 
-        let doc_id = match self.editor.open(path, action) {
-            Ok(id) => id,
-            Err(err) => {
-                log::error!("failed to open path: {:?}: {:?}", uri, err);
-                return lsp::ShowDocumentResult { success: false };
-            }
+        let doc_id = match uri.as_path() {
+            Some(path) => match self.editor.open(path, action) {
+                Ok(id) => id,
+                Err(err) => {
+                    log::error!("failed to open path: {:?}: {:?}", uri, err);
+                    return lsp::ShowDocumentResult { success: false };
+                }
+            },
+            // Virtual documents can only be shown once they are open.
+            None => match self.editor.document_by_uri(&uri).map(|doc| doc.id()) {
+                Some(id) => {
+                    self.editor.switch(id, action);
+                    id
+                }
+                None => {
+                    log::error!("virtual document is not open: {uri}");
+                    return lsp::ShowDocumentResult { success: false };
+                }
+            },
         };
 
         let doc = doc_mut!(self.editor, &doc_id);
@@ -4,7 +4,7 @@
 pub(crate) mod typed;
 
 pub use dap::*;
-use futures_util::FutureExt;
+use futures_util::{future::BoxFuture, FutureExt};
 use helix_event::status;
 use helix_stdx::{
     path::{self, find_paths},
@@ -46,8 +46,8 @@
 };
 use helix_view::{
     document::{FormatterError, Mode, SCRATCH_BUFFER_NAME},
+    editor::{Action, FileOperationEdits, MotionMode},
     expansion,
-    editor::{Action, MotionMode},
     info::Info,
     input::KeyEvent,
     keyboard::KeyCode,
@@ -409,6 +409,11 @@ pub fn doc(&self) -> &str {
         file_explorer_in_current_buffer_directory, "Open file explorer at current buffer's directory",
         file_explorer_in_current_directory, "Open file explorer at current working directory",
         code_action, "Perform code action",
+        incoming_calls, "Show incoming calls of the symbol under cursor",
+        outgoing_calls, "Show outgoing calls of the symbol under cursor",
+        supertypes, "Show supertypes of the type under cursor",
+        subtypes, "Show subtypes of the type under cursor",
+        code_lens, "Run code lens on the current line",
         buffer_picker, "Open buffer picker",
         jumplist_picker, "Open jumplist picker",
         symbol_picker, "Open symbol picker",
@@ -518,6 +523,10 @@ pub fn doc(&self) -> &str {
         keep_primary_selection, "Keep primary selection",
         remove_primary_selection, "Remove primary selection",
         completion, "Invoke completion popup",
+        accept_inline_completion, "Accept inline completion",
+        accept_inline_completion_word, "Accept next word of inline completion",
+        next_inline_completion, "Show next inline completion",
+        prev_inline_completion, "Show previous inline completion",
         hover, "Show docs for item under cursor",
         toggle_comments, "Comment/uncomment selections",
         toggle_line_comments, "Line comment/uncomment selections",
@@ -562,6 +571,9 @@ pub fn doc(&self) -> &str {
         align_view_bottom, "Align view bottom",
         scroll_up, "Scroll view up",
         scroll_down, "Scroll view down",
+        toggle_fold, "Toggle fold at the cursor",
+        fold_all, "Fold all top-level ranges",
+        unfold_all, "Unfold all folds",
         match_brackets, "Goto matching bracket",
         surround_add, "Surround add",
         surround_replace, "Surround replace",
@@ -593,8 +605,12 @@ pub fn doc(&self) -> &str {
         dap_step_out, "Step out",
         dap_next, "Step to next",
         dap_variables, "List variables",
+        dap_breakpoint_picker, "List breakpoints",
+        dap_console, "Open debug console",
+        dap_sidebar, "Open or focus the debugger sidebar",
         dap_terminate, "End debug session",
         dap_edit_condition, "Edit breakpoint condition on current line",
+        dap_edit_hit_condition, "Edit breakpoint hit condition on current line",
         dap_edit_log, "Edit breakpoint log message on current line",
         dap_switch_thread, "Switch current thread",
         dap_switch_stack_frame, "Switch stack frame",
@@ -1342,6 +1358,33 @@ fn goto_file_impl(cx: &mut Context, action: Action) {
     let text = doc.text().slice(..);
     let selections = doc.selection(view.id);
     let primary = selections.primary();
+
+    // Prefer the target of a document link provided by a language server.
+    if selections.len() == 1 && primary.len() == 1 {
+        if let Some(link) = doc.document_link_at(primary.cursor(text)) {
+            if let Some(target) = link.link.target.clone() {
+                return cx.jobs.callback(open_document_link(target, action));
+            }
+            let language_server_id = link.language_server_id;
+            let link = link.link.clone();
+            if let Some(future) = cx
+                .editor
+                .language_server_by_id(language_server_id)
+                .and_then(|language_server| language_server.resolve_document_link(&link))
+            {
+                cx.jobs.callback(async move {
+                    let Some(target) = future.await?.target else {
+                        return Ok(Callback::Editor(Box::new(|editor: &mut Editor| {
+                            editor.set_error("Document link has no target")
+                        })));
+                    };
+                    open_document_link(target, action).await
+                });
+                return;
+            }
+        }
+    }
+
     let rel_path = doc
         .relative_path()
         .map(|path| path.parent().unwrap().to_path_buf())
@@ -1394,6 +1437,23 @@ fn goto_file_impl(cx: &mut Context, action: Action) {
     }
 }
 
+/// Opens the target of a document link. `file` URLs are opened in helix, other schemes are
+/// opened using an external program.
+async fn open_document_link(target: Url, action: Action) -> anyhow::Result<Callback> {
+    if target.scheme() != "file" {
+        return crate::open_external_url_callback(target).await;
+    }
+    Ok(Callback::Editor(Box::new(move |editor: &mut Editor| {
+        // Decodes percent-escapes, for example in paths containing spaces.
+        let Ok(path) = target.to_file_path() else {
+            return editor.set_error(format!("Invalid document link: {target}"));
+        };
+        if let Err(e) = editor.open(&path, action) {
+            editor.set_error(format!("Open file failed: {:?}", e));
+        }
+    })))
+}
+
 /// Opens the given url. If the URL points to a valid textual file it is open in helix.
 //  Otherwise, the file is open using external program.
 fn open_url(cx: &mut Context, url: Url, action: Action) {
@@ -3604,6 +3664,9 @@ fn insert_with_indent(cx: &mut Context, cursor_fallback: IndentFallbackPos) {
 // Creates an LspCallback that waits for formatting changes to be computed. When they're done,
 // it applies them, but only if the doc hasn't changed.
 //
+// When the document is written afterwards, `will_create` are the `workspace/willCreateFiles`
+// requests of a write which creates the file. Their edits are applied before writing.
+//
 // TODO: provide some way to cancel this, probably as part of a more general job cancellation
 // scheme
 async fn make_format_callback(
@@ -3612,8 +3675,13 @@ async fn make_format_callback(
     view_id: ViewId,
     format: impl Future<Output = Result<Transaction, FormatterError>> + Send + 'static,
     write: Option<(Option<PathBuf>, bool)>,
+    will_create: Option<BoxFuture<'static, FileOperationEdits>>,
 ) -> anyhow::Result<job::Callback> {
     let format = format.await;
+    let will_create_edits = match will_create {
+        Some(request) => request.await,
+        None => Vec::new(),
+    };
 
     let call: job::Callback = Callback::Editor(Box::new(move |editor| {
         if !editor.documents.contains_key(&doc_id) || !editor.tree.contains(view_id) {
@@ -3646,6 +3714,7 @@ async fn make_format_callback(
 
         if let Some((path, force)) = write {
             let id = doc.id();
+            editor.apply_file_operation_edits("willCreate", will_create_edits);
             if let Err(err) = editor.save(id, path, force) {
                 editor.set_error(format!("Error saving: {}", err));
             }
@@ -5494,19 +5563,35 @@ fn selection_motion(cx: &mut Context, direction: Direction) {
 }
 
 fn expand_selection_impl(editor: &mut Editor) {
-    let (view, doc) = current!(editor);
-
-    if let Some(syntax) = doc.syntax() {
-        let text = doc.text().slice(..);
+    if lsp::selection_range_motion(editor, Direction::Forward) {
+        return;
+    }
+    syntax_selection_motion(editor, Direction::Forward);
+}
 
-        let current_selection = doc.selection(view.id);
-        let selection = object::expand_selection(syntax, text, current_selection.clone());
+/// Expands the selections of the current view to the enclosing syntax tree nodes or shrinks
+/// them to their first children.
+fn syntax_selection_motion(editor: &mut Editor, direction: Direction) {
+    let (view, doc) = current!(editor);
+    let Some(syntax) = doc.syntax() else {
+        return;
+    };
+    let text = doc.text().slice(..);
+    let current_selection = doc.selection(view.id);
+    match direction {
+        Direction::Forward => {
+            let selection = object::expand_selection(syntax, text, current_selection.clone());
 
-        // check if selection is different from the last one
-        if *current_selection != selection {
-            // save current selection so it can be restored using shrink_selection
-            view.object_selections.push(current_selection.clone());
+            // check if selection is different from the last one
+            if *current_selection != selection {
+                // save current selection so it can be restored using shrink_selection
+                view.object_selections.push(current_selection.clone());
 
+                doc.set_selection(view.id, selection);
+            }
+        }
+        Direction::Backward => {
+            let selection = object::shrink_selection(syntax, text, current_selection.clone());
             doc.set_selection(view.id, selection);
         }
     }
@@ -5526,11 +5611,10 @@ fn shrink_selection_impl(editor: &mut Editor) {
     }
     // try to restore previous selection
     // if not previous selection, shrink to first child
-    if let Some(syntax) = doc.syntax() {
-        let text = doc.text().slice(..);
-        let selection = object::shrink_selection(syntax, text, current_selection.clone());
-        doc.set_selection(view.id, selection);
+    if lsp::selection_range_motion(editor, Direction::Backward) {
+        return;
     }
+    syntax_selection_motion(editor, Direction::Backward);
 }
 
 fn select_sibling_impl(cx: &mut Context, direction: Direction) {
@@ -5932,6 +6016,68 @@ fn align_view_middle(cx: &mut Context) {
     doc.set_view_offset(view.id, offset);
 }
 
+/// Folds `start_line + 1..=end_line`, moving the cursor to `start_line` if it would be hidden.
+fn fold_lines(doc: &mut Document, view: &mut View, start_line: usize, end_line: usize) {
+    let text = doc.text().slice(..);
+    let cursor_line = doc.selection(view.id).primary().cursor_line(text);
+    if start_line < cursor_line && cursor_line <= end_line {
+        let pos = text.line_to_char(start_line);
+        doc.set_selection(view.id, Selection::point(pos));
+    }
+    doc.fold(view.id, start_line, end_line);
+}
+
+fn toggle_fold(cx: &mut Context) {
+    let (view, doc) = current!(cx.editor);
+    let cursor_line = doc
+        .selection(view.id)
+        .primary()
+        .cursor_line(doc.text().slice(..));
+    if doc.unfold(view.id, cursor_line) {
+        return;
+    }
+
+    let text = doc.text().slice(..);
+
+    // fold the innermost range containing the cursor
+    let range = doc
+        .folding_ranges
+        .iter()
+        .map(|range| range.lines(text))
+        .filter(|&(start, end)| start <= cursor_line && cursor_line <= end)
+        .min_by_key(|&(start, end)| end - start);
+    match range {
+        Some((start_line, end_line)) => fold_lines(doc, view, start_line, end_line),
+        None => cx.editor.set_error("No foldable range at the cursor"),
+    }
+}
+
+fn fold_all(cx: &mut Context) {
+    let (view, doc) = current!(cx.editor);
+    let text = doc.text().slice(..);
+    let mut ranges: Vec<_> = doc
+        .folding_ranges
+        .iter()
+        .map(|range| range.lines(text))
+        .collect();
+    ranges.sort_by_key(|&(start, end)| (start, std::cmp::Reverse(end)));
+
+    let mut last_end = None;
+    for (start_line, end_line) in ranges {
+        // only fold the outermost ranges, nested ones are hidden by them anyway
+        if last_end.is_some_and(|last_end| start_line <= last_end) {
+            continue;
+        }
+        fold_lines(doc, view, start_line, end_line);
+        last_end = Some(end_line);
+    }
+}
+
+fn unfold_all(cx: &mut Context) {
+    let (view, doc) = current!(cx.editor);
+    doc.unfold_all(view.id);
+}
+
 fn scroll_up(cx: &mut Context) {
     scroll(cx, cx.count(), Direction::Backward, false);
 }
@@ -1,14 +1,20 @@
 use super::{Context, Editor};
 use crate::{
+    alt,
     compositor::{self, Compositor},
+    handlers::dap::{fetch_inline_values, fetch_variables},
     job::{Callback, Jobs},
     ui::{self, overlay::overlaid, Picker, Popup, Prompt, PromptEvent, Text},
 };
 use dap::{StackFrame, Thread, ThreadStates};
-use helix_core::syntax::config::{DebugArgumentValue, DebugConfigCompletion, DebugTemplate};
-use helix_dap::{self as dap, requests::TerminateArguments};
+use helix_core::syntax::config::{
+    DebugAdapterConfig, DebugArgumentValue, DebugConfigCompletion, DebugTemplate,
+};
+use helix_dap::{
+    self as dap, launch_json, launch_json::LaunchConfiguration, requests::TerminateArguments,
+};
 use helix_lsp::block_on;
-use helix_view::editor::Breakpoint;
+use helix_view::editor::{Breakpoint, DataBreakpoint, FunctionBreakpoint, InstructionBreakpoint};
 
 use serde_json::{to_value, Value};
 use tui::text::Spans;
@@ -19,7 +25,10 @@
 
 use anyhow::{anyhow, bail};
 
-use helix_view::handlers::dap::{breakpoints_changed, jump_to_stack_frame, select_thread_id};
+use helix_view::handlers::dap::{
+    breakpoints_changed, data_breakpoints_changed, function_breakpoints_changed,
+    instruction_breakpoints_changed, jump_to_stack_frame, select_thread_id,
+};
 
 fn thread_picker(
     cx: &mut Context,
@@ -122,15 +131,9 @@ pub fn dap_start_impl(
     let doc = doc!(cx.editor);
     let config = doc
         .language_config()
-        .and_then(|config| config.debugger.as_ref())
+        .and_then(|config| config.debugger.clone())
         .ok_or_else(|| anyhow!("No debug adapter available for language"))?;
 
-    let id = cx
-        .editor
-        .debug_adapters
-        .start_client(socket, config)
-        .map_err(|e| anyhow!("Failed to start debug client: {}", e))?;
-
     // TODO: avoid refetching all of this... pass a config in
     let template = match name {
         Some(name) => config.templates.iter().find(|t| t.name == name),
@@ -199,6 +202,27 @@ pub fn dap_start_impl(
 
     let args = to_value(args).unwrap();
 
+    start_debug_session(cx, socket, &config, &template.request, args)
+}
+
+/// Starts the debug adapter of `config` and sends it the `launch` or `attach` `request`.
+fn start_debug_session(
+    cx: &mut compositor::Context,
+    socket: Option<std::net::SocketAddr>,
+    config: &DebugAdapterConfig,
+    request: &str,
+    args: Value,
+) -> Result<(), anyhow::Error> {
+    if !matches!(request, "launch" | "attach") {
+        bail!("Unsupported request '{}'", request);
+    }
+
+    let id = cx
+        .editor
+        .debug_adapters
+        .start_client(socket, config)
+        .map_err(|e| anyhow!("Failed to start debug client: {}", e))?;
+
     let callback = |_editor: &mut Editor, _compositor: &mut Compositor, _response: Value| {
         // if let Err(e) = result {
         //     editor.set_error(format!("Failed {} target: {}", template.request, e));
@@ -212,22 +236,42 @@ pub fn dap_start_impl(
         }
     };
 
-    match &template.request[..] {
-        "launch" => {
-            let call = debugger.launch(args);
-            dap_callback(cx.jobs, call, callback);
-        }
-        "attach" => {
-            let call = debugger.attach(args);
-            dap_callback(cx.jobs, call, callback);
-        }
-        request => bail!("Unsupported request '{}'", request),
-    };
+    if request == "launch" {
+        let call = debugger.launch(args);
+        dap_callback(cx.jobs, call, callback);
+    } else {
+        let call = debugger.attach(args);
+        dap_callback(cx.jobs, call, callback);
+    }
 
     // TODO: either await "initialized" or buffer commands until event is received
     Ok(())
 }
 
+/// A debug configuration offered by `dap_launch`.
+enum DebugConfiguration {
+    /// A template of the debugger of the current language, from `languages.toml`.
+    Template(DebugTemplate),
+    /// A configuration from the `launch.json` of the workspace.
+    LaunchJson(LaunchConfiguration),
+}
+
+impl DebugConfiguration {
+    fn name(&self) -> &str {
+        match self {
+            Self::Template(template) => &template.name,
+            Self::LaunchJson(configuration) => &configuration.name,
+        }
+    }
+
+    fn source(&self) -> &'static str {
+        match self {
+            Self::Template(_) => "languages.toml",
+            Self::LaunchJson(_) => "launch.json",
+        }
+    }
+}
+
 pub fn dap_launch(cx: &mut Context) {
     // TODO: Now that we support multiple Clients, we could run multiple debuggers at once but for now keep this as is
     if cx.editor.debug_adapters.get_active_client().is_some() {
@@ -237,31 +281,56 @@ pub fn dap_launch(cx: &mut Context) {
 
     let doc = doc!(cx.editor);
 
-    let config = match doc
+    let mut configurations: Vec<_> = doc
         .language_config()
         .and_then(|config| config.debugger.as_ref())
-    {
-        Some(c) => c,
-        None => {
+        .map(|config| config.templates.clone())
+        .unwrap_or_default()
+        .into_iter()
+        .map(DebugConfiguration::Template)
+        .collect();
+
+    match launch_json::load(&helix_loader::find_workspace().0) {
+        Ok(launch_configurations) => configurations.extend(
+            launch_configurations
+                .into_iter()
+                .map(DebugConfiguration::LaunchJson),
+        ),
+        Err(err) => cx.editor.set_error(format!("{err:#}")),
+    }
+
+    if configurations.is_empty() {
+        if !cx.editor.is_err() {
             cx.editor
                 .set_error("No debug adapter available for language");
-            return;
         }
-    };
-
-    let templates = config.templates.clone();
+        return;
+    }
 
-    let columns = [ui::PickerColumn::new(
-        "template",
-        |item: &DebugTemplate, _| item.name.as_str().into(),
-    )];
+    let columns = [
+        ui::PickerColumn::new("configuration", |item: &DebugConfiguration, _| {
+            item.name().into()
+        }),
+        ui::PickerColumn::new("source", |item: &DebugConfiguration, _| {
+            item.source().into()
+        }),
+    ];
 
     cx.push_layer(Box::new(overlaid(Picker::new(
         columns,
         0,
-        templates,
+        configurations,
         (),
-        |cx, template, _action| {
+        |cx, configuration, _action| {
+            let template = match configuration {
+                DebugConfiguration::Template(template) => template,
+                DebugConfiguration::LaunchJson(configuration) => {
+                    if let Err(err) = dap_start_launch_json(cx, configuration) {
+                        cx.editor.set_error(err.to_string());
+                    }
+                    return;
+                }
+            };
             if template.completion.is_empty() {
                 if let Err(err) = dap_start_impl(cx, Some(&template.name), None, None) {
                     cx.editor.set_error(err.to_string());
@@ -283,6 +352,62 @@ pub fn dap_launch(cx: &mut Context) {
     ))));
 }
 
+/// Starts a debug session for a configuration of `launch.json`, with the debug adapter
+/// configured for its `type`.
+fn dap_start_launch_json(
+    cx: &mut compositor::Context,
+    configuration: &LaunchConfiguration,
+) -> Result<(), anyhow::Error> {
+    let loader = cx.editor.syn_loader.load();
+    let adapters: Vec<_> = loader
+        .language_configs()
+        .filter_map(|config| config.debugger.as_ref())
+        .collect();
+    let config = configuration
+        .adapter(&adapters)
+        .ok_or_else(|| {
+            anyhow!(
+                "No debug adapter configured for type '{}' of '{}'",
+                configuration.ty,
+                configuration.name
+            )
+        })?
+        .clone();
+    drop(loader);
+
+    let (view, doc) = current_ref!(cx.editor);
+    let text = doc.text().slice(..);
+    let range = doc.selection(view.id).primary();
+    let workspace = helix_loader::find_workspace().0;
+    let variables = launch_json::Variables {
+        workspace_folder: workspace.clone(),
+        cwd: helix_stdx::env::current_working_dir(),
+        file: doc.path().cloned(),
+        line_number: Some(range.cursor_line(text) + 1),
+        selected_text: (range.len() > 1).then(|| range.fragment(text).into()),
+    };
+    let mut args = configuration.args.clone();
+    args.entry("cwd")
+        .or_insert_with(|| to_value(&workspace).unwrap());
+    let mut args = Value::Object(args);
+    variables.substitute(&mut args)?;
+
+    let tasks: Vec<_> = configuration
+        .tasks()
+        .map(|(key, task)| format!("{key} '{task}'"))
+        .collect();
+
+    start_debug_session(cx, None, &config, &configuration.request, args)?;
+
+    if !tasks.is_empty() {
+        cx.editor.set_error(format!(
+            "Tasks are not supported: {} will not be run",
+            tasks.join(" and ")
+        ));
+    }
+    Ok(())
+}
+
 pub fn dap_restart(cx: &mut Context) {
     let debugger = match cx.editor.debug_adapters.get_active_client() {
         Some(debugger) => debugger,
@@ -417,12 +542,18 @@ pub fn dap_toggle_breakpoint_impl(cx: &mut Context, path: PathBuf, line: usize)
         });
     }
 
-    let debugger = debugger!(cx.editor);
+    line_breakpoints_changed(cx.editor, path);
+}
 
-    if let Err(e) = breakpoints_changed(debugger, path, breakpoints) {
-        cx.editor
-            .set_error(format!("Failed to set breakpoints: {}", e));
+/// Sends the breakpoints of `path` to the debugger, if one is running, and saves them.
+fn line_breakpoints_changed(editor: &mut Editor, path: PathBuf) {
+    if let Some(debugger) = editor.debug_adapters.get_active_client_mut() {
+        let breakpoints = editor.breakpoints.entry(path.clone()).or_default();
+        if let Err(e) = breakpoints_changed(debugger, path, breakpoints) {
+            editor.set_error(format!("Failed to set breakpoints: {}", e));
+        }
     }
+    editor.save_breakpoints();
 }
 
 pub fn dap_continue(cx: &mut Context) {
@@ -498,6 +629,23 @@ pub fn dap_next(cx: &mut Context) {
     }
 }
 
+pub fn dap_console(cx: &mut Context) {
+    cx.push_layer(Box::new(ui::DebugConsole::new()));
+}
+
+pub fn dap_sidebar(cx: &mut Context) {
+    cx.callback.push(Box::new(
+        |compositor: &mut Compositor, cx: &mut compositor::Context| {
+            if let Some(sidebar) = compositor.find_id::<ui::DebugSidebar>(ui::debug_sidebar::ID) {
+                sidebar.focus();
+                return;
+            }
+            cx.editor.sidebar_width = ui::DebugSidebar::width(cx.editor.tree.area().width);
+            compositor.push(Box::new(ui::DebugSidebar::new()));
+        },
+    ));
+}
+
 pub fn dap_variables(cx: &mut Context) {
     let debugger = debugger!(cx.editor);
 
@@ -631,8 +779,13 @@ pub fn dap_disable_exceptions(cx: &mut Context) {
 }
 
 // TODO: both edit condition and edit log need to be stable: we might get new breakpoints from the debugger which can change offsets
-pub fn dap_edit_condition(cx: &mut Context) {
-    if let Some((pos, breakpoint)) = get_breakpoint_at_current_line(cx.editor) {
+/// Prompts for a new value of the `field` of the breakpoint on the current line.
+fn edit_breakpoint(
+    cx: &mut Context,
+    prompt: &'static str,
+    field: fn(&mut Breakpoint) -> &mut Option<String>,
+) {
+    if let Some((pos, mut breakpoint)) = get_breakpoint_at_current_line(cx.editor) {
         let path = match doc!(cx.editor).path() {
             Some(path) => path.clone(),
             None => return,
@@ -640,7 +793,7 @@ pub fn dap_edit_condition(cx: &mut Context) {
         let callback = Box::pin(async move {
             let call: Callback = Callback::EditorCompositor(Box::new(move |editor, compositor| {
                 let mut prompt = Prompt::new(
-                    "condition:".into(),
+                    prompt.into(),
                     None,
                     ui::completers::none,
                     move |cx, input: &str, event: PromptEvent| {
@@ -648,22 +801,16 @@ pub fn dap_edit_condition(cx: &mut Context) {
                             return;
                         }
 
-                        let breakpoints = &mut cx.editor.breakpoints.get_mut(&path).unwrap();
-                        breakpoints[pos].condition = match input {
+                        let breakpoints = cx.editor.breakpoints.get_mut(&path).unwrap();
+                        *field(&mut breakpoints[pos]) = match input {
                             "" => None,
                             input => Some(input.to_owned()),
                         };
-
-                        let debugger = debugger!(cx.editor);
-
-                        if let Err(e) = breakpoints_changed(debugger, path.clone(), breakpoints) {
-                            cx.editor
-                                .set_error(format!("Failed to set breakpoints: {}", e));
-                        }
+                        line_breakpoints_changed(cx.editor, path.clone());
                     },
                 );
-                if let Some(condition) = breakpoint.condition {
-                    prompt.insert_str(&condition, editor)
+                if let Some(value) = field(&mut breakpoint) {
+                    prompt.insert_str(value, editor);
                 }
                 compositor.push(Box::new(prompt));
             }));
@@ -673,50 +820,25 @@ pub fn dap_edit_condition(cx: &mut Context) {
     }
 }
 
-pub fn dap_edit_log(cx: &mut Context) {
-    if let Some((pos, breakpoint)) = get_breakpoint_at_current_line(cx.editor) {
-        let path = match doc!(cx.editor).path() {
-            Some(path) => path.clone(),
-            None => return,
-        };
-        let callback = Box::pin(async move {
-            let call: Callback = Callback::EditorCompositor(Box::new(move |editor, compositor| {
-                let mut prompt = Prompt::new(
-                    "log-message:".into(),
-                    None,
-                    ui::completers::none,
-                    move |cx, input: &str, event: PromptEvent| {
-                        if event != PromptEvent::Validate {
-                            return;
-                        }
+pub fn dap_edit_condition(cx: &mut Context) {
+    edit_breakpoint(cx, "condition:", |breakpoint| &mut breakpoint.condition);
+}
 
-                        let breakpoints = &mut cx.editor.breakpoints.get_mut(&path).unwrap();
-                        breakpoints[pos].log_message = match input {
-                            "" => None,
-                            input => Some(input.to_owned()),
-                        };
+pub fn dap_edit_hit_condition(cx: &mut Context) {
+    edit_breakpoint(cx, "hit-condition:", |breakpoint| {
+        &mut breakpoint.hit_condition
+    });
+}
 
-                        let debugger = debugger!(cx.editor);
-                        if let Err(e) = breakpoints_changed(debugger, path.clone(), breakpoints) {
-                            cx.editor
-                                .set_error(format!("Failed to set breakpoints: {}", e));
-                        }
-                    },
-                );
-                if let Some(log_message) = breakpoint.log_message {
-                    prompt.insert_str(&log_message, editor);
-                }
-                compositor.push(Box::new(prompt));
-            }));
-            Ok(call)
-        });
-        cx.jobs.callback(callback);
-    }
+pub fn dap_edit_log(cx: &mut Context) {
+    edit_breakpoint(cx, "log-message:", |breakpoint| &mut breakpoint.log_message);
 }
 
 pub fn dap_switch_thread(cx: &mut Context) {
     thread_picker(cx, |editor, thread| {
         block_on(select_thread_id(editor, thread.id, true));
+        fetch_inline_values(editor);
+        fetch_variables(editor);
     })
 }
 pub fn dap_switch_stack_frame(cx: &mut Context) {
@@ -748,6 +870,8 @@ pub fn dap_switch_stack_frame(cx: &mut Context) {
             .cloned();
         if let Some(frame) = &frame {
             jump_to_stack_frame(cx.editor, frame);
+            fetch_inline_values(cx.editor);
+            fetch_variables(cx.editor);
         }
     })
     .with_preview(move |_editor, frame| {
@@ -767,3 +891,389 @@ pub fn dap_switch_stack_frame(cx: &mut Context) {
     });
     cx.push_layer(Box::new(picker))
 }
+
+/// A breakpoint of any kind listed by `dap_breakpoint_picker`.
+#[derive(Clone)]
+enum BreakpointItem {
+    Source {
+        path: PathBuf,
+        breakpoint: Breakpoint,
+    },
+    Function(FunctionBreakpoint),
+    Data(DataBreakpoint),
+    Instruction(InstructionBreakpoint),
+}
+
+impl BreakpointItem {
+    /// All the breakpoints of the editor, the line breakpoints first.
+    fn all(editor: &Editor) -> Vec<Self> {
+        // The line breakpoints are stored by path in no particular order.
+        let mut line_breakpoints: Vec<_> = editor
+            .breakpoints
+            .iter()
+            .flat_map(|(path, breakpoints)| breakpoints.iter().map(move |b| (path, b)))
+            .collect();
+        line_breakpoints.sort_by_key(|(path, breakpoint)| (*path, breakpoint.line));
+
+        line_breakpoints
+            .into_iter()
+            .map(|(path, breakpoint)| Self::Source {
+                path: path.clone(),
+                breakpoint: breakpoint.clone(),
+            })
+            .chain(
+                editor
+                    .function_breakpoints
+                    .iter()
+                    .cloned()
+                    .map(Self::Function),
+            )
+            .chain(editor.data_breakpoints.iter().cloned().map(Self::Data))
+            .chain(
+                editor
+                    .instruction_breakpoints
+                    .iter()
+                    .cloned()
+                    .map(Self::Instruction),
+            )
+            .collect()
+    }
+
+    fn kind(&self) -> &'static str {
+        match self {
+            Self::Source { .. } => "line",
+            Self::Function(_) => "function",
+            Self::Data(_) => "data",
+            Self::Instruction(_) => "instruction",
+        }
+    }
+
+    fn target(&self) -> String {
+        match self {
+            Self::Source { path, breakpoint } => {
+                let path = helix_stdx::path::get_relative_path(path);
+                format!("{}:{}", path.display(), breakpoint.line + 1)
+            }
+            Self::Function(breakpoint) => breakpoint.name.clone(),
+            Self::Data(breakpoint) => match breakpoint.access_type {
+                Some(access_type) => format!("{} ({access_type})", breakpoint.description),
+                None => breakpoint.description.clone(),
+            },
+            Self::Instruction(breakpoint) => match breakpoint.offset {
+                Some(offset) => format!("{}{offset:+}", breakpoint.instruction_reference),
+                None => breakpoint.instruction_reference.clone(),
+            },
+        }
+    }
+
+    fn condition(&self) -> Option<&str> {
+        match self {
+            Self::Source { breakpoint, .. } => breakpoint.condition.as_deref(),
+            Self::Function(breakpoint) => breakpoint.condition.as_deref(),
+            Self::Data(breakpoint) => breakpoint.condition.as_deref(),
+            Self::Instruction(breakpoint) => breakpoint.condition.as_deref(),
+        }
+    }
+
+    fn hit_condition(&self) -> Option<&str> {
+        match self {
+            Self::Source { breakpoint, .. } => breakpoint.hit_condition.as_deref(),
+            Self::Function(breakpoint) => breakpoint.hit_condition.as_deref(),
+            Self::Data(breakpoint) => breakpoint.hit_condition.as_deref(),
+            Self::Instruction(breakpoint) => breakpoint.hit_condition.as_deref(),
+        }
+    }
+
+    fn log_message(&self) -> Option<&str> {
+        match self {
+            Self::Source { breakpoint, .. } => breakpoint.log_message.as_deref(),
+            _ => None,
+        }
+    }
+
+    fn conditions(&self) -> String {
+        match (self.condition(), self.hit_condition()) {
+            (Some(condition), Some(hit_condition)) => format!("{condition}, hits {hit_condition}"),
+            (Some(condition), None) => condition.to_string(),
+            (None, Some(hit_condition)) => format!("hits {hit_condition}"),
+            (None, None) => String::new(),
+        }
+    }
+
+    fn state(&self) -> String {
+        let (disabled, verified, message) = match self {
+            Self::Source { breakpoint, .. } => (
+                breakpoint.disabled,
+                breakpoint.verified,
+                &breakpoint.message,
+            ),
+            Self::Function(breakpoint) => (
+                breakpoint.disabled,
+                breakpoint.verified,
+                &breakpoint.message,
+            ),
+            Self::Data(breakpoint) => (
+                breakpoint.disabled,
+                breakpoint.verified,
+                &breakpoint.message,
+            ),
+            Self::Instruction(breakpoint) => (
+                breakpoint.disabled,
+                breakpoint.verified,
+                &breakpoint.message,
+            ),
+        };
+        match message {
+            _ if disabled => "disabled".to_string(),
+            Some(message) => message.clone(),
+            None if verified => "verified".to_string(),
+            None => "pending".to_string(),
+        }
+    }
+}
+
+/// A change made to a breakpoint from the breakpoint picker.
+enum BreakpointChange {
+    Toggle,
+    Delete,
+    Condition(Option<String>),
+    HitCondition(Option<String>),
+    LogMessage(Option<String>),
+}
+
+/// Returns the `disabled`, `condition` and `hit_condition` fields of a breakpoint.
+type BreakpointFields<B> = fn(&mut B) -> (&mut bool, &mut Option<String>, &mut Option<String>);
+
+/// Applies `change` to the breakpoint at `pos`.
+fn apply_breakpoint_change<B>(
+    breakpoints: &mut Vec<B>,
+    pos: Option<usize>,
+    change: BreakpointChange,
+    fields: BreakpointFields<B>,
+) {
+    let Some(pos) = pos else {
+        return;
+    };
+    if let BreakpointChange::Delete = change {
+        breakpoints.remove(pos);
+        return;
+    }
+    let (disabled, condition, hit_condition) = fields(&mut breakpoints[pos]);
+    match change {
+        BreakpointChange::Toggle => *disabled = !*disabled,
+        BreakpointChange::Condition(value) => *condition = value,
+        BreakpointChange::HitCondition(value) => *hit_condition = value,
+        BreakpointChange::Delete | BreakpointChange::LogMessage(_) => (),
+    }
+}
+
+/// Changes the breakpoint of the editor that `item` was created from, sends the breakpoints of
+/// its kind to the debugger and saves them.
+fn change_breakpoint(
+    editor: &mut Editor,
+    item: &BreakpointItem,
+    change: BreakpointChange,
+) -> anyhow::Result<()> {
+    let debugger = editor.debug_adapters.get_active_client_mut();
+    let result = match item {
+        BreakpointItem::Source { path, breakpoint } => {
+            let breakpoints = editor.breakpoints.entry(path.clone()).or_default();
+            let pos = breakpoints.iter().position(|b| b.line == breakpoint.line);
+            match (change, pos) {
+                (BreakpointChange::LogMessage(log_message), Some(pos)) => {
+                    breakpoints[pos].log_message = log_message;
+                }
+                (change, pos) => apply_breakpoint_change(breakpoints, pos, change, |b| {
+                    (&mut b.disabled, &mut b.condition, &mut b.hit_condition)
+                }),
+            }
+            match debugger {
+                Some(debugger) => breakpoints_changed(debugger, path.clone(), breakpoints),
+                None => Ok(()),
+            }
+        }
+        BreakpointItem::Function(breakpoint) => {
+            let breakpoints = &mut editor.function_breakpoints;
+            let pos = breakpoints.iter().position(|b| b.name == breakpoint.name);
+            apply_breakpoint_change(breakpoints, pos, change, |b| {
+                (&mut b.disabled, &mut b.condition, &mut b.hit_condition)
+            });
+            match debugger {
+                Some(debugger) => function_breakpoints_changed(debugger, breakpoints),
+                None => Ok(()),
+            }
+        }
+        BreakpointItem::Data(breakpoint) => {
+            let breakpoints = &mut editor.data_breakpoints;
+            let pos = breakpoints
+                .iter()
+                .position(|b| b.data_id == breakpoint.data_id);
+            apply_breakpoint_change(breakpoints, pos, change, |b| {
+                (&mut b.disabled, &mut b.condition, &mut b.hit_condition)
+            });
+            match debugger {
+                Some(debugger) => data_breakpoints_changed(debugger, breakpoints),
+                None => Ok(()),
+            }
+        }
+        BreakpointItem::Instruction(breakpoint) => {
+            let breakpoints = &mut editor.instruction_breakpoints;
+            let pos = breakpoints.iter().position(|b| {
+                b.instruction_reference == breakpoint.instruction_reference
+                    && b.offset == breakpoint.offset
+            });
+            apply_breakpoint_change(breakpoints, pos, change, |b| {
+                (&mut b.disabled, &mut b.condition, &mut b.hit_condition)
+            });
+            match debugger {
+                Some(debugger) => instruction_breakpoints_changed(debugger, breakpoints),
+                None => Ok(()),
+            }
+        }
+    };
+    editor.save_breakpoints();
+    result
+}
+
+type BreakpointPicker = ui::overlay::Overlay<Picker<BreakpointItem, ()>>;
+
+/// Shows the current breakpoints in the open breakpoint picker.
+fn refresh_breakpoint_picker(jobs: &mut Jobs) {
+    jobs.callback(async {
+        let call: Callback = Callback::EditorCompositor(Box::new(|editor, compositor| {
+            if let Some(overlay) = compositor.find::<BreakpointPicker>() {
+                overlay.content.set_options(BreakpointItem::all(editor));
+            }
+        }));
+        Ok(call)
+    });
+}
+
+fn change_breakpoint_from_picker(
+    cx: &mut compositor::Context,
+    item: &BreakpointItem,
+    change: BreakpointChange,
+) {
+    if let Err(err) = change_breakpoint(cx.editor, item, change) {
+        cx.editor.set_error(err.to_string());
+    }
+    refresh_breakpoint_picker(cx.jobs);
+}
+
+/// Opens a prompt above the breakpoint picker to edit a value of the breakpoint of `item`.
+fn edit_breakpoint_from_picker(
+    cx: &mut compositor::Context,
+    item: &BreakpointItem,
+    prompt: &'static str,
+    value: Option<&str>,
+    change: fn(Option<String>) -> BreakpointChange,
+) {
+    let value = value.unwrap_or_default().to_string();
+    let item = item.clone();
+    cx.jobs.callback(async move {
+        let call: Callback = Callback::EditorCompositor(Box::new(move |editor, compositor| {
+            let prompt = Prompt::new(
+                prompt.into(),
+                None,
+                ui::completers::none,
+                move |cx, input: &str, event: PromptEvent| {
+                    if event != PromptEvent::Validate {
+                        return;
+                    }
+                    let value = match input {
+                        "" => None,
+                        input => Some(input.to_owned()),
+                    };
+                    change_breakpoint_from_picker(cx, &item, change(value));
+                },
+            )
+            .with_line(value, editor);
+            compositor.push(Box::new(prompt));
+        }));
+        Ok(call)
+    });
+}
+
+pub fn dap_breakpoint_picker(cx: &mut Context) {
+    let items = BreakpointItem::all(cx.editor);
+    if items.is_empty() {
+        cx.editor.set_status("No breakpoints");
+        return;
+    }
+
+    let columns = [
+        ui::PickerColumn::new("kind", |item: &BreakpointItem, _| item.kind().into()),
+        ui::PickerColumn::new("target", |item: &BreakpointItem, _| item.target().into()),
+        ui::PickerColumn::new("condition", |item: &BreakpointItem, _| {
+            item.conditions().into()
+        }),
+        ui::PickerColumn::new("log", |item: &BreakpointItem, _| {
+            item.log_message().unwrap_or_default().into()
+        }),
+        ui::PickerColumn::new("state", |item: &BreakpointItem, _| item.state().into()),
+    ];
+    let picker = Picker::new(columns, 1, items, (), |cx, item, action| {
+        // Only line breakpoints have a location to jump to.
+        let BreakpointItem::Source { path, breakpoint } = item else {
+            return;
+        };
+        if let Err(err) = cx.editor.open(path, action) {
+            cx.editor
+                .set_error(format!("Failed to open '{}': {err}", path.display()));
+            return;
+        }
+        let (view, doc) = current!(cx.editor);
+        let line = breakpoint
+            .line
+            .min(doc.text().len_lines().saturating_sub(1));
+        let pos = doc.text().line_to_char(line);
+        doc.set_selection(view.id, helix_core::Selection::point(pos));
+        helix_view::align_view(doc, view, helix_view::Align::Center);
+    })
+    .with_preview(|_editor, item| match item {
+        BreakpointItem::Source { path, breakpoint } => Some((
+            path.as_path().into(),
+            Some((breakpoint.line, breakpoint.line)),
+        )),
+        _ => None,
+    })
+    .with_key_action(alt!('t'), |cx, item| {
+        change_breakpoint_from_picker(cx, item, BreakpointChange::Toggle)
+    })
+    .with_key_action(alt!('x'), |cx, item| {
+        change_breakpoint_from_picker(cx, item, BreakpointChange::Delete)
+    })
+    .with_key_action(alt!('e'), |cx, item| {
+        edit_breakpoint_from_picker(
+            cx,
+            item,
+            "condition:",
+            item.condition(),
+            BreakpointChange::Condition,
+        )
+    })
+    .with_key_action(alt!('h'), |cx, item| {
+        edit_breakpoint_from_picker(
+            cx,
+            item,
+            "hit-condition:",
+            item.hit_condition(),
+            BreakpointChange::HitCondition,
+        )
+    })
+    .with_key_action(alt!('l'), |cx, item| {
+        if !matches!(item, BreakpointItem::Source { .. }) {
+            cx.editor
+                .set_error("Only line breakpoints have a log message");
+            return;
+        }
+        edit_breakpoint_from_picker(
+            cx,
+            item,
+            "log-message:",
+            item.log_message(),
+            BreakpointChange::LogMessage,
+        )
+    });
+    cx.push_layer(Box::new(overlaid(picker)));
+}
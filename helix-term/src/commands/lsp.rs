@@ -1,4 +1,4 @@
-use futures_util::{stream::FuturesOrdered, FutureExt};
+use futures_util::{future::BoxFuture, stream::FuturesOrdered, FutureExt};
 use helix_lsp::{
     block_on,
     lsp::{
@@ -14,25 +14,39 @@
 use super::{align_view, push_jump, Align, Context, Editor};
 
 use helix_core::{
-    diagnostic::DiagnosticProvider, syntax::config::LanguageServerFeature,
-    text_annotations::InlineAnnotation, Selection, Uri,
+    chars::char_is_word,
+    diagnostic::DiagnosticProvider,
+    movement::Direction,
+    syntax::config::{LanguageServerFeature, SelectionRangeProvider},
+    text_annotations::InlineAnnotation,
+    Selection, Tendril, Transaction, Uri,
 };
 use helix_stdx::path;
 use helix_view::{
+    annotations::inline_completion::inline_completion_ghost_text,
     document::{DocumentInlayHints, DocumentInlayHintsId},
     editor::Action,
-    handlers::lsp::SignatureHelpInvoked,
+    handlers::lsp::{
+        workspace_edit_items, workspace_edit_needs_confirmation, InlineCompletionEvent,
+        SignatureHelpInvoked,
+    },
     theme::Style,
-    Document, View,
+    Document, DocumentId, View, ViewId,
 };
 
 use crate::{
     compositor::{self, Compositor},
-    job::Callback,
-    ui::{self, overlay::overlaid, FileLocation, Picker, Popup, PromptEvent},
+    job::{self, Callback},
+    ui::{
+        self,
+        overlay::{overlaid, Overlay},
+        FileLocation, Picker, Popup, PromptEvent,
+    },
 };
 
-use std::{cmp::Ordering, collections::HashSet, fmt::Display, future::Future, path::Path};
+use std::{
+    cmp::Ordering, collections::HashSet, fmt::Display, future::Future, path::Path, sync::Arc,
+};
 
 /// Gets the first language server that is attached to a document which supports a specific feature.
 /// If there is no configured language server that supports the feature, this displays a status message.
@@ -100,19 +114,29 @@ struct PickerDiagnostic {
     diag: lsp::Diagnostic,
 }
 
-fn location_to_file_location(location: &Location) -> Option<FileLocation<'_>> {
-    let path = location.uri.as_path()?;
+fn location_to_file_location<'a>(
+    editor: &Editor,
+    location: &'a Location,
+) -> Option<FileLocation<'a>> {
     let line = Some((
         location.range.start.line as usize,
         location.range.end.line as usize,
     ));
-    Some((path.into(), line))
+    match location.uri.as_path() {
+        Some(path) => Some((path.into(), line)),
+        // Virtual documents can only be previewed once they are open.
+        None => Some((editor.document_by_uri(&location.uri)?.id().into(), line)),
+    }
 }
 
 fn jump_to_location(editor: &mut Editor, location: &Location, action: Action) {
     let (view, doc) = current!(editor);
     push_jump(view, doc);
 
+    if location.uri.is_virtual() {
+        jump_to_virtual_location(editor, location.clone(), action);
+        return;
+    }
     let Some(path) = location.uri.as_path() else {
         let err = format!("unable to convert URI to filepath: {:?}", location.uri);
         editor.set_error(err);
@@ -127,6 +151,65 @@ fn jump_to_location(editor: &mut Editor, location: &Location, action: Action) {
     );
 }
 
+/// Jumps to a location in a document that doesn't exist on disk, e.g. `jdt://...`. Its contents
+/// are requested from the language servers, starting with the ones of the current document.
+fn jump_to_virtual_location(editor: &mut Editor, location: Location, action: Action) {
+    if let Some(doc_id) = editor.document_by_uri(&location.uri).map(|doc| doc.id()) {
+        editor.switch(doc_id, action);
+        select_position(
+            editor,
+            doc_id,
+            location.range,
+            location.offset_encoding,
+            action,
+        );
+        return;
+    }
+    let Ok(url) = location.uri.to_url() else {
+        return;
+    };
+
+    let doc = doc!(editor);
+    let mut language_servers: Vec<Arc<Client>> =
+        editor.language_servers.iter_clients().cloned().collect();
+    language_servers.sort_by_key(|ls| !doc.language_servers().any(|doc_ls| doc_ls.id() == ls.id()));
+
+    tokio::spawn(async move {
+        for language_server in language_servers {
+            let Some(request) = language_server.text_document_content(url.clone()) else {
+                continue;
+            };
+            let text = match request.await {
+                Ok(text) => text,
+                Err(err) => {
+                    log::warn!(
+                        "{} failed to provide the contents of {url}: {err}",
+                        language_server.name()
+                    );
+                    continue;
+                }
+            };
+            let language_server_id = language_server.id();
+            job::dispatch(move |editor, _| {
+                let doc_id = editor.open_virtual(url, text.into(), language_server_id, action);
+                select_position(
+                    editor,
+                    doc_id,
+                    location.range,
+                    location.offset_encoding,
+                    action,
+                );
+            })
+            .await;
+            return;
+        }
+        job::dispatch(move |editor, _| {
+            editor.set_error(format!("No language server provides the contents of {url}"));
+        })
+        .await;
+    });
+}
+
 fn jump_to_position(
     editor: &mut Editor,
     path: &Path,
@@ -134,14 +217,26 @@ fn jump_to_position(
     offset_encoding: OffsetEncoding,
     action: Action,
 ) {
-    let doc = match editor.open(path, action) {
-        Ok(id) => doc_mut!(editor, &id),
+    let doc_id = match editor.open(path, action) {
+        Ok(id) => id,
         Err(err) => {
             let err = format!("failed to open path: {:?}: {:?}", path, err);
             editor.set_error(err);
             return;
         }
     };
+    select_position(editor, doc_id, range, offset_encoding, action);
+}
+
+/// Selects `range` in the document `doc_id` after jumping to it.
+fn select_position(
+    editor: &mut Editor,
+    doc_id: DocumentId,
+    range: lsp::Range,
+    offset_encoding: OffsetEncoding,
+    action: Action,
+) {
+    let doc = doc_mut!(editor, &doc_id);
     let view = view_mut!(editor);
     // TODO: convert inside server
     let new_range = if let Some(new_range) = lsp_range_to_range(doc.text(), range, offset_encoding)
@@ -286,7 +381,7 @@ fn diag_picker(
                         .to_string()
                         .into()
                 } else {
-                    Default::default()
+                    item.location.uri.to_string().into()
                 }
             }),
         );
@@ -305,7 +400,7 @@ fn diag_picker(
                 .immediately_show_diagnostic(doc, view.id);
         },
     )
-    .with_preview(move |_editor, diag| location_to_file_location(&diag.location))
+    .with_preview(move |editor, diag| location_to_file_location(editor, &diag.location))
     .truncate_start(false)
 }
 
@@ -433,7 +528,7 @@ fn nested_to_flat(
                     jump_to_location(cx.editor, &item.location, action);
                 },
             )
-            .with_preview(move |_editor, item| location_to_file_location(&item.location))
+            .with_preview(move |editor, item| location_to_file_location(editor, &item.location))
             .truncate_start(false);
 
             compositor.push(Box::new(overlaid(picker)))
@@ -559,7 +654,7 @@ pub fn workspace_symbol_picker(cx: &mut Context) {
             jump_to_location(cx.editor, &item.location, action);
         },
     )
-    .with_preview(|_editor, item| location_to_file_location(&item.location))
+    .with_preview(|editor, item| location_to_file_location(editor, &item.location))
     .with_dynamic_query(get_symbols, None)
     .truncate_start(false);
 
@@ -800,14 +895,24 @@ pub fn code_action(cx: &mut Context) {
                         let resolved_code_action =
                             resolved_code_action.as_ref().unwrap_or(code_action);
 
-                        if let Some(ref workspace_edit) = resolved_code_action.edit {
-                            let _ = editor.apply_workspace_edit(offset_encoding, workspace_edit);
-                        }
-
                         // if code action provides both edit and command first the edit
                         // should be applied and then the command
-                        if let Some(command) = &code_action.command {
-                            editor.execute_lsp_command(command.clone(), action.language_server_id);
+                        let command = code_action.command.clone();
+                        let language_server_id = action.language_server_id;
+                        let execute_command = move |editor: &mut Editor| {
+                            if let Some(command) = command {
+                                editor.execute_lsp_command(command, language_server_id);
+                            }
+                        };
+
+                        match &resolved_code_action.edit {
+                            Some(workspace_edit) => apply_workspace_edit_with_preview(
+                                editor,
+                                offset_encoding,
+                                workspace_edit,
+                                execute_command,
+                            ),
+                            None => execute_command(editor),
                         }
                     }
                 }
@@ -825,6 +930,123 @@ pub fn code_action(cx: &mut Context) {
     });
 }
 
+struct CodeLensItem {
+    command: lsp::Command,
+    language_server_id: LanguageServerId,
+}
+
+impl ui::menu::Item for CodeLensItem {
+    type Data = ();
+    fn format(&self, _data: &Self::Data) -> Row<'_> {
+        self.command.title.as_str().into()
+    }
+}
+
+/// Whether a workspace edit is shown in a [`ui::WorkspaceEditPreview`] before it is applied.
+pub fn needs_workspace_edit_preview(editor: &Editor, workspace_edit: &lsp::WorkspaceEdit) -> bool {
+    editor.config().lsp.preview_workspace_edits || workspace_edit_needs_confirmation(workspace_edit)
+}
+
+/// Applies a workspace edit, letting the user preview it and select the changes to apply first
+/// if needed. `on_applied` is called unless the user rejected the edit.
+pub fn apply_workspace_edit_with_preview(
+    editor: &mut Editor,
+    offset_encoding: OffsetEncoding,
+    workspace_edit: &lsp::WorkspaceEdit,
+    on_applied: impl FnOnce(&mut Editor) + Send + 'static,
+) {
+    if !needs_workspace_edit_preview(editor, workspace_edit) {
+        let _ = editor.apply_workspace_edit(offset_encoding, workspace_edit);
+        on_applied(editor);
+        return;
+    }
+
+    let preview = ui::WorkspaceEditPreview::new(
+        workspace_edit_items(workspace_edit),
+        offset_encoding,
+        move |editor, res| {
+            if res.is_some() {
+                on_applied(editor);
+            }
+        },
+    );
+    job::dispatch_blocking(move |_editor, compositor| {
+        compositor.push(Box::new(overlaid(preview)));
+    });
+}
+
+pub fn code_lens(cx: &mut Context) {
+    let (view, doc) = current!(cx.editor);
+
+    let cursor_line = doc
+        .selection(view.id)
+        .primary()
+        .cursor_line(doc.text().slice(..)) as u32;
+
+    let mut futures = crate::handlers::code_lens::code_lenses_request(
+        doc,
+        &cx.editor.language_servers,
+        Some(cursor_line),
+    );
+
+    if futures.is_empty() {
+        cx.editor
+            .set_error("No configured language server supports code lenses");
+        return;
+    }
+
+    cx.jobs.callback(async move {
+        let mut lenses = Vec::new();
+
+        while let Some(output) = futures.next().await {
+            match output {
+                Ok(items) => {
+                    lenses.extend(items.into_iter().filter_map(|(lens, language_server_id)| {
+                        let command = lens
+                            .command
+                            .filter(|_| lens.range.start.line == cursor_line)?;
+                        Some(CodeLensItem {
+                            command,
+                            language_server_id,
+                        })
+                    }))
+                }
+                Err(err) => log::error!("while gathering code lenses: {err}"),
+            }
+        }
+
+        let call = move |editor: &mut Editor, compositor: &mut Compositor| {
+            match lenses.len() {
+                0 => editor.set_error("No code lenses available"),
+                1 => {
+                    let lens = lenses.pop().unwrap();
+                    editor.execute_lsp_command(lens.command, lens.language_server_id);
+                }
+                _ => {
+                    let mut menu = ui::Menu::new(lenses, (), move |editor, lens, event| {
+                        if event != PromptEvent::Validate {
+                            return;
+                        }
+
+                        // always present here
+                        let lens = lens.unwrap();
+                        editor.execute_lsp_command(lens.command.clone(), lens.language_server_id);
+                    });
+                    menu.move_down(); // pre-select the first item
+
+                    let popup = Popup::new("code-lens", menu)
+                        .with_scrollbar(false)
+                        .auto_close(true);
+
+                    compositor.replace_or_push("code-lens", popup);
+                }
+            }
+        };
+
+        Ok(Callback::EditorCompositor(Box::new(call)))
+    });
+}
+
 #[derive(Debug)]
 pub struct ApplyEditError {
     pub kind: ApplyEditErrorKind,
@@ -878,7 +1100,7 @@ fn goto_impl(editor: &mut Editor, compositor: &mut Compositor, locations: Vec<Lo
             let picker = Picker::new(columns, 0, locations, cwdir, |cx, location, action| {
                 jump_to_location(cx.editor, location, action)
             })
-            .with_preview(|_editor, location| location_to_file_location(location));
+            .with_preview(location_to_file_location);
             compositor.push(Box::new(overlaid(picker)));
         }
     }
@@ -1027,6 +1249,520 @@ pub fn goto_reference(cx: &mut Context) {
     });
 }
 
+/// An item of a call or type hierarchy.
+trait HierarchyItem: Clone + Send + Sync + 'static {
+    fn kind(&self) -> lsp::SymbolKind;
+    fn name(&self) -> &str;
+    fn detail(&self) -> Option<&str>;
+    fn uri(&self) -> &lsp::Url;
+}
+
+impl HierarchyItem for lsp::CallHierarchyItem {
+    fn kind(&self) -> lsp::SymbolKind {
+        self.kind
+    }
+
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn detail(&self) -> Option<&str> {
+        self.detail.as_deref()
+    }
+
+    fn uri(&self) -> &lsp::Url {
+        &self.uri
+    }
+}
+
+impl HierarchyItem for lsp::TypeHierarchyItem {
+    fn kind(&self) -> lsp::SymbolKind {
+        self.kind
+    }
+
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn detail(&self) -> Option<&str> {
+        self.detail.as_deref()
+    }
+
+    fn uri(&self) -> &lsp::Url {
+        &self.uri
+    }
+}
+
+/// An item listed in a call or type hierarchy picker.
+struct HierarchyEntry<I> {
+    item: I,
+    /// Where to jump to: the call site for incoming calls and the item itself otherwise.
+    location: Location,
+    language_server_id: LanguageServerId,
+}
+
+type HierarchyPicker<I> = Overlay<Picker<HierarchyEntry<I>, ()>>;
+
+/// Opens a picker listing `entries`, or reports `empty_error` if there are none.
+///
+/// `expand` requests the entries of the selected item and opens a new picker on top of this
+/// one, so that closing it goes back up the hierarchy.
+fn hierarchy_picker_callback<I: HierarchyItem>(
+    entries: Vec<HierarchyEntry<I>>,
+    empty_error: String,
+    expand: impl Fn(Arc<Client>, I) -> BoxFuture<'static, anyhow::Result<Callback>> + Send + 'static,
+) -> Callback {
+    let call = move |editor: &mut Editor, compositor: &mut Compositor| {
+        if entries.is_empty() {
+            editor.set_error(empty_error);
+            return;
+        }
+
+        let columns = [
+            ui::PickerColumn::new("kind", |entry: &HierarchyEntry<I>, _| {
+                display_symbol_kind(entry.item.kind()).into()
+            }),
+            ui::PickerColumn::new("name", |entry: &HierarchyEntry<I>, _| {
+                entry.item.name().into()
+            }),
+            ui::PickerColumn::new("detail", |entry: &HierarchyEntry<I>, _| {
+                entry.item.detail().unwrap_or_default().into()
+            }),
+            ui::PickerColumn::new("path", |entry: &HierarchyEntry<I>, _| {
+                let line = entry.location.range.start.line + 1;
+                if let Some(path) = entry.location.uri.as_path() {
+                    let path = path::get_relative_path(path);
+                    format!("{}:{line}", path.to_string_lossy()).into()
+                } else {
+                    format!("{}:{line}", entry.item.uri()).into()
+                }
+            }),
+        ];
+
+        let picker = Picker::new(
+            columns,
+            1, // name column
+            entries,
+            (),
+            move |cx, entry, action| {
+                jump_to_location(cx.editor, &entry.location, action);
+                // Close the pickers of the items above this one in the hierarchy as well.
+                cx.jobs.callback(async {
+                    let call: Callback =
+                        Callback::EditorCompositor(Box::new(|_editor, compositor| {
+                            compositor.remove_type::<HierarchyPicker<I>>();
+                        }));
+                    Ok(call)
+                });
+            },
+        )
+        .with_preview(move |editor, entry| location_to_file_location(editor, &entry.location))
+        .with_expand(move |cx, entry| {
+            let Some(language_server) = cx
+                .editor
+                .language_servers
+                .get_by_id(entry.language_server_id)
+                .cloned()
+            else {
+                cx.editor
+                    .set_error("The language server of the hierarchy has exited");
+                return;
+            };
+            cx.jobs
+                .callback(expand(language_server, entry.item.clone()));
+        })
+        .truncate_start(false);
+
+        compositor.push(Box::new(overlaid(picker)))
+    };
+    Callback::EditorCompositor(Box::new(call))
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum CallHierarchyDirection {
+    Incoming,
+    Outgoing,
+}
+
+/// Requests the callers or callees of `item` and opens a picker listing them.
+fn call_hierarchy_callback(
+    language_server: Arc<Client>,
+    direction: CallHierarchyDirection,
+    item: lsp::CallHierarchyItem,
+) -> BoxFuture<'static, anyhow::Result<Callback>> {
+    async move {
+        let name = item.name.clone();
+        let unsupported =
+            || anyhow::anyhow!("The language server does not support call hierarchies");
+        let calls: Vec<_> = match direction {
+            CallHierarchyDirection::Incoming => language_server
+                .call_hierarchy_incoming_calls(item)
+                .ok_or_else(unsupported)?
+                .await?
+                .unwrap_or_default()
+                .into_iter()
+                .map(|call| {
+                    let range = call
+                        .from_ranges
+                        .first()
+                        .copied()
+                        .unwrap_or(call.from.selection_range);
+                    (call.from, range)
+                })
+                .collect(),
+            CallHierarchyDirection::Outgoing => language_server
+                .call_hierarchy_outgoing_calls(item)
+                .ok_or_else(unsupported)?
+                .await?
+                .unwrap_or_default()
+                .into_iter()
+                .map(|call| {
+                    let range = call.to.selection_range;
+                    (call.to, range)
+                })
+                .collect(),
+        };
+
+        let offset_encoding = language_server.offset_encoding();
+        let language_server_id = language_server.id();
+        let entries: Vec<_> = calls
+            .into_iter()
+            .filter_map(|(item, range)| {
+                let location = lsp_location_to_location(
+                    lsp::Location::new(item.uri.clone(), range),
+                    offset_encoding,
+                )?;
+                Some(HierarchyEntry {
+                    item,
+                    location,
+                    language_server_id,
+                })
+            })
+            .collect();
+
+        let empty_error = match direction {
+            CallHierarchyDirection::Incoming => format!("No calls to '{name}' found."),
+            CallHierarchyDirection::Outgoing => format!("No calls from '{name}' found."),
+        };
+        Ok(hierarchy_picker_callback(
+            entries,
+            empty_error,
+            move |language_server, item| call_hierarchy_callback(language_server, direction, item),
+        ))
+    }
+    .boxed()
+}
+
+fn call_hierarchy(cx: &mut Context, direction: CallHierarchyDirection) {
+    let (view, doc) = current_ref!(cx.editor);
+    let language_server =
+        language_server_with_feature!(cx.editor, doc, LanguageServerFeature::CallHierarchy);
+    let pos = doc.position(view.id, language_server.offset_encoding());
+    let future = language_server
+        .prepare_call_hierarchy(doc.identifier(), pos, None)
+        .unwrap();
+    let Some(language_server) = cx
+        .editor
+        .language_servers
+        .get_by_id(language_server.id())
+        .cloned()
+    else {
+        return;
+    };
+
+    cx.jobs.callback(async move {
+        let item = future.await?.and_then(|items| items.into_iter().next());
+        let Some(item) = item else {
+            let call = |editor: &mut Editor| {
+                editor.set_error("No call hierarchy item found at the cursor.");
+            };
+            return Ok(Callback::Editor(Box::new(call)));
+        };
+        call_hierarchy_callback(language_server, direction, item).await
+    });
+}
+
+pub fn incoming_calls(cx: &mut Context) {
+    call_hierarchy(cx, CallHierarchyDirection::Incoming);
+}
+
+pub fn outgoing_calls(cx: &mut Context) {
+    call_hierarchy(cx, CallHierarchyDirection::Outgoing);
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum TypeHierarchyDirection {
+    Supertypes,
+    Subtypes,
+}
+
+/// Requests the supertypes or subtypes of `item` and opens a picker listing them.
+fn type_hierarchy_callback(
+    language_server: Arc<Client>,
+    direction: TypeHierarchyDirection,
+    item: lsp::TypeHierarchyItem,
+) -> BoxFuture<'static, anyhow::Result<Callback>> {
+    async move {
+        let name = item.name.clone();
+        let future = match direction {
+            TypeHierarchyDirection::Supertypes => language_server
+                .type_hierarchy_supertypes(item)
+                .map(FutureExt::boxed),
+            TypeHierarchyDirection::Subtypes => language_server
+                .type_hierarchy_subtypes(item)
+                .map(FutureExt::boxed),
+        };
+        let items = future
+            .ok_or_else(|| {
+                anyhow::anyhow!("The language server does not support type hierarchies")
+            })?
+            .await?;
+
+        let offset_encoding = language_server.offset_encoding();
+        let language_server_id = language_server.id();
+        let entries: Vec<_> = items
+            .unwrap_or_default()
+            .into_iter()
+            .filter_map(|item| {
+                let location = lsp_location_to_location(
+                    lsp::Location::new(item.uri.clone(), item.selection_range),
+                    offset_encoding,
+                )?;
+                Some(HierarchyEntry {
+                    item,
+                    location,
+                    language_server_id,
+                })
+            })
+            .collect();
+
+        let empty_error = match direction {
+            TypeHierarchyDirection::Supertypes => format!("No supertypes of '{name}' found."),
+            TypeHierarchyDirection::Subtypes => format!("No subtypes of '{name}' found."),
+        };
+        Ok(hierarchy_picker_callback(
+            entries,
+            empty_error,
+            move |language_server, item| type_hierarchy_callback(language_server, direction, item),
+        ))
+    }
+    .boxed()
+}
+
+fn type_hierarchy(cx: &mut Context, direction: TypeHierarchyDirection) {
+    let (view, doc) = current_ref!(cx.editor);
+    let language_server =
+        language_server_with_feature!(cx.editor, doc, LanguageServerFeature::TypeHierarchy);
+    let pos = doc.position(view.id, language_server.offset_encoding());
+    let future = language_server
+        .prepare_type_hierarchy(doc.identifier(), pos, None)
+        .unwrap();
+    let Some(language_server) = cx
+        .editor
+        .language_servers
+        .get_by_id(language_server.id())
+        .cloned()
+    else {
+        return;
+    };
+
+    cx.jobs.callback(async move {
+        let item = future.await?.and_then(|items| items.into_iter().next());
+        let Some(item) = item else {
+            let call = |editor: &mut Editor| {
+                editor.set_error("No type hierarchy item found at the cursor.");
+            };
+            return Ok(Callback::Editor(Box::new(call)));
+        };
+        type_hierarchy_callback(language_server, direction, item).await
+    });
+}
+
+pub fn supertypes(cx: &mut Context) {
+    type_hierarchy(cx, TypeHierarchyDirection::Supertypes);
+}
+
+pub fn subtypes(cx: &mut Context) {
+    type_hierarchy(cx, TypeHierarchyDirection::Subtypes);
+}
+
+/// Expands or shrinks the selections of the current view with `textDocument/selectionRange`.
+///
+/// Returns `false` if the language is configured to use the syntax tree instead. With
+/// `lsp-first` the syntax tree is also used when the language server returns no ranges.
+pub fn selection_range_motion(editor: &mut Editor, direction: Direction) -> bool {
+    let (view, doc) = current_ref!(editor);
+    let provider = doc
+        .language_config()
+        .map(|config| config.selection_range_provider)
+        .unwrap_or_default();
+    if provider == SelectionRangeProvider::TreeSitter {
+        return false;
+    }
+    let Some(language_server) = doc
+        .language_servers_with_feature(LanguageServerFeature::SelectionRange)
+        .next()
+    else {
+        if provider == SelectionRangeProvider::LspFirst {
+            return false;
+        }
+        editor.set_error(format!(
+            "No configured language server supports {}",
+            LanguageServerFeature::SelectionRange
+        ));
+        return true;
+    };
+
+    let offset_encoding = language_server.offset_encoding();
+    let selection = doc.selection(view.id).clone();
+    let positions = selection
+        .iter()
+        .map(|range| helix_lsp::util::pos_to_lsp_pos(doc.text(), range.from(), offset_encoding))
+        .collect();
+    let future = language_server
+        .text_document_selection_range(doc.identifier(), positions, None)
+        .unwrap();
+    let request = SelectionRangeRequest {
+        view_id: view.id,
+        doc_id: doc.id(),
+        version: doc.version(),
+        selection,
+        offset_encoding,
+        fallback: provider == SelectionRangeProvider::LspFirst,
+    };
+
+    tokio::spawn(async move {
+        let ranges = match future.await {
+            Ok(ranges) => ranges,
+            Err(err) => {
+                log::error!("selection range request failed: {err}");
+                None
+            }
+        };
+        job::dispatch(move |editor, _| apply_selection_ranges(editor, request, ranges, direction))
+            .await;
+    });
+    true
+}
+
+/// The state of the document when selection ranges were requested.
+struct SelectionRangeRequest {
+    view_id: ViewId,
+    doc_id: DocumentId,
+    version: i32,
+    selection: Selection,
+    offset_encoding: OffsetEncoding,
+    /// Whether to use the syntax tree if the language server doesn't return any ranges.
+    fallback: bool,
+}
+
+/// How to change the selections with the response to a selection range request.
+#[derive(Debug, PartialEq, Eq)]
+enum SelectionRangeChange {
+    Select(Selection),
+    /// Expand or shrink the selections with the syntax tree instead.
+    SyntaxTree,
+    /// The document or the selections changed in the meantime or no range encloses the
+    /// selections.
+    Unchanged,
+}
+
+fn selection_range_change(
+    doc: &Document,
+    request: &SelectionRangeRequest,
+    ranges: Option<Vec<lsp::SelectionRange>>,
+    direction: Direction,
+) -> SelectionRangeChange {
+    let Some(selection) = doc.selections().get(&request.view_id) else {
+        return SelectionRangeChange::Unchanged;
+    };
+    if doc.version() != request.version {
+        return SelectionRangeChange::Unchanged;
+    }
+    let ranges = match ranges {
+        Some(ranges) if !ranges.is_empty() && ranges.len() == request.selection.len() => ranges,
+        _ if request.fallback && *selection == request.selection => {
+            return SelectionRangeChange::SyntaxTree
+        }
+        _ => return SelectionRangeChange::Unchanged,
+    };
+    if selection.len() != request.selection.len() {
+        return SelectionRangeChange::Unchanged;
+    }
+
+    let text = doc.text();
+    let mut new_ranges = Vec::with_capacity(selection.len());
+    for ((requested, &range), selection_range) in
+        request.selection.iter().zip(selection.iter()).zip(ranges)
+    {
+        // The ranges enclosing the position, from the innermost to the outermost.
+        let mut candidates = Vec::new();
+        let mut next = Some(Box::new(selection_range));
+        while let Some(selection_range) = next {
+            candidates.extend(
+                lsp_range_to_range(text, selection_range.range, request.offset_encoding)
+                    .map(|candidate| (candidate.from(), candidate.to())),
+            );
+            next = selection_range.parent;
+        }
+        // Presses repeated before the response arrived already moved the selection along the
+        // ranges. Continue from there, but drop the response if the selection moved elsewhere.
+        let span = (range.from(), range.to());
+        if span != (requested.from(), requested.to()) && !candidates.contains(&span) {
+            return SelectionRangeChange::Unchanged;
+        }
+        let contains = |outer: (usize, usize), inner: (usize, usize)| {
+            outer.0 <= inner.0 && inner.1 <= outer.1 && outer != inner
+        };
+        let candidate = match direction {
+            Direction::Forward => candidates
+                .into_iter()
+                .find(|&candidate| contains(candidate, span)),
+            Direction::Backward => candidates
+                .into_iter()
+                .rev()
+                .find(|&candidate| contains(span, candidate)),
+        };
+        new_ranges.push(candidate.map_or(range, |(from, to)| {
+            helix_core::Range::new(from, to).with_direction(range.direction())
+        }));
+    }
+    let new_selection = Selection::new(new_ranges.into(), selection.primary_index());
+    if new_selection == *selection {
+        return SelectionRangeChange::Unchanged;
+    }
+    SelectionRangeChange::Select(new_selection)
+}
+
+fn apply_selection_ranges(
+    editor: &mut Editor,
+    request: SelectionRangeRequest,
+    ranges: Option<Vec<lsp::SelectionRange>>,
+    direction: Direction,
+) {
+    let Some(doc) = editor.documents.get_mut(&request.doc_id) else {
+        return;
+    };
+    if !editor.tree.contains(request.view_id) {
+        return;
+    }
+    match selection_range_change(doc, &request, ranges, direction) {
+        SelectionRangeChange::Select(selection) => {
+            if direction == Direction::Forward {
+                // save the current selection so it can be restored using shrink_selection
+                let view = editor.tree.get_mut(request.view_id);
+                view.object_selections
+                    .push(doc.selection(request.view_id).clone());
+            }
+            doc.set_selection(request.view_id, selection);
+        }
+        SelectionRangeChange::SyntaxTree if editor.tree.focus == request.view_id => {
+            super::syntax_selection_motion(editor, direction);
+        }
+        SelectionRangeChange::SyntaxTree | SelectionRangeChange::Unchanged => (),
+    }
+}
+
 pub fn signature_help(cx: &mut Context) {
     cx.editor
         .handlers
@@ -1160,11 +1896,12 @@ fn create_rename_prompt(
                     .unwrap();
 
                 match block_on(future) {
-                    Ok(edits) => {
-                        let _ = cx
-                            .editor
-                            .apply_workspace_edit(offset_encoding, &edits.unwrap_or_default());
-                    }
+                    Ok(edits) => apply_workspace_edit_with_preview(
+                        cx.editor,
+                        offset_encoding,
+                        &edits.unwrap_or_default(),
+                        |_| (),
+                    ),
                     Err(err) => cx.editor.set_error(err.to_string()),
                 }
             },
@@ -1456,3 +2193,393 @@ fn compute_inlay_hints_for_view(
 
     Some(callback)
 }
+
+pub fn accept_inline_completion(cx: &mut Context) {
+    let (view, doc) = current!(cx.editor);
+    if inline_completion_ghost_text(doc, view.id).is_none() {
+        return;
+    }
+    let Some(mut inline_completion) = doc.inline_completion.take() else {
+        return;
+    };
+    let item = inline_completion.items.swap_remove(inline_completion.index);
+
+    let end = item.range.start + item.text.chars().count();
+    let transaction = Transaction::change(
+        doc.text(),
+        [(item.range.start, item.range.end, Some(item.text.into()))].into_iter(),
+    );
+    let selection = move_primary_cursor(doc.selection(view.id), &transaction, end);
+    doc.apply(&transaction.with_selection(selection), view.id);
+
+    if let Some(command) = item.command {
+        cx.editor
+            .execute_lsp_command(command, inline_completion.language_server_id);
+    }
+}
+
+/// Returns the next word of the text of an inline completion: a run of word characters or of
+/// punctuation including the whitespace before it, or a single line break.
+fn inline_completion_word(ghost_text: &str) -> &str {
+    let start = ghost_text
+        .find(|ch: char| !matches!(ch, ' ' | '\t'))
+        .unwrap_or(ghost_text.len());
+    let rest = &ghost_text[start..];
+    let Some(first) = rest.chars().next() else {
+        return ghost_text;
+    };
+    let len = if rest.starts_with("\r\n") {
+        2
+    } else if first == '\n' {
+        1
+    } else {
+        let is_word = char_is_word(first);
+        rest.find(|ch: char| ch.is_whitespace() || char_is_word(ch) != is_word)
+            .unwrap_or(rest.len())
+    };
+    &ghost_text[..start + len]
+}
+
+pub fn accept_inline_completion_word(cx: &mut Context) {
+    let (view, doc) = current!(cx.editor);
+    let Some((cursor, ghost_text)) = inline_completion_ghost_text(doc, view.id) else {
+        return;
+    };
+    let word = Tendril::from(inline_completion_word(ghost_text));
+
+    let end = cursor + word.chars().count();
+    let transaction = Transaction::insert(doc.text(), &Selection::point(cursor), word);
+    let selection = move_primary_cursor(doc.selection(view.id), &transaction, end);
+    doc.apply(&transaction.with_selection(selection), view.id);
+}
+
+/// Maps `selection` through the insertion of an inline completion and moves the primary cursor,
+/// which the completion was requested for, to the end of the inserted text `end`.
+fn move_primary_cursor(selection: &Selection, transaction: &Transaction, end: usize) -> Selection {
+    let primary_index = selection.primary_index();
+    selection
+        .clone()
+        .map(transaction.changes())
+        .replace(primary_index, helix_core::Range::point(end))
+}
+
+fn cycle_inline_completion(cx: &mut Context, direction: Direction) {
+    let (view, doc) = current!(cx.editor);
+    let text = doc.text().clone();
+    let text = text.slice(..);
+    let cursor = doc.selection(view.id).primary().cursor(text);
+    match &mut doc.inline_completion {
+        Some(inline_completion)
+            if inline_completion.view_id == view.id && inline_completion.items.len() > 1 =>
+        {
+            // Skip the items which don't match the text typed since they were requested.
+            let len = inline_completion.items.len();
+            for _ in 0..len {
+                inline_completion.index = match direction {
+                    Direction::Forward => (inline_completion.index + 1) % len,
+                    Direction::Backward => (inline_completion.index + len - 1) % len,
+                };
+                if inline_completion.ghost_text(text, cursor).is_some() {
+                    break;
+                }
+            }
+        }
+        // Ask the language server for alternatives.
+        _ => {
+            let event = InlineCompletionEvent::Trigger {
+                doc: doc.id(),
+                view: view.id,
+                invoked: true,
+            };
+            helix_event::send_blocking(&cx.editor.handlers.inline_completions, event);
+        }
+    }
+}
+
+pub fn next_inline_completion(cx: &mut Context) {
+    cycle_inline_completion(cx, Direction::Forward)
+}
+
+pub fn prev_inline_completion(cx: &mut Context) {
+    cycle_inline_completion(cx, Direction::Backward)
+}
+
+/// A message exchanged with a language server, listed by [`lsp_traffic_picker`].
+pub struct TrafficItem {
+    pub server: String,
+    pub entry: helix_lsp::TrafficEntry,
+}
+
+/// Lists the JSON-RPC messages exchanged with language servers. The messages can be filtered
+/// by server and method with the picker's column filters, e.g. `%method textDocument`.
+pub fn lsp_traffic_picker(items: Vec<TrafficItem>) -> Picker<TrafficItem, ()> {
+    use helix_lsp::{TrafficDirection, TrafficKind};
+
+    let columns = [
+        ui::PickerColumn::new("time", |item: &TrafficItem, _| {
+            format!("{:.3}s", item.entry.time.as_secs_f64()).into()
+        }),
+        ui::PickerColumn::new("server", |item: &TrafficItem, _| {
+            item.server.as_str().into()
+        }),
+        ui::PickerColumn::new("direction", |item: &TrafficItem, _| {
+            match item.entry.direction {
+                TrafficDirection::Incoming => "<-",
+                TrafficDirection::Outgoing => "->",
+            }
+            .into()
+        }),
+        ui::PickerColumn::new("kind", |item: &TrafficItem, _| {
+            match item.entry.kind {
+                TrafficKind::Request => "request",
+                TrafficKind::Response => "response",
+                TrafficKind::Error => "error",
+                TrafficKind::Notification => "notification",
+            }
+            .into()
+        }),
+        ui::PickerColumn::new("method", |item: &TrafficItem, _| {
+            item.entry.method.as_str().into()
+        }),
+        ui::PickerColumn::new("id", |item: &TrafficItem, _| {
+            item.entry
+                .id
+                .as_ref()
+                .map(|id| id.to_string())
+                .unwrap_or_default()
+                .into()
+        }),
+        ui::PickerColumn::new("duration", |item: &TrafficItem, _| {
+            item.entry
+                .duration
+                .map(|duration| format!("{}ms", duration.as_millis()))
+                .unwrap_or_default()
+                .into()
+        }),
+    ];
+
+    Picker::new(columns, 4, items, (), |cx, item, _action| {
+        // Pretty print the message, the servers usually send it on a single line.
+        let content = serde_json::from_str::<serde_json::Value>(&item.entry.content)
+            .and_then(|value| serde_json::to_string_pretty(&value))
+            .unwrap_or_else(|_| item.entry.content.clone());
+        open_scratch_buffer(cx.editor, content, Some("json"));
+    })
+}
+
+/// Restarts the language servers with the given names, which have to be configured for the
+/// language of the document, and attaches them to the documents using them. Returns the errors
+/// of the servers which failed to start.
+pub fn restart_language_servers(
+    editor: &mut Editor,
+    doc_id: DocumentId,
+    names: &[String],
+) -> Vec<(String, helix_lsp::Error)> {
+    let editor_config = editor.config.load();
+    let doc = doc!(editor, &doc_id);
+    let Some(config) = doc.language.clone() else {
+        return Vec::new();
+    };
+    let doc_path = doc.path().cloned();
+
+    let mut errors = Vec::new();
+    for name in names {
+        if let Some(Err(err)) = editor.language_servers.restart_server(
+            name,
+            &config,
+            doc_path.as_ref(),
+            &editor_config.workspace_lsp_roots,
+            editor_config.lsp.snippets,
+        ) {
+            errors.push((name.clone(), err));
+        }
+    }
+
+    // This collect is needed because refresh_language_server would need to re-borrow editor.
+    let document_ids_to_refresh: Vec<DocumentId> = editor
+        .documents()
+        .filter_map(|doc| match doc.language_config() {
+            Some(config)
+                if config
+                    .language_servers
+                    .iter()
+                    .any(|ls| names.contains(&ls.name)) =>
+            {
+                Some(doc.id())
+            }
+            _ => None,
+        })
+        .collect();
+
+    for document_id in document_ids_to_refresh {
+        editor.refresh_language_servers(document_id);
+    }
+
+    errors
+}
+
+/// Stops the language server with the given name and detaches it from the documents.
+pub fn stop_language_server(editor: &mut Editor, name: &str) {
+    editor.language_servers.stop(name);
+
+    for doc in editor.documents_mut() {
+        if let Some(client) = doc.remove_language_server_by_name(name) {
+            doc.clear_diagnostics_for_language_server(client.id());
+            doc.reset_all_inlay_hints();
+            doc.inlay_hints_oudated = true;
+        }
+    }
+}
+
+/// Opens a new scratch buffer containing `text`.
+pub fn open_scratch_buffer(editor: &mut Editor, text: String, language_id: Option<&str>) {
+    editor.new_file(Action::Replace);
+    let (view, doc) = current!(editor);
+    let transaction = Transaction::insert(doc.text(), doc.selection(view.id), text.into())
+        .with_selection(Selection::point(0));
+    doc.apply(&transaction, view.id);
+    doc.append_changes_to_history(view);
+    doc.reset_modified();
+    if let Some(language_id) = language_id {
+        let loader = editor.syn_loader.load();
+        let (_, doc) = current!(editor);
+        if let Err(err) = doc.set_language_by_language_id(language_id, &loader) {
+            log::warn!("Failed to set the language of the scratch buffer: {err}");
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use arc_swap::ArcSwap;
+    use helix_core::{syntax, Range, Rope};
+    use helix_view::editor::Config;
+
+    use super::*;
+
+    fn doc(text: &str, selection: Range) -> Document {
+        let mut doc = Document::from(
+            Rope::from(text),
+            None,
+            Arc::new(ArcSwap::new(Arc::new(Config::default()))),
+            Arc::new(ArcSwap::from_pointee(syntax::Loader::default())),
+        );
+        doc.set_selection(
+            ViewId::default(),
+            Selection::single(selection.anchor, selection.head),
+        );
+        doc
+    }
+
+    fn request(doc: &Document, fallback: bool) -> SelectionRangeRequest {
+        SelectionRangeRequest {
+            view_id: ViewId::default(),
+            doc_id: doc.id(),
+            version: doc.version(),
+            selection: doc.selection(ViewId::default()).clone(),
+            offset_encoding: OffsetEncoding::Utf8,
+            fallback,
+        }
+    }
+
+    /// A selection range on the first line with the given parents, from the innermost to the
+    /// outermost.
+    fn selection_range(ranges: &[(u32, u32)]) -> lsp::SelectionRange {
+        ranges
+            .iter()
+            .rev()
+            .fold(None, |parent, &(start, end)| {
+                Some(lsp::SelectionRange {
+                    range: lsp::Range::new(
+                        lsp::Position::new(0, start),
+                        lsp::Position::new(0, end),
+                    ),
+                    parent: parent.map(Box::new),
+                })
+            })
+            .unwrap()
+    }
+
+    #[test]
+    fn select_ranges() {
+        let ranges = || {
+            Some(vec![selection_range(&[
+                (13, 16),
+                (9, 17),
+                (7, 19),
+                (0, 19),
+            ])])
+        };
+        let select = |from, to| SelectionRangeChange::Select(Selection::single(from, to));
+        let mut doc = doc("fn f() { foo(bar) }", Range::new(14, 15));
+        let request = request(&doc, true);
+        assert_eq!(
+            selection_range_change(&doc, &request, ranges(), Direction::Forward),
+            select(13, 16)
+        );
+
+        // A press repeated before the response arrived continues from the moved selection.
+        doc.set_selection(ViewId::default(), Selection::single(13, 16));
+        assert_eq!(
+            selection_range_change(&doc, &request, ranges(), Direction::Forward),
+            select(9, 17)
+        );
+        doc.set_selection(ViewId::default(), Selection::single(9, 17));
+        assert_eq!(
+            selection_range_change(&doc, &request, ranges(), Direction::Backward),
+            select(13, 16)
+        );
+
+        // The response is dropped if the selection moved elsewhere or the document changed.
+        doc.set_selection(ViewId::default(), Selection::single(0, 2));
+        assert_eq!(
+            selection_range_change(&doc, &request, ranges(), Direction::Forward),
+            SelectionRangeChange::Unchanged
+        );
+        doc.set_selection(ViewId::default(), Selection::single(14, 15));
+        let stale = SelectionRangeRequest {
+            version: doc.version() + 1,
+            ..request
+        };
+        assert_eq!(
+            selection_range_change(&doc, &stale, ranges(), Direction::Forward),
+            SelectionRangeChange::Unchanged
+        );
+    }
+
+    #[test]
+    fn fall_back_to_syntax_tree() {
+        let mut doc = doc("fn f() { foo(bar) }", Range::new(14, 15));
+
+        // Without a response, an empty one or one not matching the selections, `lsp-first`
+        // uses the syntax tree.
+        let request = request(&doc, true);
+        for ranges in [
+            None,
+            Some(Vec::new()),
+            Some(vec![
+                selection_range(&[(13, 16)]),
+                selection_range(&[(9, 17)]),
+            ]),
+        ] {
+            assert_eq!(
+                selection_range_change(&doc, &request, ranges, Direction::Forward),
+                SelectionRangeChange::SyntaxTree
+            );
+        }
+
+        // `lsp` doesn't fall back.
+        let no_fallback = self::request(&doc, false);
+        assert_eq!(
+            selection_range_change(&doc, &no_fallback, None, Direction::Backward),
+            SelectionRangeChange::Unchanged
+        );
+
+        // Neither does a request whose selection changed in the meantime.
+        doc.set_selection(ViewId::default(), Selection::single(0, 2));
+        assert_eq!(
+            selection_range_change(&doc, &request, None, Direction::Forward),
+            SelectionRangeChange::Unchanged
+        );
+    }
+}
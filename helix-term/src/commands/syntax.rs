@@ -286,6 +286,7 @@ struct SearchState {
             let text = doc.text().slice(..);
             let uri_or_id = doc
                 .uri()
+                .filter(|uri| !uri.is_virtual())
                 .map(UriOrDocumentId::Uri)
                 .unwrap_or_else(|| UriOrDocumentId::Id(doc.id()));
             for tag in tags_iter(syntax, &loader, text.slice(..), uri_or_id, Some(&pattern)) {
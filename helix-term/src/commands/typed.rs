@@ -6,14 +6,22 @@
 
 use super::*;
 
+use futures_util::future::BoxFuture;
 use helix_core::command_line::{Args, Flag, Signature, Token, TokenKind};
 use helix_core::fuzzy::fuzzy_match;
 use helix_core::indent::MAX_INDENT;
 use helix_core::line_ending;
 use helix_stdx::path::home_dir;
 use helix_view::document::{read_to_string, DEFAULT_LANGUAGE_NAME};
-use helix_view::editor::{CloseError, ConfigEvent};
+use helix_view::editor::{
+    CloseError, ConfigEvent, DataBreakpoint, FileOperationEdits, FunctionBreakpoint,
+    InstructionBreakpoint,
+};
 use helix_view::expansion;
+use helix_view::handlers::dap::{
+    data_breakpoints_changed, function_breakpoints_changed, instruction_breakpoints_changed,
+    supports,
+};
 use serde_json::Value;
 use ui::completers::{self, Completer};
 
@@ -360,30 +368,65 @@ fn write_impl(
     doc.append_changes_to_history(view);
 
     let (view, doc) = current_ref!(cx.editor);
+    let doc_id = doc.id();
+    let path = path.map(PathBuf::from);
+    let will_create = cx.editor.will_create_on_save(doc_id, path.as_deref());
     let fmt = if config.auto_format && options.auto_format {
-        doc.auto_format(cx.editor).map(|fmt| {
+        doc.auto_format(cx.editor)
+    } else {
+        None
+    };
+
+    match fmt {
+        Some(fmt) => {
             let callback = make_format_callback(
-                doc.id(),
+                doc_id,
                 doc.version(),
                 view.id,
                 fmt,
-                Some((path.map(Into::into), options.force)),
+                Some((path, options.force)),
+                will_create,
             );
 
             jobs.add(Job::with_callback(callback).wait_before_exiting());
-        })
-    } else {
-        None
-    };
-
-    if fmt.is_none() {
-        let id = doc.id();
-        cx.editor.save(id, path, options.force)?;
+        }
+        None => {
+            after_will_file_operation(cx.editor, jobs, "willCreate", will_create, move |editor| {
+                editor.save(doc_id, path, options.force)
+            })?
+        }
     }
 
     Ok(())
 }
 
+/// Runs the file operation `operation` once the edits language servers returned for its
+/// `workspace/will*Files` request `method` are applied, see [`Editor::will_file_operation`].
+/// The request is awaited in a job so that it doesn't block the UI.
+fn after_will_file_operation(
+    editor: &mut Editor,
+    jobs: &mut Jobs,
+    method: &'static str,
+    request: Option<BoxFuture<'static, FileOperationEdits>>,
+    operation: impl FnOnce(&mut Editor) -> anyhow::Result<()> + Send + 'static,
+) -> anyhow::Result<()> {
+    let Some(request) = request else {
+        return operation(editor);
+    };
+    let callback = async move {
+        let edits = request.await;
+        let call: job::Callback = Callback::Editor(Box::new(move |editor| {
+            editor.apply_file_operation_edits(method, edits);
+            if let Err(err) = operation(editor) {
+                editor.set_error(err.to_string());
+            }
+        }));
+        Ok(call)
+    };
+    jobs.add(Job::with_callback(callback).wait_before_exiting());
+    Ok(())
+}
+
 /// Trim all whitespace preceding line-endings in a document.
 fn trim_trailing_whitespace(doc: &mut Document, view_id: ViewId) {
     let text = doc.text();
@@ -542,7 +585,7 @@ fn format(cx: &mut compositor::Context, _args: Args, event: PromptEvent) -> anyh
     let format = doc.format(cx.editor).context(
         "A formatter isn't available, and no language server provides formatting capabilities",
     )?;
-    let callback = make_format_callback(doc.id(), doc.version(), view.id, format, None);
+    let callback = make_format_callback(doc.id(), doc.version(), view.id, format, None, None);
     cx.jobs.callback(callback);
 
     Ok(())
@@ -820,24 +863,33 @@ pub fn write_all_impl(
         // Save an undo checkpoint for any outstanding changes.
         doc.append_changes_to_history(view);
 
+        let doc = doc!(cx.editor, &doc_id);
+        let will_create = cx.editor.will_create_on_save(doc_id, None);
         let fmt = if options.auto_format && config.auto_format {
-            let doc = doc!(cx.editor, &doc_id);
-            doc.auto_format(cx.editor).map(|fmt| {
+            doc.auto_format(cx.editor)
+        } else {
+            None
+        };
+
+        match fmt {
+            Some(fmt) => {
                 let callback = make_format_callback(
                     doc_id,
                     doc.version(),
                     target_view,
                     fmt,
                     Some((None, options.force)),
+                    will_create,
                 );
                 jobs.add(Job::with_callback(callback).wait_before_exiting());
-            })
-        } else {
-            None
-        };
-
-        if fmt.is_none() {
-            cx.editor.save::<PathBuf>(doc_id, None, options.force)?;
+            }
+            None => after_will_file_operation(
+                cx.editor,
+                jobs,
+                "willCreate",
+                will_create,
+                move |editor| editor.save::<PathBuf>(doc_id, None, options.force),
+            )?,
         }
     }
 
@@ -1590,7 +1642,6 @@ fn lsp_restart(cx: &mut compositor::Context, args: Args, event: PromptEvent) ->
         return Ok(());
     }
 
-    let editor_config = cx.editor.config.load();
     let doc = doc!(cx.editor);
     let config = doc
         .language_config()
@@ -1599,14 +1650,14 @@ fn lsp_restart(cx: &mut compositor::Context, args: Args, event: PromptEvent) ->
     let language_servers: Vec<_> = config
         .language_servers
         .iter()
-        .map(|ls| ls.name.as_str())
+        .map(|ls| ls.name.clone())
         .collect();
     let language_servers = if args.is_empty() {
         language_servers
     } else {
         let (valid, invalid): (Vec<_>, Vec<_>) = args
             .iter()
-            .map(|arg| arg.as_ref())
+            .map(|arg| arg.to_string())
             .partition(|name| language_servers.contains(name));
         if !invalid.is_empty() {
             let s = if invalid.len() == 1 { "" } else { "s" };
@@ -1615,51 +1666,19 @@ fn lsp_restart(cx: &mut compositor::Context, args: Args, event: PromptEvent) ->
         valid
     };
 
-    let mut errors = Vec::new();
-    for server in language_servers.iter() {
-        match cx
-            .editor
-            .language_servers
-            .restart_server(
-                server,
-                config,
-                doc.path(),
-                &editor_config.workspace_lsp_roots,
-                editor_config.lsp.snippets,
-            )
-            .transpose()
-        {
+    let doc_id = doc.id();
+    let errors: Vec<_> = restart_language_servers(cx.editor, doc_id, &language_servers)
+        .into_iter()
+        .filter_map(|(server, err)| match err {
             // Ignore the executable-not-found error unless the server was explicitly requested
             // in the arguments.
-            Err(helix_lsp::Error::ExecutableNotFound(_))
-                if !args.iter().any(|arg| arg == server) => {}
-            Err(err) => errors.push(err.to_string()),
-            _ => (),
-        }
-    }
-
-    // This collect is needed because refresh_language_server would need to re-borrow editor.
-    let document_ids_to_refresh: Vec<DocumentId> = cx
-        .editor
-        .documents()
-        .filter_map(|doc| match doc.language_config() {
-            Some(config)
-                if config.language_servers.iter().any(|ls| {
-                    language_servers
-                        .iter()
-                        .any(|restarted_ls| restarted_ls == &ls.name)
-                }) =>
-            {
-                Some(doc.id())
+            helix_lsp::Error::ExecutableNotFound(_) if !args.iter().any(|arg| *arg == server) => {
+                None
             }
-            _ => None,
+            err => Some(err.to_string()),
         })
         .collect();
 
-    for document_id in document_ids_to_refresh {
-        cx.editor.refresh_language_servers(document_id);
-    }
-
     if errors.is_empty() {
         Ok(())
     } else {
@@ -1695,16 +1714,158 @@ fn lsp_stop(cx: &mut compositor::Context, args: Args, event: PromptEvent) -> any
     };
 
     for ls_name in &language_servers {
-        cx.editor.language_servers.stop(ls_name);
+        stop_language_server(cx.editor, ls_name);
+    }
+
+    Ok(())
+}
+
+/// Returns the running language servers with the given names, or all running language servers
+/// if no names are given.
+fn running_language_servers<'a>(
+    editor: &Editor,
+    names: impl Iterator<Item = &'a str>,
+) -> anyhow::Result<Vec<std::sync::Arc<helix_lsp::Client>>> {
+    let names: Vec<_> = names.collect();
+    let language_servers: Vec<_> = editor
+        .language_servers
+        .iter_clients()
+        .filter(|ls| names.is_empty() || names.contains(&ls.name()))
+        .cloned()
+        .collect();
+    let invalid: Vec<_> = names
+        .iter()
+        .filter(|name| !language_servers.iter().any(|ls| ls.name() == **name))
+        .collect();
+    if !invalid.is_empty() {
+        let s = if invalid.len() == 1 { "" } else { "s" };
+        bail!(
+            "Unknown language server{s}: {}",
+            invalid
+                .iter()
+                .map(|name| name.to_string())
+                .collect::<Vec<_>>()
+                .join(", ")
+        );
+    }
+    if language_servers.is_empty() {
+        bail!("No language server is running");
+    }
+    Ok(language_servers)
+}
+
+fn lsp_status(cx: &mut compositor::Context, _args: Args, event: PromptEvent) -> anyhow::Result<()> {
+    if event != PromptEvent::Validate {
+        return Ok(());
+    }
+
+    let callback = async move {
+        let call: job::Callback = Callback::EditorCompositor(Box::new(
+            move |_editor: &mut Editor, compositor: &mut Compositor| {
+                compositor.push(Box::new(overlaid(
+                    ui::lsp::status::LanguageServerStatus::new(),
+                )))
+            },
+        ));
+        Ok(call)
+    };
+    cx.jobs.callback(callback);
+
+    Ok(())
+}
+
+fn lsp_traffic(cx: &mut compositor::Context, args: Args, event: PromptEvent) -> anyhow::Result<()> {
+    if event != PromptEvent::Validate {
+        return Ok(());
+    }
+
+    let language_servers = running_language_servers(cx.editor, args.iter().map(AsRef::as_ref))?;
+    if !language_servers.iter().any(|ls| ls.traffic().is_enabled()) {
+        bail!("The traffic is not recorded, start recording it with :lsp-set-trace messages");
+    }
+    let items: Vec<_> = language_servers
+        .iter()
+        .flat_map(|ls| {
+            ls.traffic()
+                .entries()
+                .into_iter()
+                .map(|entry| lsp::TrafficItem {
+                    server: ls.name().to_string(),
+                    entry,
+                })
+        })
+        .collect();
+
+    let callback = async move {
+        let call: job::Callback = Callback::EditorCompositor(Box::new(
+            move |_editor: &mut Editor, compositor: &mut Compositor| {
+                compositor.push(Box::new(overlaid(lsp::lsp_traffic_picker(items))))
+            },
+        ));
+        Ok(call)
+    };
+    cx.jobs.callback(callback);
+
+    Ok(())
+}
+
+fn lsp_set_trace(
+    cx: &mut compositor::Context,
+    args: Args,
+    event: PromptEvent,
+) -> anyhow::Result<()> {
+    if event != PromptEvent::Validate {
+        return Ok(());
+    }
+
+    let value: helix_lsp::lsp::TraceValue =
+        serde_json::from_value(Value::String(args[0].to_string()))
+            .map_err(|_| anyhow!("Invalid trace value '{}'", &args[0]))?;
+    for ls in running_language_servers(cx.editor, args.iter().skip(1).map(AsRef::as_ref))? {
+        ls.set_trace(value);
+    }
+
+    Ok(())
+}
 
-        for doc in cx.editor.documents_mut() {
-            if let Some(client) = doc.remove_language_server_by_name(ls_name) {
-                doc.clear_diagnostics_for_language_server(client.id());
-                doc.reset_all_inlay_hints();
-                doc.inlay_hints_oudated = true;
+fn lsp_trace_log(
+    cx: &mut compositor::Context,
+    args: Args,
+    event: PromptEvent,
+) -> anyhow::Result<()> {
+    if event != PromptEvent::Validate {
+        return Ok(());
+    }
+
+    let mut entries: Vec<_> = running_language_servers(cx.editor, args.iter().map(AsRef::as_ref))?
+        .iter()
+        .flat_map(|ls| {
+            ls.traffic()
+                .traces()
+                .into_iter()
+                .map(|trace| (trace.time, ls.name().to_string(), trace.params))
+        })
+        .collect();
+    entries.sort_by_key(|(time, _, _)| *time);
+
+    let mut log = String::new();
+    for (time, server, params) in entries {
+        writeln!(
+            log,
+            "[{:.3}s] {server}: {}",
+            time.as_secs_f64(),
+            params.message
+        )?;
+        if let Some(verbose) = params.verbose {
+            for line in verbose.lines() {
+                writeln!(log, "    {line}")?;
             }
         }
     }
+    if log.is_empty() {
+        bail!("No traces were logged, enable them with :lsp-set-trace");
+    }
+    lsp::open_scratch_buffer(cx.editor, log, None);
 
     Ok(())
 }
@@ -1880,6 +2041,202 @@ fn debug_eval(cx: &mut compositor::Context, args: Args, event: PromptEvent) -> a
     Ok(())
 }
 
+fn debug_break_function(
+    cx: &mut compositor::Context,
+    args: Args,
+    event: PromptEvent,
+) -> anyhow::Result<()> {
+    if event != PromptEvent::Validate {
+        return Ok(());
+    }
+
+    // The breakpoints are sent when a debugger starts otherwise.
+    let debugger = cx.editor.debug_adapters.get_active_client_mut();
+    if let Some(debugger) = &debugger {
+        ensure!(
+            supports(debugger, |caps| caps.supports_function_breakpoints),
+            "Debugger does not support function breakpoints"
+        );
+    }
+
+    let breakpoint = FunctionBreakpoint {
+        name: args[0].to_string(),
+        condition: args.get_flag("condition").map(str::to_string),
+        hit_condition: args.get_flag("hit-condition").map(str::to_string),
+        ..Default::default()
+    };
+    let breakpoints = &mut cx.editor.function_breakpoints;
+    let previous = breakpoints.clone();
+    match breakpoints.iter_mut().find(|b| b.name == breakpoint.name) {
+        Some(existing) => *existing = breakpoint,
+        None => breakpoints.push(breakpoint),
+    }
+    if let Some(debugger) = debugger {
+        if let Err(err) = function_breakpoints_changed(debugger, breakpoints) {
+            *breakpoints = previous;
+            return Err(err);
+        }
+    }
+    cx.editor.save_breakpoints();
+    Ok(())
+}
+
+fn debug_break_data(
+    cx: &mut compositor::Context,
+    args: Args,
+    event: PromptEvent,
+) -> anyhow::Result<()> {
+    if event != PromptEvent::Validate {
+        return Ok(());
+    }
+
+    let access_type: Option<helix_dap::DataBreakpointAccessType> = args
+        .get_flag("access")
+        .map(str::parse)
+        .transpose()
+        .map_err(|err: String| anyhow!(err))?;
+    let Some(debugger) = cx.editor.debug_adapters.get_active_client_mut() else {
+        bail!("Debugger is not running");
+    };
+    if !supports(debugger, |caps| caps.supports_data_breakpoints) {
+        bail!("Debugger does not support data breakpoints");
+    }
+
+    // The expression is resolved in the current stack frame, or globally without one.
+    let frame_id = debugger.current_stack_frame().map(|frame| frame.id);
+    let info =
+        helix_lsp::block_on(debugger.data_breakpoint_info(args[0].to_string(), None, frame_id))?;
+    let Some(data_id) = info.data_id else {
+        bail!("Cannot break on '{}': {}", &args[0], info.description);
+    };
+    if let (Some(access_type), Some(access_types)) = (access_type, &info.access_types) {
+        ensure!(
+            access_types.contains(&access_type),
+            "Debugger does not support {access_type} access on '{}'",
+            &args[0]
+        );
+    }
+
+    let breakpoint = DataBreakpoint {
+        id: None,
+        verified: false,
+        message: None,
+        data_id,
+        description: info.description,
+        access_type,
+        condition: args.get_flag("condition").map(str::to_string),
+        hit_condition: args.get_flag("hit-condition").map(str::to_string),
+        can_persist: info.can_persist.unwrap_or_default(),
+        disabled: false,
+    };
+    let breakpoints = &mut cx.editor.data_breakpoints;
+    let previous = breakpoints.clone();
+    match breakpoints
+        .iter_mut()
+        .find(|b| b.data_id == breakpoint.data_id)
+    {
+        Some(existing) => *existing = breakpoint,
+        None => breakpoints.push(breakpoint),
+    }
+    if let Err(err) = data_breakpoints_changed(debugger, breakpoints) {
+        *breakpoints = previous;
+        return Err(err);
+    }
+    cx.editor.save_breakpoints();
+    Ok(())
+}
+
+fn debug_break_instruction(
+    cx: &mut compositor::Context,
+    args: Args,
+    event: PromptEvent,
+) -> anyhow::Result<()> {
+    if event != PromptEvent::Validate {
+        return Ok(());
+    }
+
+    // The breakpoints are sent when a debugger starts otherwise.
+    let debugger = cx.editor.debug_adapters.get_active_client_mut();
+    if let Some(debugger) = &debugger {
+        ensure!(
+            supports(debugger, |caps| caps.supports_instruction_breakpoints),
+            "Debugger does not support instruction breakpoints"
+        );
+    }
+
+    let breakpoint = InstructionBreakpoint {
+        instruction_reference: args[0].to_string(),
+        offset: args
+            .get_flag("offset")
+            .map(str::parse)
+            .transpose()
+            .context("invalid offset")?,
+        condition: args.get_flag("condition").map(str::to_string),
+        hit_condition: args.get_flag("hit-condition").map(str::to_string),
+        ..Default::default()
+    };
+    let breakpoints = &mut cx.editor.instruction_breakpoints;
+    let previous = breakpoints.clone();
+    match breakpoints.iter_mut().find(|b| {
+        b.instruction_reference == breakpoint.instruction_reference && b.offset == breakpoint.offset
+    }) {
+        Some(existing) => *existing = breakpoint,
+        None => breakpoints.push(breakpoint),
+    }
+    if let Some(debugger) = debugger {
+        if let Err(err) = instruction_breakpoints_changed(debugger, breakpoints) {
+            *breakpoints = previous;
+            return Err(err);
+        }
+    }
+    Ok(())
+}
+
+fn debug_break_remove(
+    cx: &mut compositor::Context,
+    args: Args,
+    event: PromptEvent,
+) -> anyhow::Result<()> {
+    if event != PromptEvent::Validate {
+        return Ok(());
+    }
+
+    let target = &args[0];
+    let editor = &mut *cx.editor;
+    let function_count = editor.function_breakpoints.len();
+    let data_count = editor.data_breakpoints.len();
+    let instruction_count = editor.instruction_breakpoints.len();
+    editor.function_breakpoints.retain(|b| b.name != *target);
+    editor
+        .data_breakpoints
+        .retain(|b| b.description != *target && b.data_id != *target);
+    editor
+        .instruction_breakpoints
+        .retain(|b| b.instruction_reference != *target);
+
+    let functions_changed = editor.function_breakpoints.len() != function_count;
+    let data_changed = editor.data_breakpoints.len() != data_count;
+    let instructions_changed = editor.instruction_breakpoints.len() != instruction_count;
+    ensure!(
+        functions_changed || data_changed || instructions_changed,
+        "No breakpoint on '{target}'"
+    );
+    editor.save_breakpoints();
+
+    if let Some(debugger) = editor.debug_adapters.get_active_client_mut() {
+        if functions_changed {
+            function_breakpoints_changed(debugger, &mut editor.function_breakpoints)?;
+        }
+        if data_changed {
+            data_breakpoints_changed(debugger, &mut editor.data_breakpoints)?;
+        }
+        if instructions_changed {
+            instruction_breakpoints_changed(debugger, &mut editor.instruction_breakpoints)?;
+        }
+    }
+    Ok(())
+}
+
 fn debug_start(cx: &mut compositor::Context, args: Args, event: PromptEvent) -> anyhow::Result<()> {
     if event != PromptEvent::Validate {
         return Ok(());
@@ -2548,6 +2905,151 @@ fn move_buffer(cx: &mut compositor::Context, args: Args, event: PromptEvent) ->
     Ok(())
 }
 
+fn create_file(cx: &mut compositor::Context, args: Args, event: PromptEvent) -> anyhow::Result<()> {
+    if event != PromptEvent::Validate {
+        return Ok(());
+    }
+
+    let arg = args.first().unwrap();
+    let path = helix_stdx::path::canonicalize(arg);
+    let is_dir = arg.ends_with(std::path::is_separator);
+    if path.exists() {
+        bail!("{} already exists", path.display());
+    }
+    let will_create = cx
+        .editor
+        .will_file_operation("willCreate", |language_server| {
+            language_server.will_create(&path, is_dir)
+        });
+    after_will_file_operation(
+        cx.editor,
+        cx.jobs,
+        "willCreate",
+        will_create,
+        move |editor| {
+            if let Err(err) = editor.create_path(&path, is_dir) {
+                bail!(
+                    "Could not create {}: {err}",
+                    if is_dir { "directory" } else { "file" }
+                );
+            }
+            if !is_dir {
+                editor.open(&path, Action::Replace)?;
+            }
+            Ok(())
+        },
+    )
+}
+
+fn copy_file_impl(cx: &mut compositor::Context, args: Args, force: bool) -> anyhow::Result<()> {
+    let old_path = PathBuf::from(&args[0]);
+    let new_path = PathBuf::from(&args[1]);
+
+    // if new_path is a directory, append the original file name
+    // to copy the file into that directory.
+    let new_path = old_path
+        .file_name()
+        .filter(|_| new_path.is_dir())
+        .map(|old_file_name| new_path.join(old_file_name))
+        .unwrap_or(new_path);
+    let new_path = helix_stdx::path::canonicalize(new_path);
+
+    let exists = new_path.exists();
+    if !force && exists {
+        bail!(
+            "{} already exists, use :copy-file! to overwrite it",
+            new_path.display()
+        );
+    }
+    // Overwriting a file doesn't create it.
+    let will_create = if exists {
+        None
+    } else {
+        cx.editor
+            .will_file_operation("willCreate", |language_server| {
+                language_server.will_create(&new_path, false)
+            })
+    };
+    after_will_file_operation(
+        cx.editor,
+        cx.jobs,
+        "willCreate",
+        will_create,
+        move |editor| {
+            editor
+                .copy_path(&old_path, &new_path)
+                .map_err(|err| anyhow!("Could not copy file: {err}"))
+        },
+    )
+}
+
+fn copy_file(cx: &mut compositor::Context, args: Args, event: PromptEvent) -> anyhow::Result<()> {
+    if event != PromptEvent::Validate {
+        return Ok(());
+    }
+
+    copy_file_impl(cx, args, false)
+}
+
+fn force_copy_file(
+    cx: &mut compositor::Context,
+    args: Args,
+    event: PromptEvent,
+) -> anyhow::Result<()> {
+    if event != PromptEvent::Validate {
+        return Ok(());
+    }
+
+    copy_file_impl(cx, args, true)
+}
+
+fn delete_file_impl(cx: &mut compositor::Context, args: Args, force: bool) -> anyhow::Result<()> {
+    let path = helix_stdx::path::canonicalize(args.first().unwrap());
+    let is_dir = path.is_dir();
+    if !force && is_dir {
+        bail!(
+            "{} is a directory, use :delete-file! to delete it and its contents",
+            path.display()
+        );
+    }
+    let will_delete = cx
+        .editor
+        .will_file_operation("willDelete", |language_server| {
+            language_server.will_delete(&path, is_dir)
+        });
+    after_will_file_operation(
+        cx.editor,
+        cx.jobs,
+        "willDelete",
+        will_delete,
+        move |editor| {
+            editor
+                .delete_path(&path)
+                .map_err(|err| anyhow!("Could not delete {}: {err}", path.display()))
+        },
+    )
+}
+
+fn delete_file(cx: &mut compositor::Context, args: Args, event: PromptEvent) -> anyhow::Result<()> {
+    if event != PromptEvent::Validate {
+        return Ok(());
+    }
+
+    delete_file_impl(cx, args, false)
+}
+
+fn force_delete_file(
+    cx: &mut compositor::Context,
+    args: Args,
+    event: PromptEvent,
+) -> anyhow::Result<()> {
+    if event != PromptEvent::Validate {
+        return Ok(());
+    }
+
+    delete_file_impl(cx, args, true)
+}
+
 fn yank_diagnostic(
     cx: &mut compositor::Context,
     args: Args,
@@ -3279,6 +3781,53 @@ fn noop(_cx: &mut compositor::Context, _args: Args, _event: PromptEvent) -> anyh
             ..Signature::DEFAULT
         },
     },
+    TypableCommand {
+        name: "lsp-status",
+        aliases: &[],
+        doc: "Show the status of the running language servers: workspace, progress, log messages and capabilities. Servers can be restarted or stopped from there.",
+        fun: lsp_status,
+        completer: CommandCompleter::none(),
+        signature: Signature {
+            positionals: (0, Some(0)),
+            ..Signature::DEFAULT
+        },
+    },
+    TypableCommand {
+        name: "lsp-traffic",
+        aliases: &[],
+        doc: "Lists the messages exchanged with the given language servers, or all running language servers if no arguments are supplied. The messages are recorded while tracing is enabled with :lsp-set-trace",
+        fun: lsp_traffic,
+        completer: CommandCompleter::all(completers::active_language_servers),
+        signature: Signature {
+            positionals: (0, None),
+            ..Signature::DEFAULT
+        },
+    },
+    TypableCommand {
+        name: "lsp-set-trace",
+        aliases: &[],
+        doc: "Sets the trace level (off, messages or verbose) of the given language servers, or all running language servers if no servers are supplied. The messages exchanged with the servers are recorded unless the level is off",
+        fun: lsp_set_trace,
+        completer: CommandCompleter {
+            positional_args: &[completers::lsp_trace_values],
+            var_args: completers::active_language_servers,
+        },
+        signature: Signature {
+            positionals: (1, None),
+            ..Signature::DEFAULT
+        },
+    },
+    TypableCommand {
+        name: "lsp-trace-log",
+        aliases: &[],
+        doc: "Opens the traces logged by the given language servers, or all running language servers if no arguments are supplied",
+        fun: lsp_trace_log,
+        completer: CommandCompleter::all(completers::active_language_servers),
+        signature: Signature {
+            positionals: (0, None),
+            ..Signature::DEFAULT
+        },
+    },
     TypableCommand {
         name: "tree-sitter-scopes",
         aliases: &[],
@@ -3334,6 +3883,104 @@ fn noop(_cx: &mut compositor::Context, _args: Args, _event: PromptEvent) -> anyh
             ..Signature::DEFAULT
         },
     },
+    TypableCommand {
+        name: "debug-break-function",
+        aliases: &[],
+        doc: "Set a breakpoint on the entry of the function with the given name.",
+        fun: debug_break_function,
+        completer: CommandCompleter::none(),
+        signature: Signature {
+            positionals: (1, Some(1)),
+            flags: &[
+                Flag {
+                    name: "condition",
+                    alias: Some('c'),
+                    doc: "only stop when the expression is true",
+                    completions: Some(&[]),
+                },
+                Flag {
+                    name: "hit-condition",
+                    alias: Some('h'),
+                    doc: "only stop when the hit count satisfies the expression, such as '>= 5'",
+                    completions: Some(&[]),
+                },
+            ],
+            ..Signature::DEFAULT
+        },
+    },
+    TypableCommand {
+        name: "debug-break-data",
+        aliases: &["debug-watchpoint"],
+        doc: "Set a data breakpoint which stops when the value of the given expression is accessed.",
+        fun: debug_break_data,
+        completer: CommandCompleter::none(),
+        signature: Signature {
+            positionals: (1, Some(1)),
+            flags: &[
+                Flag {
+                    name: "access",
+                    alias: Some('a'),
+                    doc: "the kind of access to stop on",
+                    completions: Some(&["read", "write", "readWrite"]),
+                },
+                Flag {
+                    name: "condition",
+                    alias: Some('c'),
+                    doc: "only stop when the expression is true",
+                    completions: Some(&[]),
+                },
+                Flag {
+                    name: "hit-condition",
+                    alias: Some('h'),
+                    doc: "only stop when the hit count satisfies the expression, such as '>= 5'",
+                    completions: Some(&[]),
+                },
+            ],
+            ..Signature::DEFAULT
+        },
+    },
+    TypableCommand {
+        name: "debug-break-instruction",
+        aliases: &[],
+        doc: "Set a breakpoint on the instruction at the given memory reference.",
+        fun: debug_break_instruction,
+        completer: CommandCompleter::none(),
+        signature: Signature {
+            positionals: (1, Some(1)),
+            flags: &[
+                Flag {
+                    name: "offset",
+                    alias: Some('o'),
+                    doc: "the offset in bytes from the memory reference",
+                    completions: Some(&[]),
+                },
+                Flag {
+                    name: "condition",
+                    alias: Some('c'),
+                    doc: "only stop when the expression is true",
+                    completions: Some(&[]),
+                },
+                Flag {
+                    name: "hit-condition",
+                    alias: Some('h'),
+                    doc: "only stop when the hit count satisfies the expression, such as '>= 5'",
+                    completions: Some(&[]),
+                },
+            ],
+            ..Signature::DEFAULT
+        },
+    },
+    TypableCommand {
+        name: "debug-break-remove",
+        aliases: &[],
+        doc: "Remove the function, data or instruction breakpoints on the given target.",
+        fun: debug_break_remove,
+        completer: CommandCompleter::positional(&[completers::debug_breakpoints]),
+        signature: Signature {
+            positionals: (1, Some(1)),
+            ..Signature::DEFAULT
+        },
+    },
     TypableCommand {
         name: "vsplit",
         aliases: &["vs"],
@@ -3622,6 +4269,61 @@ fn noop(_cx: &mut compositor::Context, _args: Args, _event: PromptEvent) -> anyh
             ..Signature::DEFAULT
         },
     },
+    TypableCommand {
+        name: "create-file",
+        aliases: &[],
+        doc: "Create a file, or a directory if the path ends with a separator, letting language servers update the workspace. Opens created files.",
+        fun: create_file,
+        completer: CommandCompleter::positional(&[completers::filename]),
+        signature: Signature {
+            positionals: (1, Some(1)),
+            ..Signature::DEFAULT
+        },
+    },
+    TypableCommand {
+        name: "copy-file",
+        aliases: &[],
+        doc: "Copy a file to a different path, letting language servers update the workspace",
+        fun: copy_file,
+        completer: CommandCompleter::positional(&[completers::filename, completers::filename]),
+        signature: Signature {
+            positionals: (2, Some(2)),
+            ..Signature::DEFAULT
+        },
+    },
+    TypableCommand {
+        name: "copy-file!",
+        aliases: &[],
+        doc: "Copy a file to a different path, overwriting an existing file, letting language servers update the workspace",
+        fun: force_copy_file,
+        completer: CommandCompleter::positional(&[completers::filename, completers::filename]),
+        signature: Signature {
+            positionals: (2, Some(2)),
+            ..Signature::DEFAULT
+        },
+    },
+    TypableCommand {
+        name: "delete-file",
+        aliases: &[],
+        doc: "Delete a file, letting language servers update the workspace",
+        fun: delete_file,
+        completer: CommandCompleter::positional(&[completers::filename]),
+        signature: Signature {
+            positionals: (1, Some(1)),
+            ..Signature::DEFAULT
+        },
+    },
+    TypableCommand {
+        name: "delete-file!",
+        aliases: &[],
+        doc: "Delete a file or a directory and its contents, letting language servers update the workspace",
+        fun: force_delete_file,
+        completer: CommandCompleter::positional(&[completers::filename]),
+        signature: Signature {
+            positionals: (1, Some(1)),
+            ..Signature::DEFAULT
+        },
+    },
     TypableCommand {
         name: "yank-diagnostic",
         aliases: &[],
@@ -136,6 +136,15 @@ pub fn remove(&mut self, id: &'static str) -> Option<Box<dyn Component>> {
         Some(self.layers.remove(idx))
     }
 
+    /// Removes the front-most layer with the given `id`.
+    pub fn remove_front(&mut self, id: &'static str) -> Option<Box<dyn Component>> {
+        let idx = self
+            .layers
+            .iter()
+            .rposition(|layer| layer.id() == Some(id))?;
+        Some(self.layers.remove(idx))
+    }
+
     pub fn remove_type<T: 'static>(&mut self) {
         let type_name = std::any::type_name::<T>();
         self.layers
@@ -1,8 +1,9 @@
 use helix_event::{events, register_event};
 use helix_view::document::Mode;
 use helix_view::events::{
-    ConfigDidChange, DiagnosticsDidChange, DocumentDidChange, DocumentDidClose, DocumentDidOpen,
-    DocumentFocusLost, LanguageServerExited, LanguageServerInitialized, SelectionDidChange,
+    ConfigDidChange, DebuggerDidStop, DiagnosticsDidChange, DocumentDidChange, DocumentDidClose,
+    DocumentDidOpen, DocumentFocusLost, LanguageServerExited, LanguageServerInitialized,
+    SelectionDidChange,
 };
 
 use crate::commands;
@@ -26,5 +27,6 @@ pub fn register() {
     register_event::<DiagnosticsDidChange>();
     register_event::<LanguageServerInitialized>();
     register_event::<LanguageServerExited>();
+    register_event::<DebuggerDidStop>();
     register_event::<ConfigDidChange>();
 }
@@ -7,40 +7,68 @@
 use crate::config::Config;
 use crate::events;
 use crate::handlers::auto_save::AutoSaveHandler;
-use crate::handlers::diagnostics::PullDiagnosticsHandler;
+use crate::handlers::diagnostics::{PullDiagnosticsHandler, PullWorkspaceDiagnosticsHandler};
 use crate::handlers::signature_help::SignatureHelpHandler;
 
 pub use helix_view::handlers::{word_index, Handlers};
 
+use self::code_lens::CodeLensHandler;
 use self::document_colors::DocumentColorsHandler;
+use self::document_links::DocumentLinksHandler;
+use self::folding_ranges::FoldingRangesHandler;
+use self::inline_completion::InlineCompletionHandler;
+use self::linked_editing::LinkedEditingHandler;
+use self::semantic_tokens::SemanticTokensHandler;
 
 mod auto_save;
+pub mod code_lens;
 pub mod completion;
+pub mod dap;
 pub mod diagnostics;
 mod document_colors;
+mod document_links;
+pub mod folding_ranges;
+mod inline_completion;
+mod linked_editing;
+mod on_type_formatting;
 mod prompt;
+pub mod semantic_tokens;
 mod signature_help;
 mod snippet;
 
 pub fn setup(config: Arc<ArcSwap<Config>>) -> Handlers {
     events::register();
 
+    let inline_completions = InlineCompletionHandler::new(config.clone()).spawn();
+    let linked_editing_ranges = LinkedEditingHandler::default().spawn();
     let event_tx = completion::CompletionHandler::new(config).spawn();
     let signature_hints = SignatureHelpHandler::new().spawn();
     let auto_save = AutoSaveHandler::new().spawn();
     let document_colors = DocumentColorsHandler::default().spawn();
+    let semantic_tokens = SemanticTokensHandler::default().spawn();
+    let code_lenses = CodeLensHandler::default().spawn();
+    let folding_ranges = FoldingRangesHandler::default().spawn();
+    let document_links = DocumentLinksHandler::default().spawn();
     let word_index = word_index::Handler::spawn();
     let pull_diagnostics = PullDiagnosticsHandler::default().spawn();
     let pull_all_documents_diagnostics = PullAllDocumentsDiagnosticHandler::default().spawn();
+    let pull_workspace_diagnostics = PullWorkspaceDiagnosticsHandler::default().spawn();
 
     let handlers = Handlers {
         completions: helix_view::handlers::completion::CompletionHandler::new(event_tx),
         signature_hints,
         auto_save,
         document_colors,
+        semantic_tokens,
+        code_lenses,
+        folding_ranges,
+        document_links,
+        inline_completions,
+        linked_editing_ranges,
         word_index,
         pull_diagnostics,
         pull_all_documents_diagnostics,
+        pull_workspace_diagnostics,
     };
 
     helix_view::handlers::register_hooks(&handlers);
@@ -48,8 +76,51 @@ pub fn setup(config: Arc<ArcSwap<Config>>) -> Handlers {
     signature_help::register_hooks(&handlers);
     auto_save::register_hooks(&handlers);
     diagnostics::register_hooks(&handlers);
+    dap::register_hooks(&handlers);
     snippet::register_hooks(&handlers);
     document_colors::register_hooks(&handlers);
+    semantic_tokens::register_hooks(&handlers);
+    code_lens::register_hooks(&handlers);
+    folding_ranges::register_hooks(&handlers);
+    document_links::register_hooks(&handlers);
+    linked_editing::register_hooks(&handlers);
+    on_type_formatting::register_hooks(&handlers);
+    inline_completion::register_hooks(&handlers);
     prompt::register_hooks(&handlers);
     handlers
 }
+
+/// Creates an editor with a scratch buffer for unit tests. No hooks are registered and the
+/// events sent to the handlers are dropped.
+#[cfg(test)]
+pub(crate) fn test_editor() -> helix_view::Editor {
+    use tokio::sync::mpsc::channel;
+
+    let handlers = Handlers {
+        completions: helix_view::handlers::completion::CompletionHandler::new(channel(1).0),
+        signature_hints: channel(1).0,
+        auto_save: channel(1).0,
+        document_colors: channel(1).0,
+        semantic_tokens: channel(1).0,
+        code_lenses: channel(1).0,
+        folding_ranges: channel(1).0,
+        document_links: channel(1).0,
+        inline_completions: channel(1).0,
+        linked_editing_ranges: channel(1).0,
+        word_index: word_index::Handler::spawn(),
+        pull_diagnostics: channel(1).0,
+        pull_all_documents_diagnostics: channel(1).0,
+        pull_workspace_diagnostics: channel(1).0,
+    };
+    let mut editor = helix_view::Editor::new(
+        helix_view::graphics::Rect::new(0, 0, 80, 24),
+        Arc::new(helix_view::theme::Loader::new(&[])),
+        Arc::new(ArcSwap::from_pointee(
+            helix_core::config::default_lang_loader(),
+        )),
+        Arc::new(ArcSwap::from_pointee(helix_view::editor::Config::default())),
+        handlers,
+    );
+    editor.new_file(helix_view::editor::Action::VerticalSplit);
+    editor
+}
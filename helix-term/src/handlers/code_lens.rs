@@ -0,0 +1,293 @@
+use std::{collections::HashSet, ops, time::Duration};
+
+use futures_util::{
+    future::{join_all, BoxFuture},
+    stream::FuturesOrdered,
+    FutureExt, StreamExt,
+};
+use helix_core::syntax::config::LanguageServerFeature;
+use helix_event::{cancelable_future, register_hook};
+use helix_lsp::{lsp, LanguageServerId};
+use helix_view::{
+    document::DocumentCodeLens,
+    events::{DocumentDidChange, DocumentDidOpen, LanguageServerExited, LanguageServerInitialized},
+    handlers::{lsp::CodeLensEvent, Handlers},
+    Document, DocumentId, Editor,
+};
+use tokio::time::Instant;
+
+use crate::job;
+
+#[derive(Default)]
+pub(super) struct CodeLensHandler {
+    docs: HashSet<DocumentId>,
+}
+
+const DOCUMENT_CHANGE_DEBOUNCE: Duration = Duration::from_millis(500);
+
+impl helix_event::AsyncHook for CodeLensHandler {
+    type Event = CodeLensEvent;
+
+    fn handle_event(&mut self, event: Self::Event, _timeout: Option<Instant>) -> Option<Instant> {
+        let CodeLensEvent(doc_id) = event;
+        self.docs.insert(doc_id);
+        Some(Instant::now() + DOCUMENT_CHANGE_DEBOUNCE)
+    }
+
+    fn finish_debounce(&mut self) {
+        let docs = std::mem::take(&mut self.docs);
+
+        job::dispatch_blocking(move |editor, _compositor| {
+            for doc in docs {
+                request_code_lenses(editor, doc);
+            }
+        });
+    }
+}
+
+type CodeLensFuture = BoxFuture<'static, anyhow::Result<Vec<(lsp::CodeLens, LanguageServerId)>>>;
+
+/// Requests the code lenses of `doc` from all language servers supporting them.
+///
+/// Lenses without a command only get a title once resolved. Resolving them can be as expensive
+/// as a reference search, so only the lenses starting on `resolve_line` are resolved right
+/// away. The lenses displayed in a view are resolved by [resolve_visible_code_lenses].
+pub fn code_lenses_request(
+    doc: &Document,
+    language_servers: &helix_lsp::Registry,
+    resolve_line: Option<u32>,
+) -> FuturesOrdered<CodeLensFuture> {
+    let mut seen_language_servers = HashSet::new();
+    doc.language_servers_with_feature(LanguageServerFeature::CodeLens)
+        .filter(|ls| seen_language_servers.insert(ls.id()))
+        .filter_map(|language_server| {
+            let language_server_id = language_server.id();
+            let future = language_server.code_lens(doc.identifier(), None)?;
+            let language_server = language_servers.get_by_id(language_server_id)?.clone();
+
+            Some(
+                async move {
+                    let lenses = future.await?.unwrap_or_default();
+                    let lenses = join_all(lenses.into_iter().map(|lens| {
+                        let resolve = (lens.command.is_none()
+                            && Some(lens.range.start.line) == resolve_line)
+                            .then(|| language_server.resolve_code_lens(&lens))
+                            .flatten();
+                        async move {
+                            match resolve {
+                                Some(future) => future.await.unwrap_or_else(|err| {
+                                    log::error!("code lens resolve request failed: {err}");
+                                    lens
+                                }),
+                                None => lens,
+                            }
+                        }
+                    }))
+                    .await;
+
+                    Ok(lenses
+                        .into_iter()
+                        .map(|lens| (lens, language_server_id))
+                        .collect())
+                }
+                .boxed(),
+            )
+        })
+        .collect()
+}
+
+/// Resolves the unresolved code lenses in the visible lines of every view.
+pub fn resolve_visible_code_lenses(editor: &mut Editor) {
+    if !editor.config().lsp.display_code_lenses {
+        return;
+    }
+
+    let mut visible: Vec<(DocumentId, ops::Range<usize>)> = Vec::new();
+    for (view, _) in editor.tree.views() {
+        let Some(doc) = editor.documents.get(&view.doc) else {
+            continue;
+        };
+        let text = doc.text();
+        let first_line = text.char_to_line(doc.view_offset(view.id).anchor.min(text.len_chars()));
+        visible.push((view.doc, first_line..first_line + view.inner_height() + 1));
+    }
+
+    for (doc_id, lines) in visible {
+        let Some(doc) = editor.documents.get_mut(&doc_id) else {
+            continue;
+        };
+        // Ropes are cheap to clone, this keeps the lenses mutable while iterating.
+        let text = doc.text().clone();
+        let version = doc.version();
+        for lens in &mut doc.code_lenses {
+            if lens.resolve_requested || lens.lens.command.is_some() {
+                continue;
+            }
+            let line = text.char_to_line(lens.char_idx.min(text.len_chars()));
+            if !lines.contains(&line) {
+                continue;
+            }
+            lens.resolve_requested = true;
+
+            let Some(future) = editor
+                .language_servers
+                .get_by_id(lens.language_server_id)
+                .and_then(|language_server| language_server.resolve_code_lens(&lens.lens))
+            else {
+                continue;
+            };
+            let unresolved = lens.lens.clone();
+            let language_server_id = lens.language_server_id;
+            tokio::spawn(async move {
+                let resolved = future.await;
+                if let Err(err) = &resolved {
+                    log::error!("code lens resolve request failed: {err}");
+                }
+                job::dispatch(move |editor, _| {
+                    let Some(doc) = editor.documents.get_mut(&doc_id) else {
+                        return;
+                    };
+                    if doc.version() != version {
+                        return;
+                    }
+                    let Some(lens) = doc.code_lenses.iter_mut().find(|lens| {
+                        lens.language_server_id == language_server_id && lens.lens == unresolved
+                    }) else {
+                        return;
+                    };
+                    match resolved {
+                        Ok(resolved) => lens.lens = resolved,
+                        // Retry once the editor is idle again.
+                        Err(_) => lens.resolve_requested = false,
+                    }
+                })
+                .await;
+            });
+        }
+    }
+}
+
+pub fn request_code_lenses(editor: &mut Editor, doc_id: DocumentId) {
+    if !editor.config().lsp.display_code_lenses {
+        return;
+    }
+
+    let Some(doc) = editor.documents.get_mut(&doc_id) else {
+        return;
+    };
+
+    let mut futures = code_lenses_request(doc, &editor.language_servers, None);
+    if futures.is_empty() {
+        doc.code_lenses.clear();
+        return;
+    }
+
+    let version = doc.version();
+    let cancel = doc.code_lens_controller.restart();
+
+    tokio::spawn(async move {
+        let mut all_lenses = Vec::new();
+        loop {
+            match cancelable_future(futures.next(), &cancel).await {
+                Some(Some(Ok(lenses))) => all_lenses.extend(lenses),
+                Some(Some(Err(err))) => log::error!("code lens request failed: {err}"),
+                Some(None) => break,
+                // The request was cancelled.
+                None => return,
+            }
+        }
+        job::dispatch(move |editor, _| attach_code_lenses(editor, doc_id, version, all_lenses))
+            .await;
+    });
+}
+
+fn attach_code_lenses(
+    editor: &mut Editor,
+    doc_id: DocumentId,
+    version: i32,
+    lenses: Vec<(lsp::CodeLens, LanguageServerId)>,
+) {
+    if !editor.config().lsp.display_code_lenses {
+        return;
+    }
+
+    let Some(doc) = editor.documents.get_mut(&doc_id) else {
+        return;
+    };
+    // The lenses are out of date, a new request has been queued by the change.
+    if doc.version() != version {
+        return;
+    }
+
+    let mut code_lenses: Vec<_> = lenses
+        .into_iter()
+        .filter_map(|(lens, language_server_id)| {
+            let offset_encoding = editor
+                .language_servers
+                .get_by_id(language_server_id)?
+                .offset_encoding();
+            let char_idx =
+                helix_lsp::util::lsp_pos_to_pos(doc.text(), lens.range.start, offset_encoding)?;
+            Some(DocumentCodeLens {
+                char_idx,
+                lens,
+                language_server_id,
+                resolve_requested: false,
+            })
+        })
+        .collect();
+    code_lenses.sort_by_key(|lens| lens.char_idx);
+
+    doc.code_lenses = code_lenses;
+    resolve_visible_code_lenses(editor);
+}
+
+pub(super) fn register_hooks(handlers: &Handlers) {
+    register_hook!(move |event: &mut DocumentDidOpen<'_>| {
+        // when a document is initially opened, request code lenses for it
+        request_code_lenses(event.editor, event.doc);
+
+        Ok(())
+    });
+
+    let tx = handlers.code_lenses.clone();
+    register_hook!(move |event: &mut DocumentDidChange<'_>| {
+        // Keep the lenses anchored to their lines until the new lenses arrive.
+        event.changes.update_positions(
+            event
+                .doc
+                .code_lenses
+                .iter_mut()
+                .map(|lens| (&mut lens.char_idx, helix_core::Assoc::After)),
+        );
+
+        // Ghost transactions (completion previews) aren't sent to the language server.
+        if !event.ghost_transaction {
+            // Cancel the ongoing request, if present.
+            event.doc.code_lens_controller.cancel();
+            helix_event::send_blocking(&tx, CodeLensEvent(event.doc.id()));
+        }
+
+        Ok(())
+    });
+
+    register_hook!(move |event: &mut LanguageServerInitialized<'_>| {
+        let doc_ids: Vec<_> = event.editor.documents().map(|doc| doc.id()).collect();
+
+        for doc_id in doc_ids {
+            request_code_lenses(event.editor, doc_id);
+        }
+
+        Ok(())
+    });
+
+    register_hook!(move |event: &mut LanguageServerExited<'_>| {
+        // Drop the lenses provided by the server that exited.
+        for doc in event.editor.documents_mut() {
+            doc.code_lenses
+                .retain(|lens| lens.language_server_id != event.server_id);
+        }
+
+        Ok(())
+    });
+}
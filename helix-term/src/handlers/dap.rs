@@ -0,0 +1,55 @@
+use std::future::Future;
+
+use helix_event::register_hook;
+use helix_view::events::DebuggerDidStop;
+use helix_view::handlers::dap::{self, DebugCallback};
+use helix_view::handlers::Handlers;
+use helix_view::Editor;
+
+use crate::job;
+
+/// Fetches the variables of the active stack frame and the children of the expanded variables
+/// in the background, see [`dap::fetch_variables`].
+pub fn fetch_variables(editor: &mut Editor) {
+    if let Some(request) = dap::fetch_variables(editor) {
+        tokio::spawn(fetch(request));
+    }
+}
+
+/// Fetches the children of the expanded variables which haven't been loaded yet in the
+/// background, see [`dap::fetch_expanded_variables`].
+pub fn fetch_expanded_variables(editor: &mut Editor) {
+    if let Some(request) = dap::fetch_expanded_variables(editor) {
+        tokio::spawn(fetch(request));
+    }
+}
+
+/// Fetches the values displayed at the end of the source lines of the active stack frame in
+/// the background, see [`dap::fetch_inline_values`].
+pub fn fetch_inline_values(editor: &mut Editor) {
+    if let Some(request) = dap::fetch_inline_values(editor) {
+        tokio::spawn(async move {
+            let callback = request.await;
+            job::dispatch(move |editor, _| callback(editor)).await;
+        });
+    }
+}
+
+async fn fetch(request: impl Future<Output = DebugCallback>) {
+    let callback = request.await;
+    job::dispatch(move |editor, _| {
+        callback(editor);
+        // The loaded variables may be expanded as well.
+        fetch_expanded_variables(editor);
+    })
+    .await;
+}
+
+pub(super) fn register_hooks(_handlers: &Handlers) {
+    register_hook!(move |event: &mut DebuggerDidStop<'_>| {
+        // The watches and inline values are re-evaluated on every stop.
+        fetch_variables(event.editor);
+        fetch_inline_values(event.editor);
+        Ok(())
+    });
+}
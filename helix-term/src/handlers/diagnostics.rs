@@ -9,13 +9,16 @@
 use helix_core::syntax::config::LanguageServerFeature;
 use helix_core::Uri;
 use helix_event::{cancelable_future, register_hook, send_blocking};
-use helix_lsp::{lsp, LanguageServerId};
+use helix_lsp::{lsp, Client, LanguageServerId};
 use helix_view::document::Mode;
 use helix_view::events::{
-    DiagnosticsDidChange, DocumentDidChange, DocumentDidOpen, LanguageServerInitialized,
+    DiagnosticsDidChange, DocumentDidChange, DocumentDidOpen, LanguageServerExited,
+    LanguageServerInitialized,
 };
 use helix_view::handlers::diagnostics::DiagnosticEvent;
-use helix_view::handlers::lsp::{PullAllDocumentsDiagnosticsEvent, PullDiagnosticsEvent};
+use helix_view::handlers::lsp::{
+    PullAllDocumentsDiagnosticsEvent, PullDiagnosticsEvent, PullWorkspaceDiagnosticsEvent,
+};
 use helix_view::handlers::Handlers;
 use helix_view::{DocumentId, Editor};
 
@@ -40,6 +43,7 @@ pub(super) fn register_hooks(handlers: &Handlers) {
 
     let tx = handlers.pull_diagnostics.clone();
     let tx_all_documents = handlers.pull_all_documents_diagnostics.clone();
+    let tx_workspace = handlers.pull_workspace_diagnostics.clone();
     register_hook!(move |event: &mut DocumentDidChange<'_>| {
         if event
             .doc
@@ -78,6 +82,20 @@ pub(super) fn register_hooks(handlers: &Handlers) {
                     language_servers: inter_file_dependencies_language_servers,
                 },
             );
+
+            let workspace_diagnostics_language_servers = event
+                .doc
+                .language_servers_with_feature(LanguageServerFeature::PullDiagnostics)
+                .filter(|language_server| language_server.supports_workspace_diagnostics())
+                .map(|language_server| language_server.id())
+                .collect();
+
+            send_blocking(
+                &tx_workspace,
+                PullWorkspaceDiagnosticsEvent {
+                    language_servers: workspace_diagnostics_language_servers,
+                },
+            );
         }
         Ok(())
     });
@@ -94,6 +112,13 @@ pub(super) fn register_hooks(handlers: &Handlers) {
         for doc_id in doc_ids {
             request_document_diagnostics(event.editor, doc_id);
         }
+        request_workspace_diagnostics(event.editor, event.server_id);
+
+        Ok(())
+    });
+
+    register_hook!(move |event: &mut LanguageServerExited<'_>| {
+        event.editor.workspace_diagnostics.remove(&event.server_id);
 
         Ok(())
     });
@@ -177,24 +202,7 @@ fn request_document_diagnostics_for_language_severs(
             let future = language_server
                 .text_document_diagnostic(doc.identifier(), doc.previous_diagnostic_id.clone())?;
 
-            let identifier = language_server
-                .capabilities()
-                .diagnostic_provider
-                .as_ref()
-                .and_then(|diagnostic_provider| match diagnostic_provider {
-                    lsp::DiagnosticServerCapabilities::Options(options) => {
-                        options.identifier.clone()
-                    }
-                    lsp::DiagnosticServerCapabilities::RegistrationOptions(options) => {
-                        options.diagnostic_options.identifier.clone()
-                    }
-                });
-
-            let language_server_id = language_server.id();
-            let provider = DiagnosticProvider::Lsp {
-                server_id: language_server_id,
-                identifier,
-            };
+            let provider = pull_diagnostic_provider(language_server);
             let uri = doc.uri()?;
 
             Some(async move {
@@ -254,6 +262,25 @@ fn request_document_diagnostics_for_language_severs(
     });
 }
 
+/// The provider of the diagnostics pulled from a language server.
+fn pull_diagnostic_provider(language_server: &Client) -> DiagnosticProvider {
+    let identifier = language_server
+        .capabilities()
+        .diagnostic_provider
+        .as_ref()
+        .and_then(|diagnostic_provider| match diagnostic_provider {
+            lsp::DiagnosticServerCapabilities::Options(options) => options.identifier.clone(),
+            lsp::DiagnosticServerCapabilities::RegistrationOptions(options) => {
+                options.diagnostic_options.identifier.clone()
+            }
+        });
+
+    DiagnosticProvider::Lsp {
+        server_id: language_server.id(),
+        identifier,
+    }
+}
+
 pub fn request_document_diagnostics(editor: &mut Editor, doc_id: DocumentId) {
     let Some(doc) = editor.document(doc_id) else {
         return;
@@ -299,3 +326,177 @@ fn handle_pull_diagnostics_response(
         lsp::DocumentDiagnosticReportResult::Partial(_) => {}
     };
 }
+
+#[derive(Debug, Default)]
+pub(super) struct PullWorkspaceDiagnosticsHandler {
+    language_servers: HashSet<LanguageServerId>,
+}
+
+impl helix_event::AsyncHook for PullWorkspaceDiagnosticsHandler {
+    type Event = PullWorkspaceDiagnosticsEvent;
+
+    fn handle_event(
+        &mut self,
+        event: Self::Event,
+        _timeout: Option<tokio::time::Instant>,
+    ) -> Option<tokio::time::Instant> {
+        self.language_servers.extend(&event.language_servers);
+        Some(Instant::now() + Duration::from_secs(1))
+    }
+
+    fn finish_debounce(&mut self) {
+        let language_servers = mem::take(&mut self.language_servers);
+        job::dispatch_blocking(move |editor, _| {
+            for language_server in language_servers {
+                request_workspace_diagnostics(editor, language_server);
+            }
+        })
+    }
+}
+
+/// Requests the diagnostics of the whole workspace from a language server.
+///
+/// Only a single request is in flight for each server, the diagnostics are requested again
+/// once it finished if they were requested in the meantime.
+pub fn request_workspace_diagnostics(editor: &mut Editor, language_server_id: LanguageServerId) {
+    let Some(language_server) = editor
+        .language_servers
+        .get_by_id(language_server_id)
+        .cloned()
+    else {
+        return;
+    };
+    let state = editor
+        .workspace_diagnostics
+        .entry(language_server_id)
+        .or_default();
+    if state.in_flight {
+        state.outdated = true;
+        return;
+    }
+
+    let previous_result_ids = state
+        .previous_result_ids
+        .iter()
+        .map(|(uri, value)| lsp::PreviousResultId {
+            uri: uri.clone(),
+            value: value.clone(),
+        })
+        .collect();
+    let token = workspace_diagnostics_token(language_server_id);
+    let Some(future) = language_server.workspace_diagnostic(previous_result_ids, Some(token))
+    else {
+        return;
+    };
+    state.in_flight = true;
+    state.outdated = false;
+
+    tokio::spawn(async move {
+        let result = future.await;
+
+        job::dispatch(move |editor, _| {
+            let Some(state) = editor.workspace_diagnostics.get_mut(&language_server_id) else {
+                // The language server exited in the meantime.
+                return;
+            };
+            state.in_flight = false;
+            match result {
+                Ok(
+                    lsp::WorkspaceDiagnosticReportResult::Report(lsp::WorkspaceDiagnosticReport {
+                        items,
+                    })
+                    | lsp::WorkspaceDiagnosticReportResult::Partial(
+                        lsp::WorkspaceDiagnosticReportPartialResult { items },
+                    ),
+                ) => handle_workspace_diagnostics_report(editor, language_server_id, items),
+                // Servers may keep the request open until the diagnostics change.
+                Err(helix_lsp::Error::Timeout(_)) => state.outdated = true,
+                Err(helix_lsp::Error::Rpc(error))
+                    if error
+                        .data
+                        .clone()
+                        .and_then(|data| {
+                            serde_json::from_value::<lsp::DiagnosticServerCancellationData>(data)
+                                .ok()
+                        })
+                        .is_some_and(|data| data.retrigger_request) =>
+                {
+                    state.outdated = true
+                }
+                Err(err) => log::error!("Workspace pull diagnostic request failed: {err}"),
+            }
+
+            if editor
+                .workspace_diagnostics
+                .get(&language_server_id)
+                .is_some_and(|state| state.outdated)
+            {
+                request_workspace_diagnostics(editor, language_server_id);
+            }
+        })
+        .await;
+    });
+}
+
+/// The partial result token of the `workspace/diagnostic` requests sent to a language server.
+pub fn workspace_diagnostics_token(language_server_id: LanguageServerId) -> lsp::ProgressToken {
+    lsp::ProgressToken::String(format!("helix-workspace-diagnostic-{language_server_id}"))
+}
+
+/// Handles the reports streamed through `$/progress` notifications while a `workspace/diagnostic`
+/// request is in flight. The notifications are identified by [workspace_diagnostics_token].
+pub fn handle_workspace_diagnostics_partial_result(
+    editor: &mut Editor,
+    language_server_id: LanguageServerId,
+    value: serde_json::Value,
+) {
+    match serde_json::from_value::<lsp::WorkspaceDiagnosticReportPartialResult>(value) {
+        Ok(partial_result) => {
+            handle_workspace_diagnostics_report(editor, language_server_id, partial_result.items)
+        }
+        Err(err) => log::warn!("Discarding unexpected partial result: {err}"),
+    }
+}
+
+fn handle_workspace_diagnostics_report(
+    editor: &mut Editor,
+    language_server_id: LanguageServerId,
+    items: Vec<lsp::WorkspaceDocumentDiagnosticReport>,
+) {
+    let Some(language_server) = editor.language_server_by_id(language_server_id) else {
+        return;
+    };
+    let provider = pull_diagnostic_provider(language_server);
+
+    for item in items {
+        let (url, result_id) = match item {
+            lsp::WorkspaceDocumentDiagnosticReport::Full(report) => {
+                match Uri::try_from(&report.uri) {
+                    Ok(uri) => editor.handle_lsp_diagnostics(
+                        &provider,
+                        uri,
+                        report.version.and_then(|version| version.try_into().ok()),
+                        report.full_document_diagnostic_report.items,
+                    ),
+                    Err(err) => {
+                        log::error!("{err}");
+                        continue;
+                    }
+                }
+                (report.uri, report.full_document_diagnostic_report.result_id)
+            }
+            lsp::WorkspaceDocumentDiagnosticReport::Unchanged(report) => (
+                report.uri,
+                Some(report.unchanged_document_diagnostic_report.result_id),
+            ),
+        };
+
+        let Some(state) = editor.workspace_diagnostics.get_mut(&language_server_id) else {
+            continue;
+        };
+        match result_id {
+            Some(result_id) => state.previous_result_ids.insert(url, result_id),
+            None => state.previous_result_ids.remove(&url),
+        };
+    }
+}
@@ -0,0 +1,252 @@
+use std::{collections::HashSet, time::Duration};
+
+use futures_util::{future::BoxFuture, stream::FuturesOrdered, FutureExt, StreamExt};
+use helix_core::syntax::config::LanguageServerFeature;
+use helix_event::{cancelable_future, register_hook};
+use helix_lsp::{lsp, LanguageServerId, OffsetEncoding};
+use helix_view::{
+    document::DocumentLink,
+    events::{DocumentDidChange, DocumentDidOpen, LanguageServerExited, LanguageServerInitialized},
+    handlers::{lsp::DocumentLinksEvent, Handlers},
+    DocumentId, Editor,
+};
+use tokio::time::Instant;
+
+use crate::job;
+
+#[derive(Default)]
+pub(super) struct DocumentLinksHandler {
+    docs: HashSet<DocumentId>,
+}
+
+const DOCUMENT_CHANGE_DEBOUNCE: Duration = Duration::from_millis(500);
+
+impl helix_event::AsyncHook for DocumentLinksHandler {
+    type Event = DocumentLinksEvent;
+
+    fn handle_event(&mut self, event: Self::Event, _timeout: Option<Instant>) -> Option<Instant> {
+        let DocumentLinksEvent(doc_id) = event;
+        self.docs.insert(doc_id);
+        Some(Instant::now() + DOCUMENT_CHANGE_DEBOUNCE)
+    }
+
+    fn finish_debounce(&mut self) {
+        let docs = std::mem::take(&mut self.docs);
+
+        job::dispatch_blocking(move |editor, _compositor| {
+            for doc in docs {
+                request_document_links(editor, doc);
+            }
+        });
+    }
+}
+
+type DocumentLinksFuture =
+    BoxFuture<'static, anyhow::Result<(Vec<lsp::DocumentLink>, LanguageServerId, OffsetEncoding)>>;
+
+fn request_document_links(editor: &mut Editor, doc_id: DocumentId) {
+    let Some(doc) = editor.documents.get_mut(&doc_id) else {
+        return;
+    };
+
+    let mut seen_language_servers = HashSet::new();
+    let mut futures: FuturesOrdered<DocumentLinksFuture> = doc
+        .language_servers_with_feature(LanguageServerFeature::DocumentLinks)
+        .filter(|ls| seen_language_servers.insert(ls.id()))
+        .filter_map(|language_server| {
+            let language_server_id = language_server.id();
+            let offset_encoding = language_server.offset_encoding();
+            let future = language_server.document_link(doc.identifier(), None)?;
+            Some(
+                async move {
+                    let links = future.await?.unwrap_or_default();
+                    Ok((links, language_server_id, offset_encoding))
+                }
+                .boxed(),
+            )
+        })
+        .collect();
+
+    if futures.is_empty() {
+        doc.document_links.clear();
+        return;
+    }
+
+    let version = doc.version();
+    let cancel = doc.document_links_controller.restart();
+
+    tokio::spawn(async move {
+        let mut all_links = Vec::new();
+        loop {
+            match cancelable_future(futures.next(), &cancel).await {
+                Some(Some(Ok((links, language_server_id, offset_encoding)))) => all_links.extend(
+                    links
+                        .into_iter()
+                        .map(|link| (link, language_server_id, offset_encoding)),
+                ),
+                Some(Some(Err(err))) => log::error!("document link request failed: {err}"),
+                Some(None) => break,
+                // The request was cancelled.
+                None => return,
+            }
+        }
+        job::dispatch(move |editor, _| attach_document_links(editor, doc_id, version, all_links))
+            .await;
+    });
+}
+
+fn attach_document_links(
+    editor: &mut Editor,
+    doc_id: DocumentId,
+    version: i32,
+    links: Vec<(lsp::DocumentLink, LanguageServerId, OffsetEncoding)>,
+) {
+    let Some(doc) = editor.documents.get_mut(&doc_id) else {
+        return;
+    };
+    // The links are out of date, a new request has been queued by the change.
+    if doc.version() != version {
+        return;
+    }
+
+    let mut document_links: Vec<_> = links
+        .into_iter()
+        .filter_map(|(link, language_server_id, offset_encoding)| {
+            let range =
+                helix_lsp::util::lsp_range_to_range(doc.text(), link.range, offset_encoding)?;
+            Some(DocumentLink {
+                range: range.from()..range.to(),
+                link,
+                language_server_id,
+            })
+        })
+        .collect();
+    document_links.sort_by_key(|link| link.range.start);
+
+    doc.document_links = document_links;
+}
+
+pub(super) fn register_hooks(handlers: &Handlers) {
+    register_hook!(move |event: &mut DocumentDidOpen<'_>| {
+        request_document_links(event.editor, event.doc);
+
+        Ok(())
+    });
+
+    let tx = handlers.document_links.clone();
+    register_hook!(move |event: &mut DocumentDidChange<'_>| {
+        // Keep the links on their text until the new links arrive.
+        event
+            .changes
+            .update_positions(event.doc.document_links.iter_mut().flat_map(|link| {
+                [
+                    (&mut link.range.start, helix_core::Assoc::After),
+                    (&mut link.range.end, helix_core::Assoc::Before),
+                ]
+            }));
+
+        if !event.ghost_transaction {
+            // Cancel the ongoing request, if present.
+            event.doc.document_links_controller.cancel();
+            helix_event::send_blocking(&tx, DocumentLinksEvent(event.doc.id()));
+        }
+
+        Ok(())
+    });
+
+    register_hook!(move |event: &mut LanguageServerInitialized<'_>| {
+        let doc_ids: Vec<_> = event.editor.documents().map(|doc| doc.id()).collect();
+
+        for doc_id in doc_ids {
+            request_document_links(event.editor, doc_id);
+        }
+
+        Ok(())
+    });
+
+    register_hook!(move |event: &mut LanguageServerExited<'_>| {
+        // Drop the links provided by the server that exited.
+        for doc in event.editor.documents_mut() {
+            doc.document_links
+                .retain(|link| link.language_server_id != event.server_id);
+        }
+
+        Ok(())
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use helix_core::{Selection, Transaction};
+
+    use super::*;
+    use crate::handlers::test_editor;
+
+    /// An editor with a document containing `see ä.rs and b.rs` on its first line.
+    fn editor() -> Editor {
+        let mut editor = test_editor();
+        let (view, doc) = current!(editor);
+        let transaction = Transaction::insert(
+            doc.text(),
+            &Selection::point(0),
+            "see ä.rs and b.rs\n".into(),
+        );
+        doc.apply(&transaction, view.id);
+        editor
+    }
+
+    fn link(start: u32, end: u32) -> (lsp::DocumentLink, LanguageServerId, OffsetEncoding) {
+        let link = lsp::DocumentLink {
+            range: lsp::Range::new(lsp::Position::new(0, start), lsp::Position::new(0, end)),
+            target: None,
+            tooltip: None,
+            data: None,
+        };
+        (link, LanguageServerId::default(), OffsetEncoding::Utf8)
+    }
+
+    fn link_ranges(editor: &Editor) -> Vec<std::ops::Range<usize>> {
+        doc!(editor)
+            .document_links
+            .iter()
+            .map(|link| link.range.clone())
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn attach_links() {
+        let mut editor = editor();
+        let doc_id = doc!(editor).id();
+        let version = doc!(editor).version();
+
+        // Links for an older version of the document are dropped.
+        attach_document_links(&mut editor, doc_id, version - 1, vec![link(4, 9)]);
+        assert!(link_ranges(&editor).is_empty());
+
+        // The byte offsets of the server are converted to char ranges and sorted.
+        let links = vec![link(14, 18), link(4, 9)];
+        attach_document_links(&mut editor, doc_id, version, links);
+        assert_eq!(link_ranges(&editor), vec![4..8, 13..17]);
+    }
+
+    #[tokio::test]
+    async fn link_at_position() {
+        let mut editor = editor();
+        let doc_id = doc!(editor).id();
+        let version = doc!(editor).version();
+        attach_document_links(&mut editor, doc_id, version, vec![link(4, 9), link(14, 18)]);
+
+        let link_at = |pos| {
+            doc!(editor)
+                .document_link_at(pos)
+                .map(|link| link.range.clone())
+        };
+        assert_eq!(link_at(3), None);
+        assert_eq!(link_at(4), Some(4..8));
+        assert_eq!(link_at(7), Some(4..8));
+        // The end of the range is exclusive.
+        assert_eq!(link_at(8), None);
+        assert_eq!(link_at(16), Some(13..17));
+        assert_eq!(link_at(17), None);
+    }
+}
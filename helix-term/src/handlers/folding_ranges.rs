@@ -0,0 +1,215 @@
+use std::{collections::HashSet, time::Duration};
+
+use futures_util::{future::BoxFuture, stream::FuturesOrdered, FutureExt, StreamExt};
+use helix_core::syntax::{config::LanguageServerFeature, Loader};
+use helix_event::{cancelable_future, register_hook};
+use helix_lsp::lsp;
+use helix_view::{
+    document::FoldingRange,
+    events::{
+        DocumentDidChange, DocumentDidOpen, LanguageServerExited, LanguageServerInitialized,
+        SelectionDidChange,
+    },
+    handlers::{lsp::FoldingRangesEvent, Handlers},
+    Document, DocumentId, Editor,
+};
+use tokio::time::Instant;
+
+use crate::job;
+
+#[derive(Default)]
+pub(super) struct FoldingRangesHandler {
+    docs: HashSet<DocumentId>,
+}
+
+const DOCUMENT_CHANGE_DEBOUNCE: Duration = Duration::from_millis(500);
+
+impl helix_event::AsyncHook for FoldingRangesHandler {
+    type Event = FoldingRangesEvent;
+
+    fn handle_event(&mut self, event: Self::Event, _timeout: Option<Instant>) -> Option<Instant> {
+        let FoldingRangesEvent(doc_id) = event;
+        self.docs.insert(doc_id);
+        Some(Instant::now() + DOCUMENT_CHANGE_DEBOUNCE)
+    }
+
+    fn finish_debounce(&mut self) {
+        let docs = std::mem::take(&mut self.docs);
+
+        job::dispatch_blocking(move |editor, _compositor| {
+            for doc in docs {
+                request_folding_ranges(editor, doc);
+            }
+        });
+    }
+}
+
+type FoldingRangesFuture = BoxFuture<'static, helix_lsp::Result<Option<Vec<lsp::FoldingRange>>>>;
+
+/// Computes the folding ranges of a document.
+///
+/// The ranges are requested from the language servers supporting them. Documents without such
+/// a server, or whose servers failed or returned no ranges, fall back to the tree-sitter
+/// `folds.scm` queries.
+pub fn request_folding_ranges(editor: &mut Editor, doc_id: DocumentId) {
+    let Some(doc) = editor.documents.get_mut(&doc_id) else {
+        return;
+    };
+
+    let mut seen_language_servers = HashSet::new();
+    let mut futures: FuturesOrdered<FoldingRangesFuture> = doc
+        .language_servers_with_feature(LanguageServerFeature::FoldingRange)
+        .filter(|ls| seen_language_servers.insert(ls.id()))
+        .filter_map(|language_server| {
+            let future = language_server.text_document_folding_range(doc.identifier(), None)?;
+            Some(future.boxed())
+        })
+        .collect();
+
+    if futures.is_empty() {
+        doc.folding_ranges = syntax_folding_ranges(doc, &editor.syn_loader.load());
+        return;
+    }
+
+    let version = doc.version();
+    let cancel = doc.folding_ranges_controller.restart();
+
+    tokio::spawn(async move {
+        let mut all_ranges = Vec::new();
+        loop {
+            match cancelable_future(futures.next(), &cancel).await {
+                Some(Some(Ok(ranges))) => all_ranges.extend(ranges.unwrap_or_default()),
+                Some(Some(Err(err))) => log::error!("folding range request failed: {err}"),
+                Some(None) => break,
+                // The request was cancelled.
+                None => return,
+            }
+        }
+        job::dispatch(move |editor, _| attach_folding_ranges(editor, doc_id, version, all_ranges))
+            .await;
+    });
+}
+
+fn attach_folding_ranges(
+    editor: &mut Editor,
+    doc_id: DocumentId,
+    version: i32,
+    ranges: Vec<lsp::FoldingRange>,
+) {
+    let Some(doc) = editor.documents.get_mut(&doc_id) else {
+        return;
+    };
+    // The ranges are out of date, a new request has been queued by the change.
+    if doc.version() != version {
+        return;
+    }
+    if ranges.is_empty() {
+        doc.folding_ranges = syntax_folding_ranges(doc, &editor.syn_loader.load());
+        return;
+    }
+
+    let text = doc.text().slice(..);
+    let last_line = text.len_lines() - 1;
+    let mut folding_ranges: Vec<_> = ranges
+        .into_iter()
+        .map(|range| {
+            (
+                (range.start_line as usize).min(last_line),
+                (range.end_line as usize).min(last_line),
+            )
+        })
+        .filter(|(start_line, end_line)| start_line < end_line)
+        .map(|(start_line, end_line)| FoldingRange::from_lines(text, start_line, end_line))
+        .collect();
+    folding_ranges.sort_by_key(|range| (range.start, std::cmp::Reverse(range.end)));
+    folding_ranges.dedup();
+
+    doc.folding_ranges = folding_ranges;
+}
+
+/// Computes the folding ranges of `doc` from the tree-sitter `folds.scm` queries.
+fn syntax_folding_ranges(doc: &Document, loader: &Loader) -> Vec<FoldingRange> {
+    let text = doc.text().slice(..);
+    let ranges = doc
+        .syntax()
+        .map(|syntax| syntax.folding_ranges(text, loader))
+        .unwrap_or_default();
+    ranges
+        .into_iter()
+        .map(|(start_line, end_line)| FoldingRange::from_lines(text, start_line, end_line))
+        .collect()
+}
+
+pub(super) fn register_hooks(handlers: &Handlers) {
+    register_hook!(move |event: &mut DocumentDidOpen<'_>| {
+        request_folding_ranges(event.editor, event.doc);
+
+        Ok(())
+    });
+
+    let tx = handlers.folding_ranges.clone();
+    register_hook!(move |event: &mut DocumentDidChange<'_>| {
+        // Keep the ranges anchored to their lines until the new ranges arrive.
+        event.changes.update_positions(
+            event
+                .doc
+                .folding_ranges
+                .iter_mut()
+                .flat_map(|range| [&mut range.start, &mut range.end])
+                .map(|pos| (pos, helix_core::Assoc::After)),
+        );
+
+        if !event.ghost_transaction {
+            // Cancel the ongoing request, if present.
+            event.doc.folding_ranges_controller.cancel();
+            helix_event::send_blocking(&tx, FoldingRangesEvent(event.doc.id()));
+        }
+
+        Ok(())
+    });
+
+    register_hook!(move |event: &mut LanguageServerInitialized<'_>| {
+        let doc_ids: Vec<_> = event.editor.documents().map(|doc| doc.id()).collect();
+
+        for doc_id in doc_ids {
+            request_folding_ranges(event.editor, doc_id);
+        }
+
+        Ok(())
+    });
+
+    register_hook!(move |event: &mut LanguageServerExited<'_>| {
+        // The documents of the server may fall back to the fold queries now.
+        let doc_ids: Vec<_> = event.editor.documents().map(|doc| doc.id()).collect();
+
+        for doc_id in doc_ids {
+            request_folding_ranges(event.editor, doc_id);
+        }
+
+        Ok(())
+    });
+
+    register_hook!(move |event: &mut SelectionDidChange<'_>| {
+        // Reveal the cursors when they move into folded text, for example after a search.
+        let text = event.doc.text().slice(..);
+        let hidden_lines: Vec<_> = event
+            .doc
+            .selection(event.view)
+            .iter()
+            .map(|range| text.char_to_line(range.cursor(text)))
+            .filter(|&line| {
+                let line_start = text.line_to_char(line);
+                event
+                    .doc
+                    .folds(event.view)
+                    .iter()
+                    .any(|fold| fold.start <= line_start && line_start < fold.end)
+            })
+            .collect();
+        for line in hidden_lines {
+            event.doc.unfold(event.view, line);
+        }
+
+        Ok(())
+    });
+}
@@ -0,0 +1,256 @@
+use std::sync::Arc;
+
+use arc_swap::ArcSwap;
+use helix_core::syntax::config::LanguageServerFeature;
+use helix_event::{cancelable_future, register_hook, send_blocking, TaskController, TaskHandle};
+use helix_lsp::{lsp, util::lsp_range_to_range, LanguageServerId, OffsetEncoding};
+use helix_view::{
+    document::{InlineCompletion, InlineCompletionItem, Mode},
+    events::DocumentDidChange,
+    handlers::{lsp::InlineCompletionEvent, Handlers},
+    DocumentId, Editor, ViewId,
+};
+use tokio::time::Instant;
+
+use crate::config::Config;
+use crate::events::{OnModeSwitch, PostCommand, PostInsertChar};
+use crate::job::{dispatch, dispatch_blocking};
+use crate::keymap::MappableCommand;
+
+#[derive(Debug, Clone, Copy)]
+struct Trigger {
+    doc: DocumentId,
+    view: ViewId,
+    invoked: bool,
+}
+
+pub(super) struct InlineCompletionHandler {
+    /// The currently active trigger which will cause a request after the timeout.
+    trigger: Option<Trigger>,
+    task_controller: TaskController,
+    config: Arc<ArcSwap<Config>>,
+}
+
+impl InlineCompletionHandler {
+    pub fn new(config: Arc<ArcSwap<Config>>) -> Self {
+        Self {
+            trigger: None,
+            task_controller: TaskController::new(),
+            config,
+        }
+    }
+}
+
+impl helix_event::AsyncHook for InlineCompletionHandler {
+    type Event = InlineCompletionEvent;
+
+    fn handle_event(&mut self, event: Self::Event, _timeout: Option<Instant>) -> Option<Instant> {
+        // Any new event makes the request in flight outdated.
+        self.task_controller.cancel();
+        match event {
+            InlineCompletionEvent::Trigger { doc, view, invoked } => {
+                self.trigger = Some(Trigger { doc, view, invoked });
+                if invoked {
+                    // Request the completions immediately.
+                    self.finish_debounce();
+                    return None;
+                }
+            }
+            InlineCompletionEvent::Cancel => self.trigger = None,
+        }
+        self.trigger
+            .map(|_| Instant::now() + self.config.load().editor.completion_timeout)
+    }
+
+    fn finish_debounce(&mut self) {
+        let Some(trigger) = self.trigger.take() else {
+            return;
+        };
+        let handle = self.task_controller.restart();
+        dispatch_blocking(move |editor, _| request_inline_completions(editor, trigger, handle));
+    }
+}
+
+fn request_inline_completions(editor: &mut Editor, trigger: Trigger, handle: TaskHandle) {
+    if editor.mode != Mode::Insert {
+        return;
+    }
+    let (view, doc) = current_ref!(editor);
+    if trigger.view != view.id || trigger.doc != doc.id() {
+        return;
+    }
+    let Some(language_server) = doc
+        .language_servers_with_feature(LanguageServerFeature::InlineCompletion)
+        .next()
+    else {
+        return;
+    };
+
+    let offset_encoding = language_server.offset_encoding();
+    let language_server_id = language_server.id();
+    let trigger_kind = if trigger.invoked {
+        lsp::InlineCompletionTriggerKind::Invoked
+    } else {
+        lsp::InlineCompletionTriggerKind::Automatic
+    };
+    let future = language_server
+        .inline_completion(
+            doc.identifier(),
+            doc.position(view.id, offset_encoding),
+            trigger_kind,
+            None,
+        )
+        .unwrap();
+    let version = doc.version();
+
+    tokio::spawn(async move {
+        let items = match cancelable_future(future, handle).await {
+            Some(Ok(Some(lsp::InlineCompletionResponse::Array(items)))) => items,
+            Some(Ok(Some(lsp::InlineCompletionResponse::List(list)))) => list.items,
+            Some(Ok(None)) => Vec::new(),
+            Some(Err(err)) => {
+                log::error!("inline completion request failed: {err}");
+                return;
+            }
+            // The request was cancelled.
+            None => return,
+        };
+        dispatch(move |editor, _| {
+            attach_inline_completions(
+                editor,
+                (trigger, version),
+                items,
+                offset_encoding,
+                language_server_id,
+            )
+        })
+        .await
+    });
+}
+
+fn attach_inline_completions(
+    editor: &mut Editor,
+    (trigger, version): (Trigger, i32),
+    items: Vec<lsp::InlineCompletionItem>,
+    offset_encoding: OffsetEncoding,
+    language_server_id: LanguageServerId,
+) {
+    if editor.mode != Mode::Insert {
+        return;
+    }
+    let Some(doc) = editor.documents.get_mut(&trigger.doc) else {
+        return;
+    };
+    if doc.version() != version || !doc.selections().contains_key(&trigger.view) {
+        return;
+    }
+
+    let text = doc.text().slice(..);
+    let cursor = doc.selection(trigger.view).primary().cursor(text);
+    let items: Vec<_> = items
+        .into_iter()
+        // Snippets can't be displayed as plain text.
+        .filter(|item| item.insert_text_format != Some(lsp::InsertTextFormat::SNIPPET))
+        .filter_map(|item| {
+            let range = match item.range {
+                Some(range) => {
+                    let range = lsp_range_to_range(doc.text(), range, offset_encoding)?;
+                    range.from()..range.to()
+                }
+                None => cursor..cursor,
+            };
+            Some(InlineCompletionItem {
+                range,
+                text: item.insert_text,
+                command: item.command,
+            })
+        })
+        .filter(|item| item.ghost_text(text, cursor).is_some())
+        .collect();
+
+    if items.is_empty() {
+        doc.inline_completion = None;
+        if trigger.invoked {
+            editor.set_status("No inline completions available");
+        }
+        return;
+    }
+    doc.inline_completion = Some(InlineCompletion {
+        view_id: trigger.view,
+        items,
+        index: 0,
+        language_server_id,
+    });
+}
+
+fn trigger_inline_completions(editor: &mut Editor) {
+    let (view, doc) = current_ref!(editor);
+    if !doc.has_language_server_with_feature(LanguageServerFeature::InlineCompletion) {
+        return;
+    }
+    let event = InlineCompletionEvent::Trigger {
+        doc: doc.id(),
+        view: view.id,
+        invoked: false,
+    };
+    send_blocking(&editor.handlers.inline_completions, event);
+}
+
+pub(super) fn register_hooks(_handlers: &Handlers) {
+    register_hook!(move |event: &mut PostInsertChar<'_, '_>| {
+        trigger_inline_completions(event.cx.editor);
+
+        Ok(())
+    });
+
+    register_hook!(move |event: &mut PostCommand<'_, '_>| {
+        if event.cx.editor.mode != Mode::Insert {
+            return Ok(());
+        }
+        match event.command {
+            // These commands keep or update the displayed completion.
+            MappableCommand::Static {
+                name:
+                    "accept_inline_completion"
+                    | "accept_inline_completion_word"
+                    | "next_inline_completion"
+                    | "prev_inline_completion",
+                ..
+            } => (),
+            _ => trigger_inline_completions(event.cx.editor),
+        }
+
+        Ok(())
+    });
+
+    register_hook!(move |event: &mut OnModeSwitch<'_, '_>| {
+        if event.old_mode == Mode::Insert {
+            send_blocking(
+                &event.cx.editor.handlers.inline_completions,
+                InlineCompletionEvent::Cancel,
+            );
+            for doc in event.cx.editor.documents_mut() {
+                doc.inline_completion = None;
+            }
+        }
+
+        Ok(())
+    });
+
+    register_hook!(move |event: &mut DocumentDidChange<'_>| {
+        let Some(inline_completion) = &mut event.doc.inline_completion else {
+            return Ok(());
+        };
+        // Text typed at the end of the replaced range is part of the completion.
+        event
+            .changes
+            .update_positions(inline_completion.items.iter_mut().flat_map(|item| {
+                [
+                    (&mut item.range.start, helix_core::Assoc::Before),
+                    (&mut item.range.end, helix_core::Assoc::After),
+                ]
+            }));
+
+        Ok(())
+    });
+}
@@ -0,0 +1,457 @@
+use std::borrow::Cow;
+use std::time::Duration;
+
+use helix_core::{
+    regex::Regex, syntax::config::LanguageServerFeature, ChangeSet, Tendril, Transaction,
+};
+use helix_event::{cancelable_future, register_hook, send_blocking, TaskController, TaskHandle};
+use helix_lsp::{lsp, util::lsp_range_to_range, OffsetEncoding};
+use helix_view::{
+    document::{LinkedEditingRanges, Mode},
+    events::{DocumentDidChange, SelectionDidChange},
+    handlers::{lsp::LinkedEditingRangeEvent, Handlers},
+    Document, DocumentId, Editor, ViewId,
+};
+use tokio::time::Instant;
+
+use crate::events::{OnModeSwitch, PostCommand, PostInsertChar};
+use crate::job::{dispatch, dispatch_blocking};
+
+const CURSOR_MOVE_DEBOUNCE: Duration = Duration::from_millis(120);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Trigger {
+    doc: DocumentId,
+    view: ViewId,
+    cursor: usize,
+}
+
+#[derive(Default)]
+pub(super) struct LinkedEditingHandler {
+    /// The currently active trigger which will cause a request after the timeout.
+    trigger: Option<Trigger>,
+    /// The trigger of the last request. The ranges are only requested again once the cursor
+    /// moved to another position.
+    requested: Option<Trigger>,
+    task_controller: TaskController,
+}
+
+impl helix_event::AsyncHook for LinkedEditingHandler {
+    type Event = LinkedEditingRangeEvent;
+
+    fn handle_event(&mut self, event: Self::Event, _timeout: Option<Instant>) -> Option<Instant> {
+        match event {
+            LinkedEditingRangeEvent::Trigger { doc, view, cursor } => {
+                let trigger = Trigger { doc, view, cursor };
+                if self.requested == Some(trigger) {
+                    // The cursor moved back to the position requested last, the request in
+                    // flight is still valid.
+                    self.trigger = None;
+                    return None;
+                }
+                self.trigger = Some(trigger);
+                Some(Instant::now() + CURSOR_MOVE_DEBOUNCE)
+            }
+            LinkedEditingRangeEvent::Cancel => {
+                self.trigger = None;
+                self.requested = None;
+                self.task_controller.cancel();
+                None
+            }
+        }
+    }
+
+    fn finish_debounce(&mut self) {
+        let Some(trigger) = self.trigger.take() else {
+            return;
+        };
+        self.requested = Some(trigger);
+        let handle = self.task_controller.restart();
+        dispatch_blocking(move |editor, _| request_linked_editing_ranges(editor, trigger, handle));
+    }
+}
+
+fn request_linked_editing_ranges(editor: &mut Editor, trigger: Trigger, handle: TaskHandle) {
+    if editor.mode() != Mode::Insert {
+        return;
+    }
+    let (view, doc) = current_ref!(editor);
+    if trigger.view != view.id || trigger.doc != doc.id() || doc.linked_editing_ranges.is_some() {
+        return;
+    }
+    let Some(language_server) = doc
+        .language_servers_with_feature(LanguageServerFeature::LinkedEditingRange)
+        .next()
+    else {
+        return;
+    };
+
+    let offset_encoding = language_server.offset_encoding();
+    let pos = doc.position(view.id, offset_encoding);
+    let Some(future) = language_server.linked_editing_range(doc.identifier(), pos, None) else {
+        return;
+    };
+    let (view_id, doc_id, version) = (view.id, doc.id(), doc.version());
+
+    tokio::spawn(async move {
+        match cancelable_future(future, handle).await {
+            Some(Ok(Some(ranges))) => {
+                dispatch(move |editor, _| {
+                    attach_linked_editing_ranges(
+                        editor,
+                        (view_id, doc_id, version),
+                        ranges,
+                        offset_encoding,
+                    )
+                })
+                .await
+            }
+            Some(Ok(None)) => (),
+            Some(Err(err)) => log::error!("linked editing range request failed: {err}"),
+            // The request was cancelled.
+            None => (),
+        }
+    });
+}
+
+/// Requests the linked editing ranges at the cursor after the debounce timeout.
+fn trigger_linked_editing_ranges(editor: &mut Editor) {
+    let (view, doc) = current_ref!(editor);
+    if doc.linked_editing_ranges.is_some()
+        || !doc.has_language_server_with_feature(LanguageServerFeature::LinkedEditingRange)
+    {
+        return;
+    }
+    let event = LinkedEditingRangeEvent::Trigger {
+        doc: doc.id(),
+        view: view.id,
+        cursor: doc
+            .selection(view.id)
+            .primary()
+            .cursor(doc.text().slice(..)),
+    };
+    send_blocking(&editor.handlers.linked_editing_ranges, event);
+}
+
+fn attach_linked_editing_ranges(
+    editor: &mut Editor,
+    (view_id, doc_id, version): (ViewId, DocumentId, i32),
+    ranges: lsp::LinkedEditingRanges,
+    offset_encoding: OffsetEncoding,
+) {
+    // The ranges are only linked while in insert mode.
+    if editor.mode() != Mode::Insert {
+        return;
+    }
+    let Some(doc) = editor.documents.get_mut(&doc_id) else {
+        return;
+    };
+    if !doc.selections().contains_key(&view_id) {
+        return;
+    }
+    if doc.version() != version {
+        // The document changed while the ranges were requested, for example by typing right
+        // after entering insert mode. The edit moved the cursor, so the ranges are requested
+        // again once the debounce timeout elapsed.
+        return;
+    }
+
+    let text = doc.text();
+    let mut linked_ranges: Vec<_> = ranges
+        .ranges
+        .into_iter()
+        .filter_map(|range| lsp_range_to_range(text, range, offset_encoding))
+        .map(|range| range.from()..range.to())
+        .collect();
+    linked_ranges.sort_by_key(|range| range.start);
+    if linked_ranges.len() < 2
+        || linked_ranges
+            .windows(2)
+            .any(|ranges| ranges[0].end > ranges[1].start)
+    {
+        return;
+    }
+
+    let cursor = doc.selection(view_id).primary().cursor(text.slice(..));
+    if !linked_ranges
+        .iter()
+        .any(|range| range.start <= cursor && cursor <= range.end)
+    {
+        return;
+    }
+
+    let word_pattern = ranges.word_pattern.and_then(|pattern| {
+        // The content of the ranges has to match the pattern as a whole.
+        match Regex::new(&format!("^(?:{pattern})$")) {
+            Ok(regex) => Some(regex),
+            Err(err) => {
+                log::warn!("invalid linked editing word pattern '{pattern}': {err}");
+                None
+            }
+        }
+    });
+
+    doc.linked_editing_ranges = Some(LinkedEditingRanges {
+        view_id,
+        ranges: linked_ranges,
+        word_pattern,
+    });
+}
+
+/// Copies the content of the linked range containing the cursor into the other linked ranges.
+fn sync_linked_editing_ranges(editor: &mut Editor) {
+    let (view, doc) = current!(editor);
+    let Some(linked) = &doc.linked_editing_ranges else {
+        return;
+    };
+    if linked.view_id != view.id {
+        return;
+    }
+
+    let text = doc.text().slice(..);
+    let cursor = doc.selection(view.id).primary().cursor(text);
+    let Some(source) = linked
+        .ranges
+        .iter()
+        .find(|range| range.start <= cursor && cursor <= range.end)
+    else {
+        return;
+    };
+
+    let content = text.slice(source.clone());
+    let changes: Vec<_> = linked
+        .ranges
+        .iter()
+        .filter(|range| *range != source && text.slice((*range).clone()) != content)
+        .map(|range| {
+            (
+                range.start,
+                range.end,
+                Some(Tendril::from(Cow::from(content))),
+            )
+        })
+        .collect();
+    if changes.is_empty() {
+        return;
+    }
+
+    let transaction = Transaction::change(doc.text(), changes.into_iter());
+    doc.apply(&transaction, view.id);
+}
+
+/// Maps the linked ranges of `doc` through `changes`. The linking stops if the changes leave
+/// the ranges or the content of the ranges doesn't match the word pattern anymore.
+fn update_linked_editing_ranges(doc: &mut Document, changes: &ChangeSet) {
+    let Some(mut linked) = doc.linked_editing_ranges.take() else {
+        return;
+    };
+
+    // Stop linking once an edit leaves the ranges.
+    let inside_ranges = changes.changes_iter().all(|(from, to, _)| {
+        linked
+            .ranges
+            .iter()
+            .any(|range| range.start <= from && to <= range.end)
+    });
+    if !inside_ranges {
+        return;
+    }
+
+    // Text inserted at the boundaries of a range extends it.
+    changes.update_positions(linked.ranges.iter_mut().flat_map(|range| {
+        [
+            (&mut range.start, helix_core::Assoc::Before),
+            (&mut range.end, helix_core::Assoc::After),
+        ]
+    }));
+
+    let text = doc.text().slice(..);
+    let matches_pattern = linked.word_pattern.as_ref().is_none_or(|word_pattern| {
+        linked
+            .ranges
+            .iter()
+            .all(|range| word_pattern.is_match(&Cow::from(text.slice(range.clone()))))
+    });
+    if matches_pattern {
+        doc.linked_editing_ranges = Some(linked);
+    }
+}
+
+pub(super) fn register_hooks(_handlers: &Handlers) {
+    register_hook!(move |event: &mut OnModeSwitch<'_, '_>| {
+        if event.old_mode == Mode::Insert {
+            send_blocking(
+                &event.cx.editor.handlers.linked_editing_ranges,
+                LinkedEditingRangeEvent::Cancel,
+            );
+            doc_mut!(event.cx.editor).linked_editing_ranges = None;
+        }
+
+        Ok(())
+    });
+
+    register_hook!(move |event: &mut PostInsertChar<'_, '_>| {
+        sync_linked_editing_ranges(event.cx.editor);
+
+        Ok(())
+    });
+
+    register_hook!(move |event: &mut PostCommand<'_, '_>| {
+        if event.cx.editor.mode() != Mode::Insert {
+            return Ok(());
+        }
+        // The ranges are requested when entering insert mode and whenever the cursor moves
+        // while there are none, for example into another tag.
+        if doc!(event.cx.editor).linked_editing_ranges.is_some() {
+            sync_linked_editing_ranges(event.cx.editor);
+        } else {
+            trigger_linked_editing_ranges(event.cx.editor);
+        }
+
+        Ok(())
+    });
+
+    register_hook!(move |event: &mut DocumentDidChange<'_>| {
+        update_linked_editing_ranges(event.doc, event.changes);
+
+        Ok(())
+    });
+
+    register_hook!(move |event: &mut SelectionDidChange<'_>| {
+        let Some(linked) = &event.doc.linked_editing_ranges else {
+            return Ok(());
+        };
+        if linked.view_id != event.view {
+            return Ok(());
+        }
+
+        // Stop linking once the cursor leaves the ranges.
+        let text = event.doc.text().slice(..);
+        let cursor = event.doc.selection(event.view).primary().cursor(text);
+        if !linked
+            .ranges
+            .iter()
+            .any(|range| range.start <= cursor && cursor <= range.end)
+        {
+            event.doc.linked_editing_ranges = None;
+        }
+
+        Ok(())
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use helix_core::Selection;
+    use helix_event::AsyncHook;
+
+    use super::*;
+    use crate::handlers::test_editor;
+
+    /// An editor with `<div></div>` in insert mode and the cursor inside the opening tag.
+    fn editor() -> Editor {
+        let mut editor = test_editor();
+        let (view, doc) = current!(editor);
+        let transaction =
+            Transaction::insert(doc.text(), &Selection::point(0), "<div></div>".into());
+        doc.apply(&transaction, view.id);
+        doc.set_selection(view.id, Selection::point(2));
+        editor.mode = Mode::Insert;
+        editor
+    }
+
+    fn attach(editor: &mut Editor, version: i32) {
+        let (view, doc) = current_ref!(editor);
+        let request = (view.id, doc.id(), version);
+        let range =
+            |start, end| lsp::Range::new(lsp::Position::new(0, start), lsp::Position::new(0, end));
+        let ranges = lsp::LinkedEditingRanges {
+            ranges: vec![range(7, 10), range(1, 4)],
+            word_pattern: Some("[a-z]+".to_string()),
+        };
+        attach_linked_editing_ranges(editor, request, ranges, OffsetEncoding::Utf8);
+    }
+
+    fn linked_ranges(editor: &Editor) -> Option<Vec<std::ops::Range<usize>>> {
+        let linked = doc!(editor).linked_editing_ranges.as_ref()?;
+        Some(linked.ranges.clone())
+    }
+
+    #[tokio::test]
+    async fn attach_ranges() {
+        let mut editor = editor();
+        let version = doc!(editor).version();
+        attach(&mut editor, version);
+        assert_eq!(linked_ranges(&editor), Some(vec![1..4, 7..10]));
+
+        // A response for an older version of the document is not attached.
+        doc_mut!(editor).linked_editing_ranges = None;
+        attach(&mut editor, version - 1);
+        assert_eq!(linked_ranges(&editor), None);
+
+        // The ranges are only linked in insert mode.
+        editor.mode = Mode::Normal;
+        attach(&mut editor, version);
+        assert_eq!(linked_ranges(&editor), None);
+    }
+
+    #[tokio::test]
+    async fn map_ranges_through_changes() {
+        let mut editor = editor();
+        let version = doc!(editor).version();
+        attach(&mut editor, version);
+
+        // Text inserted at the end of a range extends it and moves the following ranges.
+        let (view, doc) = current!(editor);
+        let transaction = Transaction::insert(doc.text(), &Selection::point(4), "x".into());
+        doc.apply(&transaction, view.id);
+        update_linked_editing_ranges(doc, transaction.changes());
+        assert_eq!(linked_ranges(&editor), Some(vec![1..5, 8..11]));
+
+        // The linking stops when the content doesn't match the word pattern anymore.
+        let (view, doc) = current!(editor);
+        let transaction = Transaction::insert(doc.text(), &Selection::point(2), " ".into());
+        doc.apply(&transaction, view.id);
+        update_linked_editing_ranges(doc, transaction.changes());
+        assert_eq!(linked_ranges(&editor), None);
+
+        // And when an edit leaves the ranges.
+        let version = doc!(editor).version();
+        attach(&mut editor, version);
+        assert!(linked_ranges(&editor).is_some());
+        let (view, doc) = current!(editor);
+        let transaction = Transaction::insert(doc.text(), &Selection::point(0), "x".into());
+        doc.apply(&transaction, view.id);
+        update_linked_editing_ranges(doc, transaction.changes());
+        assert_eq!(linked_ranges(&editor), None);
+    }
+
+    #[tokio::test]
+    async fn request_when_cursor_moved() {
+        let editor = editor();
+        let (view, doc) = current_ref!(editor);
+        let (view, doc) = (view.id, doc.id());
+        let trigger = |cursor| LinkedEditingRangeEvent::Trigger { doc, view, cursor };
+        let mut handler = LinkedEditingHandler::default();
+        assert!(handler.handle_event(trigger(2), None).is_some());
+
+        // Simulate the elapsed debounce, which requests the ranges for the pending trigger.
+        handler.requested = handler.trigger.take();
+
+        // The ranges aren't requested again while the cursor stays at the same position.
+        assert!(handler.handle_event(trigger(2), None).is_none());
+        assert!(handler.trigger.is_none());
+
+        // But once it moved.
+        assert!(handler.handle_event(trigger(3), None).is_some());
+        // Moving back to the requested position drops the pending trigger.
+        assert!(handler.handle_event(trigger(2), None).is_none());
+        assert!(handler.trigger.is_none());
+
+        // Leaving insert mode forgets the last request.
+        assert!(handler
+            .handle_event(LinkedEditingRangeEvent::Cancel, None)
+            .is_none());
+        assert!(handler.handle_event(trigger(2), None).is_some());
+    }
+}
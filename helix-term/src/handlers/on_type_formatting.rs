@@ -0,0 +1,94 @@
+use helix_core::{indent::IndentStyle, syntax::config::LanguageServerFeature};
+use helix_event::register_hook;
+use helix_lsp::{lsp, util::generate_transaction_from_edits, OffsetEncoding};
+use helix_view::{document::Mode, handlers::Handlers, DocumentId, Editor, ViewId};
+
+use crate::events::{PostCommand, PostInsertChar};
+use crate::job;
+
+/// Asks the language server to format the document after one of its trigger characters
+/// has been typed.
+fn request_on_type_formatting(editor: &mut Editor, ch: char) {
+    let (view, doc) = current_ref!(editor);
+    let ch = ch.to_string();
+    let options = lsp::FormattingOptions {
+        tab_size: doc.tab_width() as u32,
+        insert_spaces: matches!(doc.indent_style, IndentStyle::Spaces(_)),
+        ..Default::default()
+    };
+
+    let Some((future, offset_encoding)) = doc
+        .language_servers_with_feature(LanguageServerFeature::OnTypeFormatting)
+        .find_map(|language_server| {
+            let offset_encoding = language_server.offset_encoding();
+            let future = language_server.text_document_on_type_formatting(
+                doc.identifier(),
+                doc.position(view.id, offset_encoding),
+                ch.clone(),
+                options.clone(),
+            )?;
+            Some((future, offset_encoding))
+        })
+    else {
+        return;
+    };
+    let (view_id, doc_id, version) = (view.id, doc.id(), doc.version());
+
+    tokio::spawn(async move {
+        match future.await {
+            Ok(Some(edits)) if !edits.is_empty() => {
+                job::dispatch(move |editor, _| {
+                    apply_on_type_formatting(
+                        editor,
+                        (view_id, doc_id, version),
+                        edits,
+                        offset_encoding,
+                    )
+                })
+                .await
+            }
+            Ok(_) => (),
+            Err(err) => log::error!("on type formatting request failed: {err}"),
+        }
+    });
+}
+
+fn apply_on_type_formatting(
+    editor: &mut Editor,
+    (view_id, doc_id, version): (ViewId, DocumentId, i32),
+    edits: Vec<lsp::TextEdit>,
+    offset_encoding: OffsetEncoding,
+) {
+    // The edits are only merged into the insert they belong to, they are discarded once the
+    // insert was committed to the history.
+    if editor.mode() != Mode::Insert {
+        return;
+    }
+    let Some(doc) = editor.documents.get_mut(&doc_id) else {
+        return;
+    };
+    // The edits are out of date, the user kept typing.
+    if doc.version() != version || !doc.selections().contains_key(&view_id) {
+        return;
+    }
+
+    let transaction = generate_transaction_from_edits(doc.text(), edits, offset_encoding);
+    doc.apply(&transaction, view_id);
+}
+
+pub(super) fn register_hooks(_handlers: &Handlers) {
+    register_hook!(move |event: &mut PostInsertChar<'_, '_>| {
+        request_on_type_formatting(event.cx.editor, event.c);
+
+        Ok(())
+    });
+
+    register_hook!(move |event: &mut PostCommand<'_, '_>| {
+        // Line breaks are inserted by a command rather than by `insert_char`.
+        if event.cx.editor.mode() == Mode::Insert && event.command.name() == "insert_newline" {
+            request_on_type_formatting(event.cx.editor, '\n');
+        }
+
+        Ok(())
+    });
+}
@@ -0,0 +1,358 @@
+use std::{collections::HashSet, time::Duration};
+
+use futures_util::{future::BoxFuture, FutureExt};
+use helix_core::syntax::config::LanguageServerFeature;
+use helix_event::{cancelable_future, register_hook};
+use helix_lsp::{lsp, util, LanguageServerId};
+use helix_view::{
+    document::{DocumentSemanticTokens, SemanticToken},
+    events::{DocumentDidChange, DocumentDidOpen, LanguageServerExited, LanguageServerInitialized},
+    handlers::{lsp::SemanticTokensEvent, Handlers},
+    Document, DocumentId, Editor,
+};
+use tokio::time::Instant;
+
+use crate::job;
+
+#[derive(Default)]
+pub(super) struct SemanticTokensHandler {
+    docs: HashSet<DocumentId>,
+}
+
+const DOCUMENT_CHANGE_DEBOUNCE: Duration = Duration::from_millis(250);
+
+impl helix_event::AsyncHook for SemanticTokensHandler {
+    type Event = SemanticTokensEvent;
+
+    fn handle_event(&mut self, event: Self::Event, _timeout: Option<Instant>) -> Option<Instant> {
+        let SemanticTokensEvent(doc_id) = event;
+        self.docs.insert(doc_id);
+        Some(Instant::now() + DOCUMENT_CHANGE_DEBOUNCE)
+    }
+
+    fn finish_debounce(&mut self) {
+        let docs = std::mem::take(&mut self.docs);
+
+        job::dispatch_blocking(move |editor, _compositor| {
+            for doc in docs {
+                request_semantic_tokens(editor, doc);
+            }
+        });
+    }
+}
+
+enum SemanticTokensResponse {
+    Full {
+        result_id: Option<String>,
+        data: Vec<lsp::SemanticToken>,
+    },
+    Delta {
+        previous_result_id: String,
+        result_id: Option<String>,
+        edits: Vec<lsp::SemanticTokensEdit>,
+    },
+}
+
+impl From<lsp::SemanticTokensResult> for SemanticTokensResponse {
+    fn from(result: lsp::SemanticTokensResult) -> Self {
+        match result {
+            lsp::SemanticTokensResult::Tokens(tokens) => Self::Full {
+                result_id: tokens.result_id,
+                data: tokens.data,
+            },
+            lsp::SemanticTokensResult::Partial(partial) => Self::Full {
+                result_id: None,
+                data: partial.data,
+            },
+        }
+    }
+}
+
+impl From<lsp::SemanticTokensRangeResult> for SemanticTokensResponse {
+    fn from(result: lsp::SemanticTokensRangeResult) -> Self {
+        match result {
+            lsp::SemanticTokensRangeResult::Tokens(tokens) => Self::Full {
+                // Range responses can't be used as the base for deltas.
+                result_id: None,
+                data: tokens.data,
+            },
+            lsp::SemanticTokensRangeResult::Partial(partial) => Self::Full {
+                result_id: None,
+                data: partial.data,
+            },
+        }
+    }
+}
+
+type SemanticTokensFuture = BoxFuture<'static, helix_lsp::Result<Option<SemanticTokensResponse>>>;
+
+/// Prefers a delta against the previous response, then the full document and finally falls back
+/// to requesting the range covering the whole document.
+fn semantic_tokens_request(
+    doc: &Document,
+    language_server: &helix_lsp::Client,
+    previous_result_id: Option<String>,
+) -> Option<SemanticTokensFuture> {
+    if let Some(previous_result_id) = previous_result_id {
+        if let Some(future) = language_server.text_document_semantic_tokens_full_delta(
+            doc.identifier(),
+            previous_result_id.clone(),
+            None,
+        ) {
+            return Some(
+                async move {
+                    let response = future.await?.map(|result| match result {
+                        lsp::SemanticTokensFullDeltaResult::Tokens(tokens) => {
+                            SemanticTokensResponse::Full {
+                                result_id: tokens.result_id,
+                                data: tokens.data,
+                            }
+                        }
+                        lsp::SemanticTokensFullDeltaResult::TokensDelta(delta) => {
+                            SemanticTokensResponse::Delta {
+                                previous_result_id,
+                                result_id: delta.result_id,
+                                edits: delta.edits,
+                            }
+                        }
+                        lsp::SemanticTokensFullDeltaResult::PartialTokensDelta { edits } => {
+                            SemanticTokensResponse::Delta {
+                                previous_result_id,
+                                result_id: None,
+                                edits,
+                            }
+                        }
+                    });
+                    Ok(response)
+                }
+                .boxed(),
+            );
+        }
+    }
+
+    if let Some(future) = language_server.text_document_semantic_tokens_full(doc.identifier(), None)
+    {
+        return Some(async move { Ok(future.await?.map(SemanticTokensResponse::from)) }.boxed());
+    }
+
+    let text = doc.text();
+    let range = util::range_to_lsp_range(
+        text,
+        helix_core::Range::new(0, text.len_chars()),
+        language_server.offset_encoding(),
+    );
+    let future =
+        language_server.text_document_semantic_tokens_range(doc.identifier(), range, None)?;
+    Some(async move { Ok(future.await?.map(SemanticTokensResponse::from)) }.boxed())
+}
+
+pub fn request_semantic_tokens(editor: &mut Editor, doc_id: DocumentId) {
+    if !editor.config().lsp.display_semantic_tokens {
+        return;
+    }
+
+    let Some(doc) = editor.document_mut(doc_id) else {
+        return;
+    };
+
+    let Some(language_server) = doc
+        .language_servers_with_feature(LanguageServerFeature::SemanticTokens)
+        .next()
+    else {
+        doc.semantic_tokens.take();
+        return;
+    };
+    let Some(legend) = language_server.semantic_tokens_legend() else {
+        return;
+    };
+
+    let language_server_id = language_server.id();
+    let token_types: Vec<_> = legend
+        .token_types
+        .iter()
+        .map(|token_type| format!("lsp.type.{}", token_type.as_str()))
+        .collect();
+    let token_modifiers: Vec<_> = legend
+        .token_modifiers
+        .iter()
+        .map(|modifier| format!("lsp.mod.{}", modifier.as_str()))
+        .collect();
+
+    let previous_result_id = doc
+        .semantic_tokens
+        .as_ref()
+        .filter(|tokens| tokens.language_server_id == language_server_id)
+        .and_then(|tokens| tokens.result_id.clone());
+    let Some(future) = semantic_tokens_request(doc, language_server, previous_result_id) else {
+        return;
+    };
+
+    let offset_encoding = language_server.offset_encoding();
+    let version = doc.version();
+    let cancel = doc.semantic_tokens_controller.restart();
+
+    tokio::spawn(async move {
+        let response = match cancelable_future(future, &cancel).await {
+            Some(Ok(response)) => response,
+            Some(Err(err)) => {
+                log::error!("semantic tokens request failed: {err}");
+                return;
+            }
+            // The request was cancelled.
+            None => return,
+        };
+
+        job::dispatch(move |editor, _| {
+            let Some(doc) = editor.documents.get_mut(&doc_id) else {
+                return;
+            };
+            // The tokens are out of date if the document changed in the meantime. A new request
+            // has been queued by the change.
+            if doc.version() != version {
+                return;
+            }
+
+            let (result_id, data) = match response {
+                Some(SemanticTokensResponse::Full { result_id, data }) => (result_id, data),
+                Some(SemanticTokensResponse::Delta {
+                    previous_result_id,
+                    result_id,
+                    edits,
+                }) => {
+                    let Some(mut data) = doc
+                        .semantic_tokens
+                        .take()
+                        .filter(|tokens| {
+                            tokens.language_server_id == language_server_id
+                                && tokens.result_id.as_ref() == Some(&previous_result_id)
+                        })
+                        .map(|tokens| tokens.data)
+                    else {
+                        // The base of the delta is gone, start over with a full request.
+                        request_semantic_tokens(editor, doc_id);
+                        return;
+                    };
+                    util::apply_semantic_tokens_edits(&mut data, edits);
+                    (result_id, data)
+                }
+                None => {
+                    doc.semantic_tokens.take();
+                    return;
+                }
+            };
+
+            attach_semantic_tokens(
+                editor,
+                doc_id,
+                language_server_id,
+                result_id,
+                data,
+                token_types,
+                token_modifiers,
+                offset_encoding,
+            );
+        })
+        .await;
+    });
+}
+
+#[allow(clippy::too_many_arguments)]
+fn attach_semantic_tokens(
+    editor: &mut Editor,
+    doc_id: DocumentId,
+    language_server_id: LanguageServerId,
+    result_id: Option<String>,
+    data: Vec<lsp::SemanticToken>,
+    token_types: Vec<String>,
+    token_modifiers: Vec<String>,
+    offset_encoding: helix_lsp::OffsetEncoding,
+) {
+    let Some(doc) = editor.documents.get_mut(&doc_id) else {
+        return;
+    };
+
+    let mut tokens: Vec<_> =
+        util::lsp_semantic_tokens_to_ranges(doc.text(), &data, offset_encoding)
+            .map(|(range, token_type, token_modifiers)| SemanticToken {
+                range,
+                token_type,
+                token_modifiers,
+            })
+            .collect();
+    // Servers shouldn't send overlapping tokens since we don't advertise support for them but
+    // the overlays used to render tokens require non-overlapping ranges.
+    tokens.sort_by_key(|token| token.range.start);
+    tokens.dedup_by(|token, prev| token.range.start < prev.range.end);
+
+    doc.semantic_tokens = Some(DocumentSemanticTokens {
+        language_server_id,
+        result_id,
+        data,
+        tokens,
+        token_types,
+        token_modifiers,
+    });
+}
+
+pub(super) fn register_hooks(handlers: &Handlers) {
+    register_hook!(move |event: &mut DocumentDidOpen<'_>| {
+        // when a document is initially opened, request semantic tokens for it
+        request_semantic_tokens(event.editor, event.doc);
+
+        Ok(())
+    });
+
+    let tx = handlers.semantic_tokens.clone();
+    register_hook!(move |event: &mut DocumentDidChange<'_>| {
+        // Map the tokens through the change so they stay roughly in place until the server
+        // sends the updated tokens.
+        if let Some(semantic_tokens) = &mut event.doc.semantic_tokens {
+            event
+                .changes
+                .update_positions(semantic_tokens.tokens.iter_mut().flat_map(|token| {
+                    [
+                        (&mut token.range.start, helix_core::Assoc::After),
+                        (&mut token.range.end, helix_core::Assoc::Before),
+                    ]
+                }));
+            semantic_tokens
+                .tokens
+                .retain(|token| token.range.start < token.range.end);
+        }
+
+        // Ghost transactions (completion previews) aren't sent to the language server, so any
+        // tokens it returns would be out of date.
+        if !event.ghost_transaction {
+            // Cancel the ongoing request, if present.
+            event.doc.semantic_tokens_controller.cancel();
+            helix_event::send_blocking(&tx, SemanticTokensEvent(event.doc.id()));
+        }
+
+        Ok(())
+    });
+
+    register_hook!(move |event: &mut LanguageServerInitialized<'_>| {
+        let doc_ids: Vec<_> = event.editor.documents().map(|doc| doc.id()).collect();
+
+        for doc_id in doc_ids {
+            request_semantic_tokens(event.editor, doc_id);
+        }
+
+        Ok(())
+    });
+
+    register_hook!(move |event: &mut LanguageServerExited<'_>| {
+        // Drop the tokens provided by the server that exited.
+        for doc in event.editor.documents_mut() {
+            if doc
+                .semantic_tokens
+                .as_ref()
+                .is_some_and(|tokens| tokens.language_server_id == event.server_id)
+            {
+                doc.semantic_tokens.take();
+            }
+        }
+
+        Ok(())
+    });
+}
@@ -1,5 +1,6 @@
 use crate::config::{Config, ConfigLoadError};
 use helix_core::config::{default_lang_config, user_lang_config};
+use helix_core::syntax::config::LanguageServerConfiguration;
 use helix_loader::grammar::load_runtime_file;
 use std::{
     collections::HashSet,
@@ -224,6 +225,17 @@ fn languages(selection: Option<HashSet<String>>) -> std::io::Result<()> {
 
     let check_binary = |cmd: Option<&str>| check_binary_with_name(cmd.map(|cmd| (cmd, cmd)));
 
+    let check_language_server =
+        |language_server: Option<(&str, &LanguageServerConfiguration)>| match language_server {
+            // Language servers without a command connect to a running server.
+            Some((name, config)) if config.connect.is_some() && config.command.is_empty() => {
+                color(fit(&format!("✓ {}", name)), ColorSpec::BRIGHT_GREEN)
+            }
+            _ => check_binary_with_name(
+                language_server.map(|(name, config)| (name, config.command.as_str())),
+            ),
+        };
+
     for lang in &syn_loader_conf.language {
         if selection
             .as_ref()
@@ -234,13 +246,13 @@ fn languages(selection: Option<HashSet<String>>) -> std::io::Result<()> {
 
         write!(stdout, "{}", fit(&lang.language_id))?;
 
-        let mut cmds = lang.language_servers.iter().filter_map(|ls| {
+        let mut language_servers = lang.language_servers.iter().filter_map(|ls| {
             syn_loader_conf
                 .language_server
                 .get(&ls.name)
-                .map(|config| (ls.name.as_str(), config.command.as_str()))
+                .map(|config| (ls.name.as_str(), config))
         });
-        write!(stdout, "{}", check_binary_with_name(cmds.next()))?;
+        write!(stdout, "{}", check_language_server(language_servers.next()))?;
 
         let dap = lang.debugger.as_ref().map(|dap| dap.command.as_str());
         write!(stdout, "{}", check_binary(dap))?;
@@ -260,9 +272,9 @@ fn languages(selection: Option<HashSet<String>>) -> std::io::Result<()> {
 
         writeln!(stdout)?;
 
-        for cmd in cmds {
+        for language_server in language_servers {
             write!(stdout, "{}", fit(""))?;
-            writeln!(stdout, "{}", check_binary_with_name(Some(cmd)))?;
+            writeln!(stdout, "{}", check_language_server(Some(language_server)))?;
         }
     }
 
@@ -333,7 +345,7 @@ pub fn language(lang_str: String) -> std::io::Result<()> {
             syn_loader_conf
                 .language_server
                 .get(&ls.name)
-                .map(|config| (ls.name.as_str(), config.command.as_str()))
+                .map(|config| (ls.name.as_str(), config))
         }),
     )?;
 
@@ -371,7 +383,7 @@ fn probe_parser(grammar_name: &str) -> std::io::Result<()> {
 }
 
 /// Display diagnostics about multiple LSPs and DAPs.
-fn probe_protocols<'a, I: Iterator<Item = (&'a str, &'a str)> + 'a>(
+fn probe_protocols<'a, I: Iterator<Item = (&'a str, &'a LanguageServerConfiguration)> + 'a>(
     protocol_name: &str,
     server_cmds: I,
 ) -> std::io::Result<()> {
@@ -386,10 +398,13 @@ fn probe_protocols<'a, I: Iterator<Item = (&'a str, &'a str)> + 'a>(
     }
     writeln!(stdout)?;
 
-    for (name, cmd) in server_cmds {
-        let (diag, icon) = match helix_stdx::env::which(cmd) {
-            Ok(path) => (path.display().to_string().green(), "✓".green()),
-            Err(_) => (format!("'{}' not found in $PATH", cmd).red(), "✘".red()),
+    for (name, config) in server_cmds {
+        let (diag, icon) = match (&config.connect, config.command.as_str()) {
+            (Some(connection), "") => (format!("connects to {connection}").green(), "✓".green()),
+            (_, cmd) => match helix_stdx::env::which(cmd) {
+                Ok(path) => (path.display().to_string().green(), "✓".green()),
+                Err(_) => (format!("'{}' not found in $PATH", cmd).red(), "✘".red()),
+            },
         };
         writeln!(stdout, "  {} {}: {}", icon, name, diag)?;
     }
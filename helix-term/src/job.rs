@@ -169,3 +169,15 @@ pub async fn finish(
         Ok(())
     }
 }
+
+/// Returns the job queue shared by the unit tests. `dispatch` and `dispatch_blocking` send
+/// their callbacks to the first queue created in the process, so the tests must not create
+/// their own. Holding the guard keeps other tests from taking the dispatched callbacks.
+#[cfg(test)]
+pub(crate) fn test_jobs() -> std::sync::MutexGuard<'static, Jobs> {
+    use std::sync::{Mutex, PoisonError};
+
+    static JOBS: once_cell::sync::Lazy<Mutex<Jobs>> =
+        once_cell::sync::Lazy::new(|| Mutex::new(Jobs::new()));
+    JOBS.lock().unwrap_or_else(PoisonError::into_inner)
+}
@@ -249,14 +249,18 @@ pub fn default() -> HashMap<Mode, KeyTrie> {
                 "l" => dap_launch,
                 "r" => dap_restart,
                 "b" => dap_toggle_breakpoint,
+                "B" => dap_breakpoint_picker,
                 "c" => dap_continue,
                 "h" => dap_pause,
                 "i" => dap_step_in,
                 "o" => dap_step_out,
                 "n" => dap_next,
                 "v" => dap_variables,
+                "d" => dap_console,
+                "w" => dap_sidebar,
                 "t" => dap_terminate,
                 "C-c" => dap_edit_condition,
+                "H" => dap_edit_hit_condition,
                 "C-l" => dap_edit_log,
                 "s" => { "Switch"
                     "t" => dap_switch_thread,
@@ -299,11 +303,16 @@ pub fn default() -> HashMap<Mode, KeyTrie> {
                 "a" => code_action,
                 "d" => diagnostics_picker,
                 "D" => workspace_diagnostics_picker,
+                "i" => incoming_calls,
                 "k" => hover,
+                "l" => code_lens,
+                "o" => outgoing_calls,
                 "r" => rename_symbol,
                 "R" => select_references_to_symbol_under_cursor,
                 "s" => lsp_or_syntax_symbol_picker,
                 "S" => lsp_or_syntax_workspace_symbol_picker,
+                "t" => supertypes,
+                "T" => subtypes,
             },
             "t" => { "Toggle"
                 "_" => no_op, // placeholder
@@ -333,6 +342,9 @@ pub fn default() -> HashMap<Mode, KeyTrie> {
             "t" => align_view_top,
             "b" => align_view_bottom,
             "m" => align_view_middle,
+            "a" => toggle_fold,
+            "M" => fold_all,
+            "R" => unfold_all,
             "e" | "up" => scroll_up,
             "n" | "down" => scroll_down,
             "C-E" | "C-b" | "pageup" => page_up,
@@ -350,6 +362,9 @@ pub fn default() -> HashMap<Mode, KeyTrie> {
             "t" => align_view_top,
             "b" => align_view_bottom,
             "m" => align_view_middle,
+            "a" => toggle_fold,
+            "M" => fold_all,
+            "R" => unfold_all,
             "e" | "up" => scroll_up,
             "n" | "down" => scroll_down,
             "C-E" | "C-b" | "pageup" => page_up,
@@ -421,6 +436,10 @@ pub fn default() -> HashMap<Mode, KeyTrie> {
         "C-s" => commit_undo_checkpoint,
         "C-x" => completion,
         "C-r" => insert_register,
+        "A-l" => accept_inline_completion,
+        "A-w" => accept_inline_completion_word,
+        "A-n" => next_inline_completion,
+        "A-p" => prev_inline_completion,
 
         "C-w" | "A-backspace" => delete_word_backward,
         "A-d" | "A-del" => delete_word_forward,
@@ -0,0 +1,570 @@
+use helix_core::Selection;
+use helix_view::debug_console::{ConsoleCategory, ConsoleEntry, ConsoleSource};
+use helix_view::editor::Action;
+use helix_view::graphics::{Color, CursorKind, Margin, Modifier, Rect, UnderlineStyle};
+use helix_view::handlers::dap::dap_pos_to_pos;
+use helix_view::input::Event;
+use helix_view::theme::{Style, Theme};
+use helix_view::{align_view, Align, Editor};
+use tui::buffer::Buffer as Surface;
+use tui::text::Span;
+use tui::widgets::{Block, Widget};
+
+use crate::compositor::{Component, Context, EventResult};
+use crate::job::Callback;
+use crate::ui::prompt::{Completion, CompletionDirection, Prompt, PromptEvent};
+use crate::{alt, ctrl, key};
+
+pub const ID: &str = "debug-console";
+
+/// Splits a line of program output into styled segments, applying the SGR escape sequences
+/// (colors and text attributes) on top of `base`. Other escape sequences are dropped.
+fn ansi_spans(line: &str, base: Style) -> Vec<(String, Style)> {
+    let mut spans = Vec::new();
+    let mut style = base;
+    let mut text = String::new();
+    let mut chars = line.chars().peekable();
+
+    while let Some(ch) = chars.next() {
+        if ch != '\x1b' {
+            text.push(ch);
+            continue;
+        }
+        if chars.next_if_eq(&'[').is_none() {
+            // Not a control sequence: drop the escape and the character following it.
+            chars.next();
+            continue;
+        }
+        let mut params = String::new();
+        let mut terminator = None;
+        for ch in chars.by_ref() {
+            if ('\x40'..='\x7e').contains(&ch) {
+                terminator = Some(ch);
+                break;
+            }
+            params.push(ch);
+        }
+        if terminator != Some('m') {
+            continue;
+        }
+        if !text.is_empty() {
+            spans.push((std::mem::take(&mut text), style));
+        }
+        style = apply_sgr(&params, style, base);
+    }
+    if !text.is_empty() {
+        spans.push((text, style));
+    }
+    spans
+}
+
+fn ansi_color(index: u8) -> Color {
+    match index {
+        0 => Color::Black,
+        1 => Color::Red,
+        2 => Color::Green,
+        3 => Color::Yellow,
+        4 => Color::Blue,
+        5 => Color::Magenta,
+        6 => Color::Cyan,
+        7 => Color::LightGray,
+        8 => Color::Gray,
+        9 => Color::LightRed,
+        10 => Color::LightGreen,
+        11 => Color::LightYellow,
+        12 => Color::LightBlue,
+        13 => Color::LightMagenta,
+        14 => Color::LightCyan,
+        15 => Color::White,
+        index => Color::Indexed(index),
+    }
+}
+
+/// Parses the color of an extended (`38`/`48`) SGR parameter: `5;n` or `2;r;g;b`.
+fn extended_color(params: &mut impl Iterator<Item = u8>) -> Option<Color> {
+    match params.next()? {
+        5 => params.next().map(ansi_color),
+        2 => Some(Color::Rgb(params.next()?, params.next()?, params.next()?)),
+        _ => None,
+    }
+}
+
+fn apply_sgr(params: &str, mut style: Style, base: Style) -> Style {
+    let mut params = params
+        .split(';')
+        .map(|param| param.parse::<u8>().unwrap_or(0));
+    while let Some(param) = params.next() {
+        style = match param {
+            0 => base,
+            1 => style.add_modifier(Modifier::BOLD),
+            2 => style.add_modifier(Modifier::DIM),
+            3 => style.add_modifier(Modifier::ITALIC),
+            4 => style.underline_style(UnderlineStyle::Line),
+            7 => style.add_modifier(Modifier::REVERSED),
+            9 => style.add_modifier(Modifier::CROSSED_OUT),
+            22 => style.remove_modifier(Modifier::BOLD | Modifier::DIM),
+            23 => style.remove_modifier(Modifier::ITALIC),
+            24 => Style {
+                underline_style: base.underline_style,
+                ..style
+            },
+            27 => style.remove_modifier(Modifier::REVERSED),
+            29 => style.remove_modifier(Modifier::CROSSED_OUT),
+            30..=37 => style.fg(ansi_color(param - 30)),
+            90..=97 => style.fg(ansi_color(param - 90 + 8)),
+            40..=47 => style.bg(ansi_color(param - 40)),
+            100..=107 => style.bg(ansi_color(param - 100 + 8)),
+            38 => match extended_color(&mut params) {
+                Some(color) => style.fg(color),
+                None => style,
+            },
+            48 => match extended_color(&mut params) {
+                Some(color) => style.bg(color),
+                None => style,
+            },
+            39 => Style {
+                fg: base.fg,
+                ..style
+            },
+            49 => Style {
+                bg: base.bg,
+                ..style
+            },
+            _ => style,
+        };
+    }
+    style
+}
+
+fn category_style(theme: &Theme, category: &ConsoleCategory) -> Style {
+    match category {
+        ConsoleCategory::Stderr | ConsoleCategory::Error => theme.get("error"),
+        ConsoleCategory::Important => theme.get("warning"),
+        ConsoleCategory::Console => theme.get("ui.text.info"),
+        ConsoleCategory::Input => theme.get("ui.text.focus"),
+        ConsoleCategory::Stdout | ConsoleCategory::Result | ConsoleCategory::Other(_) => {
+            theme.get("ui.text")
+        }
+    }
+}
+
+/// The number of lines an entry takes up in the console.
+fn entry_height(entry: &ConsoleEntry) -> usize {
+    entry.text.lines().count().max(1)
+}
+
+/// Converts a column counted in UTF-16 code units into a byte offset within `text`.
+fn utf16_to_byte(text: &str, column: usize) -> usize {
+    let mut units = 0;
+    for (i, ch) in text.char_indices() {
+        if units >= column {
+            return i;
+        }
+        units += ch.len_utf16();
+    }
+    text.len()
+}
+
+/// Requests completions for the REPL input from the active debug adapter. They are shown in
+/// the prompt of the console once the adapter responds, if the input didn't change meanwhile.
+fn request_repl_completions(cx: &mut Context, input: &str) {
+    let Some(debugger) = cx.editor.debug_adapters.get_active_client() else {
+        return;
+    };
+    let supported = debugger
+        .caps
+        .as_ref()
+        .and_then(|caps| caps.supports_completions_request)
+        .unwrap_or_default();
+    if input.is_empty() || !supported {
+        return;
+    }
+
+    let column = input.encode_utf16().count() + 1;
+    let frame_id = debugger.current_stack_frame().map(|frame| frame.id);
+    let request = debugger.completions(input.to_string(), column, frame_id);
+    let input = input.to_string();
+    cx.jobs.callback(async move {
+        let targets = request.await?;
+        let call: Callback = Callback::EditorCompositor(Box::new(move |_, compositor| {
+            let Some(console) = compositor.find_id::<DebugConsole>(ID) else {
+                return;
+            };
+            if *console.prompt.line() != input {
+                return;
+            }
+            console
+                .prompt
+                .set_completion(repl_completions(&input, targets));
+            console
+                .prompt
+                .change_completion_selection(CompletionDirection::Forward);
+        }));
+        Ok(call)
+    });
+}
+
+/// Converts the completion targets of a debug adapter for the REPL `input` into completions of
+/// the prompt.
+fn repl_completions(input: &str, mut targets: Vec<helix_dap::CompletionItem>) -> Vec<Completion> {
+    // Completions replace the word before the cursor unless the adapter specifies a start.
+    let word_start = input
+        .char_indices()
+        .rev()
+        .find(|(_, ch)| !(ch.is_alphanumeric() || *ch == '_'))
+        .map_or(0, |(i, ch)| i + ch.len_utf8());
+    targets.sort_by(|a, b| {
+        let a = a.sort_text.as_ref().unwrap_or(&a.label);
+        let b = b.sort_text.as_ref().unwrap_or(&b.label);
+        a.cmp(b)
+    });
+    targets
+        .into_iter()
+        .map(|target| {
+            let start = target.start.map_or(word_start, |start| {
+                utf16_to_byte(input, start.saturating_sub(1))
+            });
+            (start.., Span::raw(target.text.unwrap_or(target.label)))
+        })
+        .collect()
+}
+
+/// Evaluates a REPL expression in the current stack frame and records it and its result in the
+/// debug console.
+fn repl_eval(cx: &mut Context, expression: &str) {
+    let expression = expression.trim();
+    if expression.is_empty() {
+        return;
+    }
+    cx.editor
+        .debug_console
+        .push(ConsoleCategory::Input, expression);
+
+    let Some(debugger) = cx.editor.debug_adapters.get_active_client() else {
+        cx.editor
+            .debug_console
+            .push(ConsoleCategory::Error, "Debugger is not running");
+        return;
+    };
+    let frame_id = debugger.current_stack_frame().map(|frame| frame.id);
+    let call = debugger.repl_eval(expression.to_string(), frame_id);
+    cx.jobs.callback(async move {
+        let response = call.await.and_then(|json| {
+            serde_json::from_value::<helix_dap::requests::EvaluateResponse>(json)
+                .map_err(Into::into)
+        });
+        let call: Callback =
+            Callback::Editor(Box::new(move |editor: &mut Editor| match response {
+                Ok(response) => editor
+                    .debug_console
+                    .push(ConsoleCategory::Result, response.result),
+                Err(err) => editor
+                    .debug_console
+                    .push(ConsoleCategory::Error, err.to_string()),
+            }));
+        Ok(call)
+    });
+}
+
+fn open_source(editor: &mut Editor, source: &ConsoleSource) {
+    if let Err(err) = editor.open(&source.path, Action::Replace) {
+        editor.set_error(format!("Failed to open {}: {err}", source.path.display()));
+        return;
+    }
+    let (view, doc) = current!(editor);
+    let text = doc.text();
+    let line = source.line.min(text.len_lines()).max(1);
+    let pos = dap_pos_to_pos(text, line, source.column.unwrap_or(1))
+        .unwrap_or_default()
+        .min(text.len_chars());
+    doc.set_selection(view.id, Selection::point(pos));
+    align_view(doc, view, Align::Center);
+}
+
+/// The scrollback of the debug adapter's output with a REPL prompt for evaluating expressions
+/// in the current stack frame.
+pub struct DebugConsole {
+    prompt: Prompt,
+    /// The number of lines scrolled up from the end of the scrollback.
+    scroll: usize,
+    /// The index of the selected entry with a source link.
+    selected: Option<usize>,
+    /// The number of lines that fit in the console, as of the last render.
+    height: usize,
+}
+
+impl DebugConsole {
+    pub fn new() -> Self {
+        // Completions are requested from the debug adapter on tab, see `handle_event`.
+        let prompt = Prompt::new(
+            "> ".into(),
+            None,
+            |_, _| Vec::new(),
+            |cx: &mut Context, input: &str, event: PromptEvent| {
+                if event == PromptEvent::Validate {
+                    repl_eval(cx, input);
+                }
+            },
+        );
+        Self {
+            prompt,
+            scroll: 0,
+            selected: None,
+            height: 0,
+        }
+    }
+
+    /// Selects the next entry with a source link, searching backwards through the scrollback
+    /// if `backward` is set.
+    fn select_source(&mut self, editor: &Editor, backward: bool) {
+        let entries: Vec<_> = editor.debug_console.entries().collect();
+        let mut candidates = entries
+            .iter()
+            .enumerate()
+            .filter(|(_, entry)| entry.source.is_some())
+            .map(|(i, _)| i);
+        let selected = match (self.selected, backward) {
+            (None, true) => candidates.next_back(),
+            (None, false) => None,
+            (Some(selected), true) => candidates.rev().find(|&i| i < selected),
+            (Some(selected), false) => candidates.find(|&i| i > selected),
+        };
+        if backward && selected.is_none() {
+            // Keep the topmost link selected.
+            return;
+        }
+        self.selected = selected;
+
+        // Scroll the selected entry into view.
+        self.scroll = match selected {
+            Some(selected) => {
+                let below: usize = entries[selected + 1..]
+                    .iter()
+                    .map(|entry| entry_height(entry))
+                    .sum();
+                let top = below + entry_height(entries[selected]) - 1;
+                if top >= self.scroll + self.height {
+                    (top + 1).saturating_sub(self.height)
+                } else {
+                    self.scroll.min(below)
+                }
+            }
+            None => 0,
+        };
+    }
+
+    fn open_selected(&self, editor: &mut Editor) -> bool {
+        let Some(source) = self
+            .selected
+            .and_then(|selected| editor.debug_console.entries().nth(selected))
+            .and_then(|entry| entry.source.clone())
+        else {
+            return false;
+        };
+        open_source(editor, &source);
+        true
+    }
+
+    fn close(&self) -> EventResult {
+        EventResult::Consumed(Some(Box::new(|compositor, _| {
+            compositor.remove(ID);
+        })))
+    }
+
+    fn render_scrollback(&mut self, area: Rect, surface: &mut Surface, cx: &mut Context) {
+        let theme = &cx.editor.theme;
+        let link_style = theme.get("markup.link.url");
+        let selected_style = theme.get("ui.selection");
+        surface.clear_with(area, theme.get("ui.background"));
+
+        let block = Block::bordered().title(
+            " Debug console - enter: evaluate, tab: complete, alt-up/alt-down: select source, ctrl-l: clear ",
+        );
+        let inner = block.inner(area).inner(Margin::horizontal(1));
+        block.render(area, surface);
+
+        self.height = inner.height as usize;
+        let console = &cx.editor.debug_console;
+        if console.is_empty() {
+            surface.set_stringn(
+                inner.x,
+                inner.y,
+                "No output yet",
+                inner.width as usize,
+                theme.get("ui.text.inactive"),
+            );
+            return;
+        }
+
+        // Collect the visible lines starting from the end of the scrollback.
+        let wanted = self.height + self.scroll;
+        let mut lines = Vec::with_capacity(wanted);
+        let len = console.len();
+        for (i, entry) in console.entries().enumerate().rev() {
+            if lines.len() >= wanted {
+                break;
+            }
+            let mut base = category_style(theme, &entry.category);
+            if self.selected == Some(i) {
+                base = base.patch(selected_style);
+            }
+            let mut entry_lines: Vec<_> = entry
+                .text
+                .lines()
+                .map(|line| ansi_spans(line, base))
+                .collect();
+            if entry_lines.is_empty() {
+                entry_lines.push(Vec::new());
+            }
+            if entry.category == ConsoleCategory::Input {
+                entry_lines[0].insert(0, ("> ".to_string(), base));
+            }
+            if let Some(source) = &entry.source {
+                let location = match source.column {
+                    Some(column) => {
+                        format!("  {}:{}:{column}", source.path.display(), source.line)
+                    }
+                    None => format!("  {}:{}", source.path.display(), source.line),
+                };
+                let style = if self.selected == Some(i) {
+                    link_style.patch(selected_style)
+                } else {
+                    link_style
+                };
+                entry_lines[0].push((location, style));
+            }
+            lines.extend(entry_lines.into_iter().rev());
+        }
+        self.scroll = self.scroll.min(lines.len().saturating_sub(self.height));
+        if self.selected.is_some_and(|selected| selected >= len) {
+            self.selected = None;
+        }
+
+        let visible = lines
+            .iter()
+            .skip(self.scroll)
+            .take(self.height)
+            .collect::<Vec<_>>();
+        let top = inner.y + (self.height - visible.len()) as u16;
+        for (row, spans) in visible.into_iter().rev().enumerate() {
+            let y = top + row as u16;
+            let mut x = inner.x;
+            for (text, style) in spans {
+                let width = (inner.right().saturating_sub(x)) as usize;
+                if width == 0 {
+                    break;
+                }
+                x = surface.set_stringn(x, y, text, width, *style).0;
+            }
+        }
+    }
+}
+
+impl Default for DebugConsole {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Component for DebugConsole {
+    fn render(&mut self, area: Rect, surface: &mut Surface, cx: &mut Context) {
+        // +-----------------+
+        // |scrollback       |
+        // |                 |
+        // +-----------------+
+        // > prompt
+        self.render_scrollback(area.clip_bottom(1), surface, cx);
+        self.prompt.render_prompt(area, surface, cx);
+    }
+
+    fn handle_event(&mut self, event: &Event, cx: &mut Context) -> EventResult {
+        let key_event = match event {
+            Event::Key(event) => *event,
+            Event::Paste(..) => {
+                self.prompt.handle_event(event, cx);
+                return EventResult::Consumed(None);
+            }
+            Event::Resize(..) => return EventResult::Consumed(None),
+            _ => return EventResult::Ignored(None),
+        };
+
+        match key_event {
+            key!(Esc) | ctrl!('c') => return self.close(),
+            key!(PageUp) => self.scroll += self.height.max(1),
+            key!(PageDown) => self.scroll = self.scroll.saturating_sub(self.height.max(1)),
+            alt!(Up) | alt!('k') => self.select_source(cx.editor, true),
+            alt!(Down) | alt!('j') => self.select_source(cx.editor, false),
+            ctrl!('l') => {
+                cx.editor.debug_console.clear();
+                self.scroll = 0;
+                self.selected = None;
+            }
+            key!(Tab) if self.prompt.completion().is_empty() => {
+                let input = self.prompt.line().clone();
+                request_repl_completions(cx, &input);
+            }
+            key!(Enter) if self.prompt.line().is_empty() && self.selected.is_some() => {
+                if self.open_selected(cx.editor) {
+                    return self.close();
+                }
+            }
+            key!(Enter) => {
+                // The prompt evaluates the input and asks to be closed: keep the console open
+                // and follow the output instead.
+                self.prompt.handle_event(event, cx);
+                self.prompt.clear(cx.editor);
+                self.scroll = 0;
+                self.selected = None;
+            }
+            _ => {
+                self.prompt.handle_event(event, cx);
+            }
+        }
+        EventResult::Consumed(None)
+    }
+
+    fn cursor(&self, area: Rect, editor: &Editor) -> (Option<helix_core::Position>, CursorKind) {
+        self.prompt.cursor(area, editor)
+    }
+
+    fn id(&self) -> Option<&'static str> {
+        Some(ID)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_ansi_colors() {
+        let base = Style::default().fg(Color::White);
+        assert_eq!(
+            ansi_spans(
+                "plain \x1b[1;31merror\x1b[0m: \x1b[38;5;208mfoo\x1b[39m\x1b[K",
+                base
+            ),
+            vec![
+                ("plain ".to_string(), base),
+                (
+                    "error".to_string(),
+                    base.fg(Color::Red).add_modifier(Modifier::BOLD)
+                ),
+                (": ".to_string(), base),
+                ("foo".to_string(), base.fg(Color::Indexed(208))),
+            ]
+        );
+        assert_eq!(
+            ansi_spans("\x1b[48;2;1;2;3mx", base),
+            vec![("x".to_string(), base.bg(Color::Rgb(1, 2, 3)))]
+        );
+    }
+
+    #[test]
+    fn utf16_columns() {
+        assert_eq!(utf16_to_byte("a😀b", 0), 0);
+        assert_eq!(utf16_to_byte("a😀b", 1), 1);
+        assert_eq!(utf16_to_byte("a😀b", 3), 5);
+        assert_eq!(utf16_to_byte("a😀b", 10), 6);
+    }
+}
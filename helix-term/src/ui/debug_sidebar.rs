@@ -0,0 +1,356 @@
+use futures_util::FutureExt;
+use helix_view::debug_variables::{NodeKind, VariableRow, Watch};
+use helix_view::graphics::{CursorKind, Margin, Rect};
+use helix_view::input::Event;
+use helix_view::Editor;
+use tui::buffer::Buffer as Surface;
+use tui::widgets::{Block, Widget};
+
+use crate::compositor::{Component, Context, EventResult};
+use crate::handlers::dap::{fetch_expanded_variables, fetch_inline_values, fetch_variables};
+use crate::job::Callback;
+use crate::ui::{self, Prompt, PromptEvent};
+use crate::{ctrl, key};
+
+pub const ID: &str = "debug-sidebar";
+
+/// The preferred width of the sidebar. It never takes more than half of the screen.
+const WIDTH: u16 = 40;
+
+/// How a value is changed in the debuggee.
+enum EditTarget {
+    /// `setVariable` on the variable `name` of the container with the given reference.
+    Variable { container: usize, name: String },
+    /// `setExpression` on an assignable expression.
+    Expression(String),
+}
+
+/// Changes a value in the debuggee in the background. Other values may depend on the one that
+/// changed, so everything is fetched again once the debugger replies.
+fn set_value(cx: &mut Context, target: &EditTarget, value: &str) {
+    let Some(debugger) = cx.editor.debug_adapters.get_active_client() else {
+        cx.editor.set_error("Debugger is not running");
+        return;
+    };
+    let request = match target {
+        EditTarget::Variable { container, name } => debugger
+            .set_variable(*container, name.clone(), value.to_string())
+            .map(|response| response.map(|_| ()))
+            .boxed(),
+        EditTarget::Expression(expression) => {
+            let frame_id = debugger.current_stack_frame().map(|frame| frame.id);
+            debugger
+                .set_expression(expression.clone(), value.to_string(), frame_id)
+                .map(|response| response.map(|_| ()))
+                .boxed()
+        }
+    };
+    cx.jobs.callback(async move {
+        let result = request.await;
+        let call: Callback = Callback::Editor(Box::new(move |editor: &mut Editor| match result {
+            Ok(()) => {
+                fetch_variables(editor);
+                fetch_inline_values(editor);
+            }
+            Err(err) => editor.set_error(format!("Failed to set the value: {err}")),
+        }));
+        Ok(call)
+    });
+}
+
+/// A sidebar on the right of the views with the watch expressions and the variables of the
+/// current stack frame. It stays open while the debugger runs and only handles keys while it is
+/// focused.
+pub struct DebugSidebar {
+    focused: bool,
+    cursor: usize,
+    offset: usize,
+}
+
+impl DebugSidebar {
+    pub fn new() -> Self {
+        Self {
+            focused: true,
+            cursor: 0,
+            offset: 0,
+        }
+    }
+
+    /// The width of the sidebar on a screen with the given width.
+    pub fn width(screen_width: u16) -> u16 {
+        WIDTH.min(screen_width / 2)
+    }
+
+    pub fn focus(&mut self) {
+        self.focused = true;
+    }
+
+    fn is_stopped(editor: &Editor) -> bool {
+        editor
+            .debug_adapters
+            .get_active_client()
+            .is_some_and(|debugger| debugger.current_stack_frame().is_some())
+    }
+
+    /// The rows shown in the sidebar. The values are only available while the debuggee is
+    /// stopped, otherwise just the watch expressions are listed.
+    fn rows(editor: &Editor) -> Vec<VariableRow<'_>> {
+        let mut rows = editor.debug_variables.rows();
+        if !Self::is_stopped(editor) {
+            rows.retain(|row| matches!(row.kind, NodeKind::Watch(_)));
+            for row in &mut rows {
+                row.value = None;
+                row.variables_reference = 0;
+            }
+        }
+        rows
+    }
+
+    fn move_cursor(&mut self, cursor: usize, editor: &Editor) {
+        self.cursor = cursor.min(Self::rows(editor).len().saturating_sub(1));
+    }
+
+    fn toggle(&mut self, editor: &mut Editor, expand: Option<bool>) {
+        let rows = Self::rows(editor);
+        let Some(row) = rows.get(self.cursor) else {
+            return;
+        };
+        if row.variables_reference == 0 || expand == Some(row.expanded) {
+            // Collapsing a leaf moves to its parent instead.
+            if expand == Some(false) && row.depth > 0 {
+                let depth = row.depth;
+                if let Some(parent) = rows[..self.cursor]
+                    .iter()
+                    .rposition(|row| row.depth < depth)
+                {
+                    self.cursor = parent;
+                }
+            }
+            return;
+        }
+        let path = row.path.clone();
+        editor.debug_variables.toggle(&path);
+        fetch_expanded_variables(editor);
+    }
+
+    fn edit_value(&self, editor: &mut Editor) -> EventResult {
+        let rows = Self::rows(editor);
+        let Some(row) = rows.get(self.cursor) else {
+            return EventResult::Consumed(None);
+        };
+        let Some(caps) = editor
+            .debug_adapters
+            .get_active_client()
+            .and_then(|debugger| debugger.caps.as_ref())
+        else {
+            editor.set_error("Debugger is not running");
+            return EventResult::Consumed(None);
+        };
+        let set_variable = caps.supports_set_variable.unwrap_or_default();
+        let set_expression = caps.supports_set_expression.unwrap_or_default();
+
+        let target = match (row.kind, row.evaluate_name) {
+            (NodeKind::Variable { container }, _) if set_variable => EditTarget::Variable {
+                container,
+                name: row.name.to_string(),
+            },
+            (NodeKind::Variable { .. } | NodeKind::Watch(_), Some(expression))
+                if set_expression =>
+            {
+                EditTarget::Expression(expression.to_string())
+            }
+            _ => {
+                editor.set_error("The debugger can't change this value");
+                return EventResult::Consumed(None);
+            }
+        };
+        let value = match row.value {
+            Some(Ok(value)) => value.to_string(),
+            _ => String::new(),
+        };
+
+        let prompt = Prompt::new(
+            format!("set {}: ", row.name).into(),
+            None,
+            ui::completers::none,
+            move |cx, input: &str, event: PromptEvent| {
+                if event == PromptEvent::Validate {
+                    set_value(cx, &target, input);
+                }
+            },
+        )
+        .with_line(value, editor);
+        EventResult::Consumed(Some(Box::new(|compositor, _| {
+            compositor.push(Box::new(prompt))
+        })))
+    }
+
+    fn add_watch(&self) -> EventResult {
+        let prompt = Prompt::new(
+            "watch: ".into(),
+            None,
+            ui::completers::none,
+            |cx, input: &str, event: PromptEvent| {
+                let expression = input.trim();
+                if event != PromptEvent::Validate || expression.is_empty() {
+                    return;
+                }
+                cx.editor.debug_variables.watches.push(Watch {
+                    expression: expression.to_string(),
+                    value: None,
+                });
+                fetch_variables(cx.editor);
+            },
+        );
+        EventResult::Consumed(Some(Box::new(|compositor, _| {
+            compositor.push(Box::new(prompt))
+        })))
+    }
+
+    fn remove_watch(&mut self, editor: &mut Editor) {
+        let rows = Self::rows(editor);
+        if let Some(NodeKind::Watch(i)) = rows.get(self.cursor).map(|row| row.kind) {
+            editor.debug_variables.watches.remove(i);
+            self.move_cursor(self.cursor, editor);
+        }
+    }
+
+    fn close(&self) -> EventResult {
+        EventResult::Consumed(Some(Box::new(|compositor, cx| {
+            compositor.remove(ID);
+            cx.editor.sidebar_width = 0;
+        })))
+    }
+}
+
+impl Default for DebugSidebar {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Component for DebugSidebar {
+    fn render(&mut self, area: Rect, surface: &mut Surface, cx: &mut Context) {
+        let width = Self::width(area.width);
+        cx.editor.sidebar_width = width;
+        // Leave the command line below the sidebar free.
+        let area = area.clip_bottom(1).clip_left(area.width - width);
+
+        let theme = &cx.editor.theme;
+        let text_style = theme.get("ui.text");
+        let inactive_style = theme.get("ui.text.inactive");
+        let scope_style = theme.get("ui.linenr.selected");
+        let name_style = theme.get("variable");
+        let error_style = theme.get("error");
+        let selected_style = theme.get("ui.selection");
+        surface.clear_with(area, theme.get("ui.background"));
+
+        let title = if self.focused {
+            " Debugger - a: watch, d: delete, e: edit "
+        } else {
+            " Debugger "
+        };
+        let block = Block::bordered().title(title);
+        let inner = block.inner(area).inner(Margin::horizontal(1));
+        block.render(area, surface);
+
+        let message = if cx.editor.debug_adapters.get_active_client().is_none() {
+            Some("No debug session")
+        } else if !Self::is_stopped(cx.editor) {
+            Some("The debuggee is running")
+        } else {
+            None
+        };
+        let rows = Self::rows(cx.editor);
+        let height = (inner.height as usize).saturating_sub(message.is_some() as usize);
+        self.cursor = self.cursor.min(rows.len().saturating_sub(1));
+        if self.cursor < self.offset {
+            self.offset = self.cursor;
+        } else if self.cursor >= self.offset + height {
+            self.offset = self.cursor + 1 - height;
+        }
+
+        let mut y = inner.y;
+        if let Some(message) = message {
+            surface.set_stringn(inner.x, y, message, inner.width as usize, inactive_style);
+            y += 1;
+        }
+        for (i, row) in rows.iter().enumerate().skip(self.offset).take(height) {
+            if self.focused && i == self.cursor {
+                surface.set_style(Rect::new(inner.x, y, inner.width, 1), selected_style);
+            }
+            let marker = match (row.variables_reference, row.expanded) {
+                (0, _) => "  ",
+                (_, true) => "▾ ",
+                (_, false) => "▸ ",
+            };
+            let indent = " ".repeat(row.depth * 2);
+            let name = if row.kind == NodeKind::Scope {
+                scope_style
+            } else {
+                name_style
+            };
+            let mut spans = vec![
+                (format!("{indent}{marker}"), text_style),
+                (row.name.to_string(), name),
+            ];
+            match row.value {
+                Some(Ok(value)) => {
+                    let value = value.lines().next().unwrap_or_default();
+                    spans.push((format!(" = {value}"), text_style));
+                }
+                Some(Err(err)) => {
+                    let err = err.lines().next().unwrap_or_default();
+                    spans.push((format!(" {err}"), error_style));
+                }
+                None => (),
+            }
+
+            let mut x = inner.x;
+            for (text, style) in spans {
+                let width = inner.right().saturating_sub(x) as usize;
+                if width == 0 {
+                    break;
+                }
+                x = surface.set_stringn(x, y, text, width, style).0;
+            }
+            y += 1;
+        }
+    }
+
+    fn handle_event(&mut self, event: &Event, cx: &mut Context) -> EventResult {
+        let key_event = match event {
+            Event::Key(event) if self.focused => *event,
+            _ => return EventResult::Ignored(None),
+        };
+
+        match key_event {
+            key!(Up) | ctrl!('p') | key!('k') => {
+                self.move_cursor(self.cursor.saturating_sub(1), cx.editor);
+            }
+            key!(Down) | ctrl!('n') | key!('j') => {
+                self.move_cursor(self.cursor + 1, cx.editor);
+            }
+            key!(Enter) | key!(Tab) => self.toggle(cx.editor, None),
+            key!(Right) | key!('l') => self.toggle(cx.editor, Some(true)),
+            key!(Left) | key!('h') => self.toggle(cx.editor, Some(false)),
+            key!('e') => return self.edit_value(cx.editor),
+            key!('a') => return self.add_watch(),
+            key!('d') => self.remove_watch(cx.editor),
+            key!('r') => fetch_variables(cx.editor),
+            // Give the keys back to the editor but keep showing the sidebar.
+            key!(Esc) | ctrl!('c') => self.focused = false,
+            key!('q') => return self.close(),
+            _ => (),
+        }
+        EventResult::Consumed(None)
+    }
+
+    fn cursor(&self, _area: Rect, _editor: &Editor) -> (Option<helix_core::Position>, CursorKind) {
+        (None, CursorKind::Hidden)
+    }
+
+    fn id(&self) -> Option<&'static str> {
+        Some(ID)
+    }
+}
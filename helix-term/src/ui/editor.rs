@@ -8,7 +8,10 @@
     ui::{
         document::{render_document, LinePos, TextRenderer},
         statusline,
-        text_decorations::{self, Decoration, DecorationManager, InlineDiagnostics},
+        text_decorations::{
+            self, CodeLenses, Decoration, DecorationManager, InlineCompletion, InlineDiagnostics,
+            InlineValues,
+        },
         Completion, ProgressSpinners,
     },
 };
@@ -120,6 +123,16 @@ pub fn render_view(
             Self::doc_syntax_highlighter(doc, view_offset.anchor, inner.height, &loader);
         let mut overlays = Vec::new();
 
+        if config.lsp.display_semantic_tokens {
+            Self::doc_semantic_tokens_highlights_into(
+                doc,
+                view_offset.anchor,
+                inner.height,
+                theme,
+                &mut overlays,
+            );
+        }
+
         overlays.push(Self::overlay_syntax_highlights(
             doc,
             view_offset.anchor,
@@ -141,6 +154,10 @@ pub fn render_view(
 
         Self::doc_diagnostics_highlights_into(doc, theme, &mut overlays);
 
+        if let Some(overlay) = Self::doc_document_links_highlights(doc, theme) {
+            overlays.push(overlay);
+        }
+
         if is_focused {
             if let Some(tabstops) = Self::tabstop_highlights(doc, theme) {
                 overlays.push(tabstops);
@@ -189,6 +206,12 @@ pub fn render_view(
             .diagnostics_handler
             .show_cursorline_diagnostics(doc, view.id);
         let inline_diagnostic_config = config.inline_diagnostics.prepare(width, enable_cursor_line);
+        if let Some(inline_completion) = InlineCompletion::new(doc, view.id, theme) {
+            decorations.add_decoration(inline_completion);
+        }
+        if let Some(inline_values) = InlineValues::new(editor, doc) {
+            decorations.add_decoration(inline_values);
+        }
         decorations.add_decoration(InlineDiagnostics::new(
             doc,
             theme,
@@ -196,6 +219,9 @@ pub fn render_view(
             inline_diagnostic_config,
             config.end_of_line_diagnostics,
         ));
+        if config.lsp.display_code_lenses && !doc.code_lenses.is_empty() {
+            decorations.add_decoration(CodeLenses::new(doc, view.id, theme));
+        }
         render_document(
             surface,
             inner,
@@ -337,6 +363,72 @@ pub fn doc_rainbow_highlights(
         Some(syntax.rainbow_highlights(text, theme.rainbow_length(), loader, range))
     }
 
+    /// Get highlight spans for the LSP semantic tokens of a document within the viewport.
+    ///
+    /// Token types map to `lsp.type.<type>` scopes and each token modifier to an
+    /// `lsp.mod.<modifier>` scope. Tokens without a matching scope in the theme keep their
+    /// tree-sitter highlight.
+    pub fn doc_semantic_tokens_highlights_into(
+        doc: &Document,
+        anchor: usize,
+        height: u16,
+        theme: &Theme,
+        overlay_highlights: &mut Vec<OverlayHighlights>,
+    ) {
+        let Some(semantic_tokens) = &doc.semantic_tokens else {
+            return;
+        };
+
+        let text = doc.text().slice(..);
+        let row = text.char_to_line(anchor.min(text.len_chars()));
+        let range = Self::viewport_byte_range(text, row, height);
+        let range = text.byte_to_char(range.start)..text.byte_to_char(range.end);
+
+        let start = semantic_tokens
+            .tokens
+            .partition_point(|token| token.range.end <= range.start);
+        let tokens = semantic_tokens.tokens[start..]
+            .iter()
+            .take_while(|token| token.range.start < range.end);
+
+        let type_highlights: Vec<_> = semantic_tokens
+            .token_types
+            .iter()
+            .map(|scope| theme.find_highlight(scope))
+            .collect();
+        let mut modifier_ranges: Vec<_> = semantic_tokens
+            .token_modifiers
+            .iter()
+            // The modifiers of a token are a `u32` bitset.
+            .take(u32::BITS as usize)
+            .map(|scope| (theme.find_highlight(scope), Vec::new()))
+            .collect();
+
+        let mut highlights = Vec::new();
+        for token in tokens {
+            if let Some(Some(highlight)) = type_highlights.get(token.token_type as usize) {
+                highlights.push((*highlight, token.range.clone()));
+            }
+            for (idx, (highlight, ranges)) in modifier_ranges.iter_mut().enumerate() {
+                if highlight.is_some() && token.token_modifiers & (1 << idx) != 0 {
+                    ranges.push(token.range.clone());
+                }
+            }
+        }
+
+        overlay_highlights.push(OverlayHighlights::Heterogenous { highlights });
+        overlay_highlights.extend(
+            modifier_ranges
+                .into_iter()
+                .filter_map(|(highlight, ranges)| {
+                    Some(OverlayHighlights::Homogeneous {
+                        highlight: highlight?,
+                        ranges,
+                    })
+                }),
+        );
+    }
+
     /// Get highlight spans for document diagnostics
     pub fn doc_diagnostics_highlights_into(
         doc: &Document,
@@ -581,6 +673,23 @@ pub fn highlight_focused_view_elements(
         Some(OverlayHighlights::single(highlight, pos..pos + 1))
     }
 
+    pub fn doc_document_links_highlights(
+        doc: &Document,
+        theme: &Theme,
+    ) -> Option<OverlayHighlights> {
+        if doc.document_links.is_empty() {
+            return None;
+        }
+        let highlight = theme.find_highlight_exact("ui.link")?;
+        let ranges = doc
+            .document_links
+            .iter()
+            .filter(|link| !link.range.is_empty())
+            .map(|link| link.range.clone())
+            .collect();
+        Some(OverlayHighlights::Homogeneous { highlight, ranges })
+    }
+
     pub fn tabstop_highlights(doc: &Document, theme: &Theme) -> Option<OverlayHighlights> {
         let snippet = doc.active_snippet.as_ref()?;
         let highlight = theme.find_highlight_exact("tabstop")?;
@@ -1091,6 +1200,7 @@ pub fn clear_completion(&mut self, editor: &mut Editor) -> Option<OnKeyCallback>
 
     pub fn handle_idle_timeout(&mut self, cx: &mut commands::Context) -> EventResult {
         commands::compute_inlay_hints_for_all_views(cx.editor, cx.jobs);
+        crate::handlers::code_lens::resolve_visible_code_lenses(cx.editor);
 
         EventResult::Ignored(None)
     }
@@ -1536,7 +1646,7 @@ fn render(&mut self, area: Rect, surface: &mut Surface, cx: &mut Context) {
         };
 
         // -1 for commandline and -1 for bufferline
-        let mut editor_area = area.clip_bottom(1);
+        let mut editor_area = area.clip_bottom(1).clip_right(cx.editor.sidebar_width);
         if use_bufferline {
             editor_area = editor_area.clip_top(1);
         }
@@ -1545,7 +1655,11 @@ fn render(&mut self, area: Rect, surface: &mut Surface, cx: &mut Context) {
         cx.editor.resize(editor_area);
 
         if use_bufferline {
-            Self::render_bufferline(cx.editor, area.with_height(1), surface);
+            Self::render_bufferline(
+                cx.editor,
+                area.with_height(1).clip_right(cx.editor.sidebar_width),
+                surface,
+            );
         }
 
         for (view, is_focused) in cx.editor.tree.views() {
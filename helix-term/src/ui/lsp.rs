@@ -1,2 +1,3 @@
 pub mod hover;
 pub mod signature_help;
+pub mod status;
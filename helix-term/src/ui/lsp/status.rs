@@ -0,0 +1,422 @@
+use helix_core::Uri;
+use helix_lsp::lsp;
+use helix_lsp::{Client, LanguageServerId, ProgressStatus};
+use helix_view::graphics::{CursorKind, Margin, Rect};
+use helix_view::input::Event;
+use helix_view::theme::Style;
+use helix_view::Editor;
+use tui::buffer::Buffer as Surface;
+use tui::widgets::{Block, Widget};
+
+use crate::commands::{open_scratch_buffer, restart_language_servers, stop_language_server};
+use crate::compositor::{Component, Context, EventResult};
+use crate::{ctrl, key, shift};
+
+pub const ID: &str = "lsp-status";
+
+enum DetailLine {
+    Heading(&'static str),
+    Text(String),
+}
+
+fn progress_token(token: &lsp::ProgressToken) -> String {
+    match token {
+        lsp::NumberOrString::Number(number) => number.to_string(),
+        lsp::NumberOrString::String(string) => string.clone(),
+    }
+}
+
+fn progress_line(token: &lsp::ProgressToken, status: &ProgressStatus) -> String {
+    let ProgressStatus::Started { title, progress } = status else {
+        return format!("{}: created", progress_token(token));
+    };
+    let (percentage, message) = match progress {
+        lsp::WorkDoneProgress::Begin(begin) => (begin.percentage, begin.message.as_ref()),
+        lsp::WorkDoneProgress::Report(report) => (report.percentage, report.message.as_ref()),
+        lsp::WorkDoneProgress::End(end) => (None, end.message.as_ref()),
+    };
+    let mut line = title.clone();
+    if let Some(percentage) = percentage {
+        line.push_str(&format!(" {percentage}%"));
+    }
+    if let Some(message) = message {
+        line.push_str(" ⋅ ");
+        line.push_str(message);
+    }
+    line
+}
+
+fn log_message_type(typ: lsp::MessageType) -> &'static str {
+    match typ {
+        lsp::MessageType::ERROR => "error",
+        lsp::MessageType::WARNING => "warning",
+        lsp::MessageType::INFO => "info",
+        _ => "log",
+    }
+}
+
+/// The root and workspace folders of a language server.
+fn workspace_details(client: &Client) -> Vec<DetailLine> {
+    let mut lines = vec![
+        DetailLine::Heading("Root"),
+        DetailLine::Text(client.root_path().display().to_string()),
+        DetailLine::Heading("Workspace folders"),
+    ];
+    let workspace_folders = helix_lsp::block_on(client.workspace_folders()).clone();
+    lines.extend(workspace_folders.iter().map(|folder| {
+        DetailLine::Text(
+            match Uri::try_from(&folder.uri)
+                .ok()
+                .as_ref()
+                .and_then(Uri::as_path)
+            {
+                Some(path) => path.display().to_string(),
+                None => folder.uri.to_string(),
+            },
+        )
+    }));
+    lines
+}
+
+/// The active progress and latest log messages of a language server.
+fn activity_details(editor: &Editor, client: &Client) -> Vec<DetailLine> {
+    let mut lines = vec![DetailLine::Heading("Progress")];
+    let mut progress: Vec<_> = editor
+        .lsp_progress
+        .progress_map(client.id())
+        .into_iter()
+        .flatten()
+        .map(|(token, status)| (progress_token(token), progress_line(token, status)))
+        .collect();
+    progress.sort();
+    lines.extend(progress.into_iter().map(|(_, line)| DetailLine::Text(line)));
+
+    lines.push(DetailLine::Heading("Log messages"));
+    lines.extend(client.traffic().log_messages().iter().flat_map(|params| {
+        let typ = log_message_type(params.typ);
+        params
+            .message
+            .lines()
+            .map(move |line| DetailLine::Text(format!("[{typ}] {line}")))
+    }));
+    lines
+}
+
+/// The capabilities of a language server.
+fn capability_details(client: &Client) -> Vec<DetailLine> {
+    let mut lines = vec![DetailLine::Heading("Capabilities")];
+    if client.is_initialized() {
+        let capabilities = serde_json::to_string_pretty(client.capabilities()).unwrap_or_default();
+        lines.extend(
+            capabilities
+                .lines()
+                .map(|line| DetailLine::Text(line.to_string())),
+        );
+    } else {
+        lines.push(DetailLine::Text("not initialized yet".to_string()));
+    }
+    lines
+}
+
+/// The details of a language server which are expensive to build, kept across renders.
+struct CachedDetails {
+    id: LanguageServerId,
+    initialized: bool,
+    workspace: Vec<DetailLine>,
+    capabilities: Vec<DetailLine>,
+}
+
+impl CachedDetails {
+    fn new(client: &Client) -> Self {
+        Self {
+            id: client.id(),
+            initialized: client.is_initialized(),
+            workspace: workspace_details(client),
+            capabilities: capability_details(client),
+        }
+    }
+
+    fn is_valid(&self, client: &Client) -> bool {
+        self.id == client.id() && self.initialized == client.is_initialized()
+    }
+}
+
+/// Lists the running language servers with the status of the selected server and lets the
+/// user restart or stop them.
+#[derive(Default)]
+pub struct LanguageServerStatus {
+    cursor: usize,
+    list_offset: usize,
+    details_offset: usize,
+    /// The details of the selected server, rebuilt when the cursor moves or the server changes.
+    details: Option<CachedDetails>,
+}
+
+impl LanguageServerStatus {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn servers(editor: &Editor) -> Vec<&Client> {
+        let mut servers: Vec<_> = editor
+            .language_servers
+            .iter_clients()
+            .map(|client| &**client)
+            .collect();
+        servers.sort_by_key(|client| (client.name().to_string(), client.id()));
+        servers
+    }
+
+    fn selected(&self, editor: &Editor) -> Option<(LanguageServerId, String)> {
+        Self::servers(editor)
+            .get(self.cursor)
+            .map(|client| (client.id(), client.name().to_string()))
+    }
+
+    fn move_cursor(&mut self, cursor: usize, editor: &Editor) {
+        self.cursor = cursor.min(Self::servers(editor).len().saturating_sub(1));
+        self.details_offset = 0;
+        self.details = None;
+    }
+
+    fn restart(&self, editor: &mut Editor) {
+        let Some((id, name)) = self.selected(editor) else {
+            return;
+        };
+        let Some(doc_id) = editor
+            .documents()
+            .find(|doc| doc.language_servers().any(|ls| ls.id() == id))
+            .map(|doc| doc.id())
+        else {
+            editor.set_error(format!("No document uses the language server '{name}'"));
+            return;
+        };
+        let errors = restart_language_servers(editor, doc_id, &[name]);
+        if let Some((name, err)) = errors.first() {
+            editor.set_error(format!("Error restarting language server '{name}': {err}"));
+        }
+    }
+
+    fn stop(&self, editor: &mut Editor) {
+        if let Some((_, name)) = self.selected(editor) {
+            stop_language_server(editor, &name);
+        }
+    }
+
+    fn open_details(&self, editor: &mut Editor) {
+        let Some(client) = Self::servers(editor).get(self.cursor).copied() else {
+            return;
+        };
+        let mut text = format!("# {}\n", client.name());
+        let lines = [
+            workspace_details(client),
+            activity_details(editor, client),
+            capability_details(client),
+        ];
+        for line in lines.into_iter().flatten() {
+            match line {
+                DetailLine::Heading(heading) => text.push_str(&format!("\n## {heading}\n\n")),
+                DetailLine::Text(line) => {
+                    text.push_str(&line);
+                    text.push('\n');
+                }
+            }
+        }
+        open_scratch_buffer(editor, text, Some("markdown"));
+    }
+
+    fn close(&self) -> EventResult {
+        EventResult::Consumed(Some(Box::new(|compositor, _| {
+            compositor.remove(ID);
+        })))
+    }
+
+    fn render_list(&mut self, area: Rect, surface: &mut Surface, cx: &mut Context) {
+        let theme = &cx.editor.theme;
+        let text_style = theme.get("ui.text");
+        let selected_style = theme.get("ui.text.focus");
+        let inactive_style = theme.get("ui.text.inactive");
+        surface.clear_with(area, theme.get("ui.background"));
+
+        let block = Block::bordered()
+            .title(" Language servers - r: restart, s: stop, enter: open in buffer ");
+        let inner = block.inner(area).inner(Margin::horizontal(1));
+        block.render(area, surface);
+
+        let servers = Self::servers(cx.editor);
+        if servers.is_empty() {
+            surface.set_stringn(
+                inner.x,
+                inner.y,
+                "No language server is running",
+                inner.width as usize,
+                inactive_style,
+            );
+            return;
+        }
+        self.cursor = self.cursor.min(servers.len() - 1);
+
+        let height = inner.height as usize;
+        if self.cursor < self.list_offset {
+            self.list_offset = self.cursor;
+        } else if self.cursor >= self.list_offset + height {
+            self.list_offset = self.cursor + 1 - height;
+        }
+
+        for (i, client) in servers
+            .iter()
+            .enumerate()
+            .skip(self.list_offset)
+            .take(height)
+        {
+            let status = if !client.is_initialized() {
+                "initializing"
+            } else if cx.editor.lsp_progress.is_progressing(client.id()) {
+                "busy"
+            } else {
+                "idle"
+            };
+            let style = if i == self.cursor {
+                selected_style
+            } else {
+                text_style
+            };
+            let y = inner.y + (i - self.list_offset) as u16;
+            surface.set_stringn(inner.x, y, client.name(), inner.width as usize, style);
+            let x = inner.x + inner.width.saturating_sub(status.len() as u16);
+            if x > inner.x + client.name().len() as u16 {
+                surface.set_string(x, y, status, inactive_style);
+            }
+        }
+    }
+
+    fn render_details(&mut self, area: Rect, surface: &mut Surface, cx: &mut Context) {
+        let theme = &cx.editor.theme;
+        let text_style = theme.get("ui.text");
+        let heading_style = theme.get("markup.heading");
+        surface.clear_with(area, theme.get("ui.background"));
+
+        let block = Block::bordered();
+        let inner = block.inner(area).inner(Margin::horizontal(1));
+        block.render(area, surface);
+
+        let Some(client) = Self::servers(cx.editor).get(self.cursor).copied() else {
+            return;
+        };
+        if !self
+            .details
+            .as_ref()
+            .is_some_and(|details| details.is_valid(client))
+        {
+            self.details = Some(CachedDetails::new(client));
+        }
+        let details = self.details.as_ref().unwrap();
+        let activity = activity_details(cx.editor, client);
+        let lines = details
+            .workspace
+            .iter()
+            .chain(&activity)
+            .chain(&details.capabilities);
+        let len = details.workspace.len() + activity.len() + details.capabilities.len();
+        self.details_offset = self
+            .details_offset
+            .min(len.saturating_sub(inner.height as usize));
+        for (i, line) in lines
+            .skip(self.details_offset)
+            .take(inner.height as usize)
+            .enumerate()
+        {
+            let (text, style): (&str, Style) = match line {
+                DetailLine::Heading(heading) => (heading, heading_style),
+                DetailLine::Text(text) => (text, text_style),
+            };
+            let indent = if matches!(line, DetailLine::Text(_)) {
+                2
+            } else {
+                0
+            };
+            surface.set_stringn(
+                inner.x + indent,
+                inner.y + i as u16,
+                text,
+                (inner.width as usize).saturating_sub(indent as usize),
+                style,
+            );
+        }
+    }
+}
+
+impl Component for LanguageServerStatus {
+    fn render(&mut self, area: Rect, surface: &mut Surface, cx: &mut Context) {
+        // +---------+ +---------+
+        // |servers  | |details  |
+        // |         | |         |
+        // +---------+ +---------+
+        let list_width = (area.width / 3).max(30).min(area.width);
+        self.render_list(area.with_width(list_width), surface, cx);
+        self.render_details(area.clip_left(list_width), surface, cx);
+    }
+
+    fn handle_event(&mut self, event: &Event, cx: &mut Context) -> EventResult {
+        let key_event = match event {
+            Event::Key(event) => *event,
+            Event::Resize(..) => return EventResult::Consumed(None),
+            _ => return EventResult::Ignored(None),
+        };
+
+        match key_event {
+            shift!(Tab) | key!(Up) | ctrl!('p') | key!('k') => {
+                self.move_cursor(self.cursor.saturating_sub(1), cx.editor);
+            }
+            key!(Tab) | key!(Down) | ctrl!('n') | key!('j') => {
+                self.move_cursor(self.cursor + 1, cx.editor);
+            }
+            key!(PageUp) | ctrl!('u') => {
+                self.details_offset = self.details_offset.saturating_sub(10);
+            }
+            key!(PageDown) | ctrl!('d') => {
+                self.details_offset += 10;
+            }
+            key!('r') => self.restart(cx.editor),
+            key!('s') => self.stop(cx.editor),
+            key!(Enter) => {
+                self.open_details(cx.editor);
+                return self.close();
+            }
+            key!(Esc) | ctrl!('c') | key!('q') => return self.close(),
+            _ => (),
+        }
+        EventResult::Consumed(None)
+    }
+
+    fn cursor(&self, _area: Rect, _editor: &Editor) -> (Option<helix_core::Position>, CursorKind) {
+        (None, CursorKind::Hidden)
+    }
+
+    fn id(&self) -> Option<&'static str> {
+        Some(ID)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn format_progress() {
+        let token = lsp::ProgressToken::String("indexing".to_string());
+        assert_eq!(
+            progress_line(&token, &ProgressStatus::Created),
+            "indexing: created"
+        );
+        let status = ProgressStatus::Started {
+            title: "Indexing".to_string(),
+            progress: lsp::WorkDoneProgress::Report(lsp::WorkDoneProgressReport {
+                cancellable: None,
+                message: Some("3/10 crates".to_string()),
+                percentage: Some(30),
+            }),
+        };
+        assert_eq!(progress_line(&token, &status), "Indexing 30% ⋅ 3/10 crates");
+    }
+}
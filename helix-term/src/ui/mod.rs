@@ -1,4 +1,6 @@
 mod completion;
+pub mod debug_console;
+pub mod debug_sidebar;
 mod document;
 pub(crate) mod editor;
 mod info;
@@ -13,11 +15,14 @@
 mod statusline;
 mod text;
 mod text_decorations;
+pub mod workspace_edit;
 
 use crate::compositor::Compositor;
 use crate::filter_picker_entry;
 use crate::job::{self, Callback};
 pub use completion::Completion;
+pub use debug_console::DebugConsole;
+pub use debug_sidebar::DebugSidebar;
 pub use editor::EditorView;
 use helix_stdx::rope;
 use helix_view::theme::Style;
@@ -28,6 +33,7 @@
 pub use prompt::{Prompt, PromptEvent};
 pub use spinner::{ProgressSpinners, Spinner};
 pub use text::Text;
+pub use workspace_edit::WorkspaceEditPreview;
 
 use helix_view::Editor;
 use tui::text::{Span, Spans};
@@ -477,6 +483,14 @@ fn get_keys(value: &serde_json::Value, vec: &mut Vec<String>, scope: Option<&str
         }
     }
 
+    /// Completes the values of `:lsp-set-trace`.
+    pub fn lsp_trace_values(_editor: &Editor, input: &str) -> Vec<Completion> {
+        fuzzy_match(input, ["off", "messages", "verbose"], false)
+            .into_iter()
+            .map(|(name, _)| ((0..), name.into()))
+            .collect()
+    }
+
     /// Completes names of language servers which are running for the current document.
     pub fn active_language_servers(editor: &Editor, input: &str) -> Vec<Completion> {
         let language_servers = doc!(editor).language_servers().map(|ls| ls.name());
@@ -487,6 +501,31 @@ pub fn active_language_servers(editor: &Editor, input: &str) -> Vec<Completion>
             .collect()
     }
 
+    /// Completes the targets of the function, data and instruction breakpoints.
+    pub fn debug_breakpoints(editor: &Editor, input: &str) -> Vec<Completion> {
+        let targets = editor
+            .function_breakpoints
+            .iter()
+            .map(|b| b.name.as_str())
+            .chain(
+                editor
+                    .data_breakpoints
+                    .iter()
+                    .map(|b| b.description.as_str()),
+            )
+            .chain(
+                editor
+                    .instruction_breakpoints
+                    .iter()
+                    .map(|b| b.instruction_reference.as_str()),
+            );
+
+        fuzzy_match(input, targets, false)
+            .into_iter()
+            .map(|(target, _)| ((0..), Span::raw(target.to_string())))
+            .collect()
+    }
+
     /// Completes names of language servers which are configured for the language of the current
     /// document.
     pub fn configured_language_servers(editor: &Editor, input: &str) -> Vec<Completion> {
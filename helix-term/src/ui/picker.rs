@@ -47,6 +47,7 @@
 use helix_view::{
     editor::Action,
     graphics::{CursorKind, Margin, Modifier, Rect},
+    input::KeyEvent,
     theme::Style,
     view::ViewPosition,
     Document, DocumentId, Editor,
@@ -259,6 +260,11 @@ pub struct Picker<T: 'static + Send + Sync, D: 'static> {
 
     callback_fn: PickerCallback<T>,
     default_action: Action,
+    /// Called to expand the selected item into a new picker, for example to list the callers
+    /// of a function.
+    expand_fn: Option<ExpandCallback<T>>,
+    /// Actions on the selected item bound to other keys, see [Picker::with_key_action].
+    key_actions: Vec<(KeyEvent, KeyActionCallback<T>)>,
 
     pub truncate_start: bool,
     /// Caches paths to documents
@@ -387,6 +393,8 @@ fn with(
             show_preview: true,
             callback_fn: Box::new(callback_fn),
             default_action: Action::Replace,
+            expand_fn: None,
+            key_actions: Vec::new(),
             completion_height: 0,
             widths,
             preview_cache: HashMap::new(),
@@ -455,6 +463,35 @@ pub fn with_default_action(mut self, action: Action) -> Self {
         self
     }
 
+    /// Allows expanding the selected item with `Ctrl-o`. The callback is expected to open a new
+    /// picker on top of this one, which stays open so that closing the new picker goes back to it.
+    pub fn with_expand(mut self, expand_fn: impl Fn(&mut Context, &T) + 'static) -> Self {
+        self.expand_fn = Some(Box::new(expand_fn));
+        self
+    }
+
+    /// Binds `key` to an action on the selected item. Unlike the other actions, the picker stays
+    /// open. Actions which change the items can update them with [Picker::set_options].
+    pub fn with_key_action(
+        mut self,
+        key: KeyEvent,
+        action_fn: impl Fn(&mut Context, &T) + 'static,
+    ) -> Self {
+        self.key_actions.push((key, Box::new(action_fn)));
+        self
+    }
+
+    /// Replaces the items of the picker. The query and the position of the cursor are kept.
+    pub fn set_options(&mut self, options: impl IntoIterator<Item = T>) {
+        // Stop streaming in items from before.
+        self.version.fetch_add(1, atomic::Ordering::Relaxed);
+        self.matcher.restart(false);
+        let injector = self.matcher.injector();
+        for item in options {
+            inject_nucleo_item(&injector, &self.columns, item, &self.editor_data);
+        }
+    }
+
     /// Move the cursor by a number of lines, either down (`Forward`) or up (`Backward`)
     pub fn move_by(&mut self, amount: u32, direction: Direction) {
         let len = self.matcher.snapshot().matched_item_count();
@@ -1068,6 +1105,13 @@ fn handle_event(&mut self, event: &Event, ctx: &mut Context) -> EventResult {
             EventResult::Consumed(Some(callback))
         };
 
+        if let Some((_, action_fn)) = self.key_actions.iter().find(|(key, _)| *key == key_event) {
+            if let Some(option) = self.selection() {
+                action_fn(ctx, option);
+            }
+            return EventResult::Consumed(None);
+        }
+
         match key_event {
             shift!(Tab) | key!(Up) | ctrl!('p') => {
                 self.move_by(1, Direction::Backward);
@@ -1143,6 +1187,11 @@ fn handle_event(&mut self, event: &Event, ctx: &mut Context) -> EventResult {
             ctrl!('t') => {
                 self.toggle_preview();
             }
+            ctrl!('o') if self.expand_fn.is_some() => {
+                if let Some((option, expand_fn)) = self.selection().zip(self.expand_fn.as_ref()) {
+                    expand_fn(ctx, option);
+                }
+            }
             _ => {
                 self.prompt_handle_event(event, ctx);
             }
@@ -1187,3 +1236,5 @@ fn drop(&mut self) {
 }
 
 type PickerCallback<T> = Box<dyn Fn(&mut Context, &T, Action)>;
+type ExpandCallback<T> = Box<dyn Fn(&mut Context, &T)>;
+type KeyActionCallback<T> = Box<dyn Fn(&mut Context, &T)>;
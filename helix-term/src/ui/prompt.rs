@@ -159,6 +159,17 @@ pub fn recalculate_completion(&mut self, editor: &Editor) {
         self.completion = (self.completion_fn)(editor, &self.line);
     }
 
+    pub fn completion(&self) -> &[Completion] {
+        &self.completion
+    }
+
+    /// Replaces the completions, for example with completions that were requested
+    /// asynchronously.
+    pub fn set_completion(&mut self, completion: Vec<Completion>) {
+        self.exit_selection();
+        self.completion = completion;
+    }
+
     /// Compute the cursor position after applying movement
     /// Taken from: <https://github.com/wez/wezterm/blob/e0b62d07ca9bf8ce69a61e30a3c20e7abc48ce7e/termwiz/src/lineedit/mod.rs#L516-L611>
     fn eval_movement(&self, movement: Movement) -> usize {
@@ -6,9 +6,15 @@
 
 use crate::ui::document::{LinePos, TextRenderer};
 
+pub use code_lens::CodeLenses;
 pub use diagnostics::InlineDiagnostics;
+pub use inline_completion::InlineCompletion;
+pub use inline_values::InlineValues;
 
+mod code_lens;
 mod diagnostics;
+mod inline_completion;
+mod inline_values;
 
 /// Decorations are the primary mechanism for extending the text rendering.
 ///
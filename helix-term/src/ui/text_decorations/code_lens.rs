@@ -0,0 +1,126 @@
+use helix_core::doc_formatter::FormattedGrapheme;
+use helix_core::line_ending::line_end_char_index;
+use helix_core::Position;
+use helix_view::annotations::code_lens::code_lens_rows;
+use helix_view::theme::Style;
+use helix_view::{Document, Theme, ViewId};
+
+use crate::ui::document::{LinePos, TextRenderer};
+use crate::ui::text_decorations::Decoration;
+
+struct LensRow {
+    /// The char index of the line ending the row is rendered below.
+    anchor: usize,
+    /// The column the row starts at, the indentation of the line the lenses belong to.
+    col: usize,
+    label: String,
+}
+
+/// Renders the code lenses of a document into the virtual lines reserved by
+/// [`helix_view::annotations::code_lens::CodeLenses`].
+pub struct CodeLenses {
+    rows: Vec<LensRow>,
+    idx: usize,
+    pending: Vec<usize>,
+    style: Style,
+}
+
+impl CodeLenses {
+    pub fn new(doc: &Document, view_id: ViewId, theme: &Theme) -> Self {
+        let text = doc.text().slice(..);
+        let tab_width = doc.tab_width();
+        let rows = code_lens_rows(doc, view_id)
+            .into_iter()
+            .map(|(anchor_line, lens_line)| {
+                let label = doc
+                    .code_lenses
+                    .iter()
+                    .filter(|lens| {
+                        text.char_to_line(lens.char_idx.min(text.len_chars())) == lens_line
+                    })
+                    .filter_map(|lens| lens.lens.command.as_ref())
+                    .map(|command| command.title.as_str())
+                    .collect::<Vec<_>>()
+                    .join(" | ");
+                let col = text
+                    .line(lens_line)
+                    .chars()
+                    .take_while(|&ch| ch == ' ' || ch == '\t')
+                    .fold(0, |col, ch| match ch {
+                        '\t' => col + tab_width - col % tab_width,
+                        _ => col + 1,
+                    });
+                LensRow {
+                    anchor: line_end_char_index(&text, anchor_line),
+                    col,
+                    label,
+                }
+            })
+            .collect();
+
+        CodeLenses {
+            rows,
+            idx: 0,
+            pending: Vec::new(),
+            style: theme.get("ui.virtual.code-lens"),
+        }
+    }
+
+    fn next_anchor(&self) -> usize {
+        self.rows.get(self.idx).map_or(usize::MAX, |row| row.anchor)
+    }
+}
+
+impl Decoration for CodeLenses {
+    fn reset_pos(&mut self, pos: usize) -> usize {
+        self.idx = self.rows.partition_point(|row| row.anchor < pos);
+        self.pending.clear();
+        self.next_anchor()
+    }
+
+    fn decorate_grapheme(
+        &mut self,
+        _renderer: &mut TextRenderer,
+        grapheme: &FormattedGrapheme,
+    ) -> usize {
+        while self
+            .rows
+            .get(self.idx)
+            .is_some_and(|row| row.anchor == grapheme.char_idx)
+        {
+            self.pending.push(self.idx);
+            self.idx += 1;
+        }
+        self.next_anchor()
+    }
+
+    fn render_virt_lines(
+        &mut self,
+        renderer: &mut TextRenderer,
+        pos: LinePos,
+        virt_off: Position,
+    ) -> Position {
+        let height = self.pending.len();
+        for (i, idx) in self.pending.drain(..).enumerate() {
+            let row = &self.rows[idx];
+            let line = pos.visual_line + (virt_off.row + i) as u16;
+            if line >= renderer.viewport.height {
+                break;
+            }
+            let col = row.col.saturating_sub(renderer.offset.col) as u16;
+            if col >= renderer.viewport.width {
+                continue;
+            }
+            renderer.set_string_truncated(
+                renderer.viewport.x + col,
+                line,
+                &row.label,
+                (renderer.viewport.width - col) as usize,
+                |_| self.style,
+                true,
+                false,
+            );
+        }
+        Position::new(height, 0)
+    }
+}
@@ -0,0 +1,141 @@
+use std::borrow::Cow;
+
+use helix_core::doc_formatter::FormattedGrapheme;
+use helix_core::line_ending::line_end_char_index;
+use helix_core::unicode::width::UnicodeWidthStr;
+use helix_core::Position;
+use helix_view::annotations::inline_completion::inline_completion_ghost_text;
+use helix_view::theme::Style;
+use helix_view::{Document, Theme, ViewId};
+
+use crate::ui::document::{LinePos, TextRenderer};
+use crate::ui::text_decorations::Decoration;
+
+/// Renders the inline completion displayed in a view as virtual text.
+///
+/// The first line is drawn over the text after the cursor, the other lines are rendered into
+/// the virtual lines reserved by
+/// [`helix_view::annotations::inline_completion::InlineCompletionLines`].
+pub struct InlineCompletion {
+    cursor: usize,
+    line_end: usize,
+    lines: Vec<String>,
+    /// The width of the text after the cursor which is replaced by the completion.
+    replaced_width: usize,
+    /// The visual column of the cursor, set once the cursor was rendered.
+    cursor_col: Option<usize>,
+    /// Whether the end of the line of the cursor was rendered.
+    at_line_end: bool,
+    style: Style,
+}
+
+impl InlineCompletion {
+    pub fn new(doc: &Document, view_id: ViewId, theme: &Theme) -> Option<Self> {
+        let (cursor, ghost_text) = inline_completion_ghost_text(doc, view_id)?;
+        let text = doc.text().slice(..);
+        let line_end = line_end_char_index(&text, text.char_to_line(cursor));
+        let item = doc.inline_completion.as_ref()?.item()?;
+        let replaced = Cow::from(text.slice(cursor..item.range.end.min(line_end)));
+
+        let tab = " ".repeat(doc.tab_width());
+        let lines = ghost_text
+            .lines()
+            .map(|line| line.replace('\t', &tab))
+            .collect();
+
+        Some(InlineCompletion {
+            cursor,
+            line_end,
+            lines,
+            replaced_width: replaced.width(),
+            cursor_col: None,
+            at_line_end: false,
+            style: theme.get("ui.virtual.inline-completion"),
+        })
+    }
+
+    fn next_anchor(&self, pos: usize) -> usize {
+        if pos <= self.cursor {
+            self.cursor
+        } else if pos <= self.line_end {
+            self.line_end
+        } else {
+            usize::MAX
+        }
+    }
+}
+
+impl Decoration for InlineCompletion {
+    fn reset_pos(&mut self, pos: usize) -> usize {
+        self.cursor_col = None;
+        self.at_line_end = false;
+        self.next_anchor(pos)
+    }
+
+    fn decorate_grapheme(
+        &mut self,
+        _renderer: &mut TextRenderer,
+        grapheme: &FormattedGrapheme,
+    ) -> usize {
+        if grapheme.char_idx == self.cursor {
+            self.cursor_col = Some(grapheme.visual_pos.col);
+        }
+        if grapheme.char_idx == self.line_end {
+            self.at_line_end = true;
+        }
+        self.next_anchor(grapheme.char_idx + 1)
+    }
+
+    fn render_virt_lines(
+        &mut self,
+        renderer: &mut TextRenderer,
+        pos: LinePos,
+        virt_off: Position,
+    ) -> Position {
+        let mut col_off = 0;
+        if let Some(col) = self.cursor_col.take() {
+            // Pad the first line to hide the replaced text.
+            let first_line = format!(
+                "{:width$}",
+                self.lines.first().map_or("", String::as_str),
+                width = self.replaced_width
+            );
+            col_off = first_line.width() + 1;
+            if let Some(col) = col.checked_sub(renderer.offset.col) {
+                if col < renderer.viewport.width as usize {
+                    renderer.set_string_truncated(
+                        renderer.viewport.x + col as u16,
+                        pos.visual_line,
+                        &first_line,
+                        renderer.viewport.width as usize - col,
+                        |_| self.style,
+                        false,
+                        false,
+                    );
+                }
+            }
+        }
+
+        if !std::mem::take(&mut self.at_line_end) {
+            return Position::new(0, col_off);
+        }
+        let height = self.lines.len().saturating_sub(1);
+        for (i, line) in self.lines.iter().skip(1).enumerate() {
+            let row = pos.visual_line + (virt_off.row + i) as u16;
+            if row >= renderer.viewport.height {
+                break;
+            }
+            let line: String = line.chars().skip(renderer.offset.col).collect();
+            renderer.set_string_truncated(
+                renderer.viewport.x,
+                row,
+                &line,
+                renderer.viewport.width as usize,
+                |_| self.style,
+                false,
+                false,
+            );
+        }
+        Position::new(height, col_off)
+    }
+}
@@ -0,0 +1,112 @@
+use helix_core::doc_formatter::FormattedGrapheme;
+use helix_core::line_ending::line_end_char_index;
+use helix_core::Position;
+use helix_view::theme::Style;
+use helix_view::{Document, Editor};
+
+use crate::ui::document::{LinePos, TextRenderer};
+use crate::ui::text_decorations::Decoration;
+
+struct InlineValue<'a> {
+    /// The char index of the line ending the value is rendered after.
+    anchor: usize,
+    text: &'a str,
+}
+
+/// Renders the values of the active stack frame at the end of its source lines, see
+/// [`helix_dap::Client::inline_values`].
+pub struct InlineValues<'a> {
+    values: Vec<InlineValue<'a>>,
+    idx: usize,
+    /// The value whose line end was rendered last.
+    pending: Option<&'a str>,
+    style: Style,
+}
+
+impl<'a> InlineValues<'a> {
+    pub fn new(editor: &'a Editor, doc: &Document) -> Option<Self> {
+        let debugger = editor.debug_adapters.get_active_client()?;
+        let path = editor
+            .current_stack_frame()?
+            .source
+            .as_ref()?
+            .path
+            .as_ref()?;
+        if debugger.inline_values.is_empty() || doc.path() != Some(path) {
+            return None;
+        }
+
+        let text = doc.text().slice(..);
+        let values = debugger
+            .inline_values
+            .iter()
+            .filter(|value| value.line < text.len_lines())
+            .map(|value| InlineValue {
+                anchor: line_end_char_index(&text, value.line),
+                text: &value.text,
+            })
+            .collect();
+
+        Some(InlineValues {
+            values,
+            idx: 0,
+            pending: None,
+            style: editor.theme.get("ui.virtual.inline-value"),
+        })
+    }
+
+    fn next_anchor(&self) -> usize {
+        self.values
+            .get(self.idx)
+            .map_or(usize::MAX, |value| value.anchor)
+    }
+}
+
+impl Decoration for InlineValues<'_> {
+    fn reset_pos(&mut self, pos: usize) -> usize {
+        self.idx = self.values.partition_point(|value| value.anchor < pos);
+        self.pending = None;
+        self.next_anchor()
+    }
+
+    fn decorate_grapheme(
+        &mut self,
+        _renderer: &mut TextRenderer,
+        grapheme: &FormattedGrapheme,
+    ) -> usize {
+        if let Some(value) = self.values.get(self.idx) {
+            if value.anchor == grapheme.char_idx {
+                self.pending = Some(value.text);
+                self.idx += 1;
+            }
+        }
+        self.next_anchor()
+    }
+
+    fn render_virt_lines(
+        &mut self,
+        renderer: &mut TextRenderer,
+        pos: LinePos,
+        virt_off: Position,
+    ) -> Position {
+        let Some(text) = self.pending.take() else {
+            return Position::new(0, 0);
+        };
+        // Leave a space between the end of the line and the value.
+        let col = virt_off.col + 1;
+        if !renderer.column_in_bounds(col, 1) {
+            return Position::new(0, 0);
+        }
+        let col = col - renderer.offset.col;
+        let (end, _) = renderer.set_string_truncated(
+            renderer.viewport.x + col as u16,
+            pos.visual_line,
+            text,
+            renderer.viewport.width as usize - col,
+            |_| self.style,
+            true,
+            false,
+        );
+        Position::new(0, (end - renderer.viewport.x) as usize - col + 1)
+    }
+}
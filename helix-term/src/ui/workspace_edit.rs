@@ -0,0 +1,549 @@
+use std::borrow::Cow;
+
+use helix_core::diff::compare_rope_lines;
+use helix_core::{Rope, Uri};
+use helix_lsp::{lsp, OffsetEncoding};
+use helix_view::graphics::{CursorKind, Margin, Rect};
+use helix_view::handlers::lsp::{select_workspace_edit_items, ApplyEditError, WorkspaceEditItem};
+use helix_view::input::Event;
+use helix_view::theme::Style;
+use helix_view::Editor;
+use tui::buffer::Buffer as Surface;
+use tui::widgets::{Block, Widget};
+
+use crate::compositor::{Component, Context, EventResult};
+use crate::{ctrl, key, shift};
+
+pub const ID: &str = "workspace-edit";
+
+/// The number of unchanged lines shown around the changed lines of a diff.
+const DIFF_CONTEXT: usize = 3;
+
+type OnClose = Box<dyn FnOnce(&mut Editor, Option<Result<(), ApplyEditError>>) + Send>;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DiffLine {
+    Header(String),
+    Context(String),
+    Removed(String),
+    Added(String),
+}
+
+fn line_text(text: &Rope, line: usize) -> String {
+    let line = Cow::from(text.line(line));
+    line.trim_end_matches(['\n', '\r']).to_string()
+}
+
+/// Renders the changes between `old` and `new` as unified diff hunks.
+pub fn diff_lines(old: &Rope, new: &Rope) -> Vec<DiffLine> {
+    let hunks = compare_rope_lines(old, new);
+    // Don't count the empty line after a trailing line ending as context.
+    let mut old_lines = old.len_lines();
+    if old.line(old_lines - 1).len_chars() == 0 {
+        old_lines -= 1;
+    }
+    let mut lines = Vec::new();
+    let mut hunks = hunks.iter().peekable();
+    while let Some(first) = hunks.next() {
+        // Merge the hunks whose context overlaps.
+        let mut group = vec![first];
+        while let Some(next) = hunks.peek() {
+            let end = group.last().unwrap().0.end;
+            if next.0.start > end + 2 * DIFF_CONTEXT {
+                break;
+            }
+            group.push(hunks.next().unwrap());
+        }
+
+        let (first_before, first_after) = first;
+        let (last_before, last_after) = group.last().unwrap();
+        let context_before = first_before.start.min(DIFF_CONTEXT);
+        let old_start = first_before.start - context_before;
+        let new_start = first_after.start - context_before;
+        let old_end = (last_before.end + DIFF_CONTEXT).min(old_lines.max(last_before.end));
+        let context_after = old_end - last_before.end;
+        let new_end = last_after.end + context_after;
+        lines.push(DiffLine::Header(format!(
+            "@@ -{},{} +{},{} @@",
+            old_start + 1,
+            old_end - old_start,
+            new_start + 1,
+            new_end - new_start
+        )));
+
+        let mut pos = old_start;
+        for (before, after) in group {
+            lines.extend((pos..before.start).map(|line| DiffLine::Context(line_text(old, line))));
+            lines.extend(
+                before
+                    .clone()
+                    .map(|line| DiffLine::Removed(line_text(old, line))),
+            );
+            lines.extend(
+                after
+                    .clone()
+                    .map(|line| DiffLine::Added(line_text(new, line))),
+            );
+            pos = before.end;
+        }
+        lines.extend((pos..old_end).map(|line| DiffLine::Context(line_text(old, line))));
+    }
+    lines
+}
+
+fn display_url(url: &lsp::Url) -> String {
+    match Uri::try_from(url).ok().as_ref().and_then(Uri::as_path) {
+        Some(path) => helix_stdx::path::get_relative_path(path)
+            .to_string_lossy()
+            .into_owned(),
+        None => url.to_string(),
+    }
+}
+
+fn item_label(item: &WorkspaceEditItem) -> String {
+    let change = match &item.change {
+        lsp::DocumentChangeOperation::Edit(edit) => {
+            let count = edit.edits.len();
+            format!(
+                "{} ({count} edit{})",
+                display_url(&edit.text_document.uri),
+                if count == 1 { "" } else { "s" }
+            )
+        }
+        lsp::DocumentChangeOperation::Op(lsp::ResourceOp::Create(op)) => {
+            format!("create {}", display_url(&op.uri))
+        }
+        lsp::DocumentChangeOperation::Op(lsp::ResourceOp::Rename(op)) => format!(
+            "rename {} to {}",
+            display_url(&op.old_uri),
+            display_url(&op.new_uri)
+        ),
+        lsp::DocumentChangeOperation::Op(lsp::ResourceOp::Delete(op)) => {
+            format!("delete {}", display_url(&op.uri))
+        }
+    };
+    match &item.annotation {
+        Some(annotation) => format!("{change}: {}", annotation.label),
+        None => change,
+    }
+}
+
+/// Lists the changes of a workspace edit with a diff of the selected change and lets the user
+/// pick the changes to apply. Changes the language server asked to confirm are unselected
+/// initially.
+pub struct WorkspaceEditPreview {
+    items: Vec<WorkspaceEditItem>,
+    selected: Vec<bool>,
+    cursor: usize,
+    list_offset: usize,
+    diff_offset: usize,
+    /// The diff of the item under the cursor.
+    diff: Option<(usize, Vec<DiffLine>)>,
+    offset_encoding: OffsetEncoding,
+    on_close: Option<OnClose>,
+}
+
+impl WorkspaceEditPreview {
+    pub fn new(
+        items: Vec<WorkspaceEditItem>,
+        offset_encoding: OffsetEncoding,
+        on_close: impl FnOnce(&mut Editor, Option<Result<(), ApplyEditError>>) + Send + 'static,
+    ) -> Self {
+        let selected = items
+            .iter()
+            .map(|item| !item.needs_confirmation())
+            .collect();
+        Self {
+            items,
+            selected,
+            cursor: 0,
+            list_offset: 0,
+            diff_offset: 0,
+            diff: None,
+            offset_encoding,
+            on_close: Some(Box::new(on_close)),
+        }
+    }
+
+    fn move_cursor(&mut self, cursor: usize) {
+        self.cursor = cursor.min(self.items.len().saturating_sub(1));
+        self.diff_offset = 0;
+    }
+
+    fn close(&mut self, editor: &mut Editor, apply: bool) -> EventResult {
+        // Applying none of the changes is the same as rejecting the edit.
+        let apply = apply && self.selected.contains(&true);
+        let res = apply.then(|| {
+            let items = self
+                .items
+                .iter()
+                .zip(&self.selected)
+                .filter(|(_, selected)| **selected)
+                .map(|(item, _)| item);
+            editor.apply_workspace_edit(self.offset_encoding, &select_workspace_edit_items(items))
+        });
+        if let Some(on_close) = self.on_close.take() {
+            on_close(editor, res);
+        }
+        // Previews consume every key, so the preview closed by a key is the front-most one.
+        // Removing by `ID` alone would close the wrong preview when several are stacked.
+        EventResult::Consumed(Some(Box::new(|compositor, _| {
+            compositor.remove_front(ID);
+        })))
+    }
+
+    fn render_list(&mut self, area: Rect, surface: &mut Surface, cx: &mut Context) {
+        let text_style = cx.editor.theme.get("ui.text");
+        let selected_style = cx.editor.theme.get("ui.text.focus");
+        let background = cx.editor.theme.get("ui.background");
+        surface.clear_with(area, background);
+
+        let count = self.selected.iter().filter(|selected| **selected).count();
+        let title = format!(
+            " Workspace edit ({count}/{} selected) - space: toggle, a: toggle all, enter: apply ",
+            self.items.len()
+        );
+        let block = Block::bordered().title(title.as_str());
+        let inner = block.inner(area).inner(Margin::horizontal(1));
+        block.render(area, surface);
+
+        let height = inner.height as usize;
+        if self.cursor < self.list_offset {
+            self.list_offset = self.cursor;
+        } else if self.cursor >= self.list_offset + height {
+            self.list_offset = self.cursor + 1 - height;
+        }
+
+        for (i, (item, selected)) in self
+            .items
+            .iter()
+            .zip(&self.selected)
+            .enumerate()
+            .skip(self.list_offset)
+            .take(height)
+        {
+            let style = if i == self.cursor {
+                selected_style
+            } else {
+                text_style
+            };
+            let label = format!(
+                "[{}] {}",
+                if *selected { 'x' } else { ' ' },
+                item_label(item)
+            );
+            surface.set_stringn(
+                inner.x,
+                inner.y + (i - self.list_offset) as u16,
+                label,
+                inner.width as usize,
+                style,
+            );
+        }
+    }
+
+    fn render_diff(&mut self, area: Rect, surface: &mut Surface, cx: &mut Context) {
+        let theme = &cx.editor.theme;
+        let text_style = theme.get("ui.text");
+        let header_style = theme.get("diff.delta");
+        let removed_style = theme.get("diff.minus");
+        let added_style = theme.get("diff.plus");
+        let background = theme.get("ui.background");
+        surface.clear_with(area, background);
+
+        let block = Block::bordered();
+        let inner = block.inner(area).inner(Margin::horizontal(1));
+        block.render(area, surface);
+
+        let Some(item) = self.items.get(self.cursor) else {
+            return;
+        };
+        if self
+            .diff
+            .as_ref()
+            .is_none_or(|(idx, _)| *idx != self.cursor)
+        {
+            let diff = cx
+                .editor
+                .workspace_edit_item_preview(item, self.offset_encoding)
+                .map(|(old, new)| diff_lines(&old, &new))
+                .unwrap_or_default();
+            self.diff = Some((self.cursor, diff));
+        }
+        let diff = &self.diff.as_ref().unwrap().1;
+        if diff.is_empty() {
+            let placeholder = match &item.change {
+                lsp::DocumentChangeOperation::Edit(_) => "<No changes>",
+                lsp::DocumentChangeOperation::Op(_) => "<No preview available>",
+            };
+            let x = inner.x + inner.width.saturating_sub(placeholder.len() as u16) / 2;
+            let y = inner.y + inner.height / 2;
+            surface.set_stringn(x, y, placeholder, inner.width as usize, text_style);
+            return;
+        }
+
+        self.diff_offset = self
+            .diff_offset
+            .min(diff.len().saturating_sub(inner.height as usize));
+        for (i, line) in diff
+            .iter()
+            .skip(self.diff_offset)
+            .take(inner.height as usize)
+            .enumerate()
+        {
+            let (text, style): (Cow<str>, Style) = match line {
+                DiffLine::Header(text) => (text.into(), header_style),
+                DiffLine::Context(text) => (format!(" {text}").into(), text_style),
+                DiffLine::Removed(text) => (format!("-{text}").into(), removed_style),
+                DiffLine::Added(text) => (format!("+{text}").into(), added_style),
+            };
+            surface.set_stringn(
+                inner.x,
+                inner.y + i as u16,
+                text,
+                inner.width as usize,
+                style,
+            );
+        }
+    }
+}
+
+impl Drop for WorkspaceEditPreview {
+    fn drop(&mut self) {
+        // The preview was removed without being closed, for example by another component
+        // replacing it. The edit is rejected so that a language server waiting for a reply to
+        // `workspace/applyEdit` gets one.
+        if let Some(on_close) = self.on_close.take() {
+            crate::job::dispatch_blocking(move |editor, _compositor| on_close(editor, None));
+        }
+    }
+}
+
+impl Component for WorkspaceEditPreview {
+    fn render(&mut self, area: Rect, surface: &mut Surface, cx: &mut Context) {
+        // +---------+ +---------+
+        // |changes  | |diff     |
+        // |         | |         |
+        // +---------+ +---------+
+        let list_width = area.width / 2;
+        self.render_list(area.with_width(list_width), surface, cx);
+        self.render_diff(area.clip_left(list_width), surface, cx);
+    }
+
+    fn handle_event(&mut self, event: &Event, cx: &mut Context) -> EventResult {
+        let key_event = match event {
+            Event::Key(event) => *event,
+            Event::Resize(..) => return EventResult::Consumed(None),
+            _ => return EventResult::Ignored(None),
+        };
+
+        match key_event {
+            shift!(Tab) | key!(Up) | ctrl!('p') | key!('k') => {
+                self.move_cursor(self.cursor.saturating_sub(1));
+            }
+            key!(Tab) | key!(Down) | ctrl!('n') | key!('j') => {
+                self.move_cursor(self.cursor + 1);
+            }
+            key!(Home) => self.move_cursor(0),
+            key!(End) => self.move_cursor(usize::MAX),
+            key!(PageUp) | ctrl!('u') => {
+                self.diff_offset = self.diff_offset.saturating_sub(10);
+            }
+            key!(PageDown) | ctrl!('d') => {
+                self.diff_offset += 10;
+            }
+            key!(' ') => {
+                if let Some(selected) = self.selected.get_mut(self.cursor) {
+                    *selected = !*selected;
+                }
+            }
+            key!('a') => {
+                let select = !self.selected.iter().all(|selected| *selected);
+                self.selected.fill(select);
+            }
+            key!(Enter) => return self.close(cx.editor, true),
+            key!(Esc) | ctrl!('c') => return self.close(cx.editor, false),
+            _ => (),
+        }
+        EventResult::Consumed(None)
+    }
+
+    fn cursor(&self, _area: Rect, _editor: &Editor) -> (Option<helix_core::Position>, CursorKind) {
+        (None, CursorKind::Hidden)
+    }
+
+    fn id(&self) -> Option<&'static str> {
+        Some(ID)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::{Arc, Mutex};
+
+    use helix_view::input::KeyEvent;
+
+    use super::*;
+    use crate::compositor::Compositor;
+    use crate::handlers::test_editor;
+    use crate::job::{test_jobs, Callback};
+    use crate::ui::overlay::overlaid;
+
+    type Closed = Arc<Mutex<Vec<(usize, Option<bool>)>>>;
+
+    /// A preview of an edit replacing the first line of `path` with `text` which records how
+    /// it was closed in `closed`.
+    fn preview(
+        path: &std::path::Path,
+        text: &str,
+        idx: usize,
+        closed: &Closed,
+    ) -> WorkspaceEditPreview {
+        let edit = lsp::WorkspaceEdit {
+            document_changes: Some(lsp::DocumentChanges::Edits(vec![lsp::TextDocumentEdit {
+                text_document: lsp::OptionalVersionedTextDocumentIdentifier {
+                    uri: lsp::Url::from_file_path(path).unwrap(),
+                    version: None,
+                },
+                edits: vec![lsp::OneOf::Left(lsp::TextEdit {
+                    range: lsp::Range::new(lsp::Position::new(0, 0), lsp::Position::new(0, 5)),
+                    new_text: text.to_string(),
+                })],
+            }])),
+            ..Default::default()
+        };
+        let closed = closed.clone();
+        WorkspaceEditPreview::new(
+            helix_view::handlers::lsp::workspace_edit_items(&edit),
+            OffsetEncoding::Utf8,
+            move |_editor, res| {
+                closed
+                    .lock()
+                    .unwrap()
+                    .push((idx, res.map(|res| res.is_ok())))
+            },
+        )
+    }
+
+    fn text(editor: &Editor, path: &std::path::Path) -> String {
+        editor.document_by_path(path).unwrap().text().to_string()
+    }
+
+    #[tokio::test]
+    async fn close_front_most_preview() {
+        let mut jobs = test_jobs();
+        let mut editor = test_editor();
+        let file = tempfile::NamedTempFile::new().unwrap();
+        std::fs::write(file.path(), "hello\n").unwrap();
+        let closed = Closed::default();
+
+        let mut compositor = Compositor::new(editor.tree.area());
+        compositor.push(Box::new(overlaid(preview(
+            file.path(),
+            "world",
+            0,
+            &closed,
+        ))));
+        compositor.push(Box::new(overlaid(preview(
+            file.path(),
+            "there",
+            1,
+            &closed,
+        ))));
+        let mut cx = crate::compositor::Context {
+            editor: &mut editor,
+            scroll: None,
+            jobs: &mut jobs,
+        };
+        let mut press = |key: KeyEvent| compositor.handle_event(&Event::Key(key), &mut cx);
+
+        // Enter applies the edit of the front-most preview and closes only that preview.
+        assert!(press(key!(Enter)));
+        assert_eq!(*closed.lock().unwrap(), vec![(1, Some(true))]);
+
+        // Escape rejects the edit of the remaining preview.
+        assert!(press(key!(Esc)));
+        assert_eq!(*closed.lock().unwrap(), vec![(1, Some(true)), (0, None)]);
+        assert!(!press(key!(Enter)));
+        assert_eq!(closed.lock().unwrap().len(), 2);
+        assert_eq!(text(&editor, file.path()), "there\n");
+    }
+
+    #[tokio::test]
+    async fn apply_selected_changes() {
+        let _jobs = test_jobs();
+        let mut editor = test_editor();
+        let file = tempfile::NamedTempFile::new().unwrap();
+        std::fs::write(file.path(), "hello\n").unwrap();
+        let closed = Closed::default();
+
+        // Applying without any selected change rejects the edit.
+        let mut preview = preview(file.path(), "world", 0, &closed);
+        preview.selected.fill(false);
+        preview.close(&mut editor, true);
+        assert_eq!(*closed.lock().unwrap(), vec![(0, None)]);
+
+        let mut preview = self::preview(file.path(), "world", 1, &closed);
+        preview.close(&mut editor, true);
+        assert_eq!(closed.lock().unwrap()[1], (1, Some(true)));
+        assert_eq!(text(&editor, file.path()), "world\n");
+    }
+
+    #[tokio::test]
+    async fn drop_rejects_edit() {
+        let mut jobs = test_jobs();
+        let mut editor = test_editor();
+        let mut compositor = Compositor::new(editor.tree.area());
+        while jobs.callbacks.try_recv().is_ok() {}
+        let closed = Closed::default();
+
+        drop(preview(std::path::Path::new("/a"), "world", 0, &closed));
+        let Ok(Callback::EditorCompositor(callback)) = jobs.callbacks.try_recv() else {
+            panic!("dropping the preview did not dispatch a callback");
+        };
+        callback(&mut editor, &mut compositor);
+        assert_eq!(*closed.lock().unwrap(), vec![(0, None)]);
+
+        // A closed preview does not reply again when dropped.
+        let mut preview = preview(std::path::Path::new("/a"), "world", 1, &closed);
+        preview.close(&mut editor, false);
+        drop(preview);
+        assert!(jobs.callbacks.try_recv().is_err());
+        assert_eq!(*closed.lock().unwrap(), vec![(0, None), (1, None)]);
+    }
+
+    #[test]
+    fn diff_hunks() {
+        let old = Rope::from("1\n2\n3\n4\n5\n6\n7\n8\n9\n10\n11\n12\n13\n14\n15\n16\n");
+        let new = Rope::from("1\ntwo\n3\n4\n5\n6\n7\n8\n9\n10\n11\n12\n13\n14\n15\n");
+        assert_eq!(
+            diff_lines(&old, &new),
+            vec![
+                DiffLine::Header("@@ -1,5 +1,5 @@".to_string()),
+                DiffLine::Context("1".to_string()),
+                DiffLine::Removed("2".to_string()),
+                DiffLine::Added("two".to_string()),
+                DiffLine::Context("3".to_string()),
+                DiffLine::Context("4".to_string()),
+                DiffLine::Context("5".to_string()),
+                DiffLine::Header("@@ -13,4 +13,3 @@".to_string()),
+                DiffLine::Context("13".to_string()),
+                DiffLine::Context("14".to_string()),
+                DiffLine::Context("15".to_string()),
+                DiffLine::Removed("16".to_string()),
+            ]
+        );
+
+        // Hunks whose context overlaps are merged.
+        let new = Rope::from("1\ntwo\n3\n4\n5\n6\nseven\n8\n9\n10\n11\n12\n13\n14\n15\n16\n");
+        let lines = diff_lines(&old, &new);
+        assert_eq!(lines[0], DiffLine::Header("@@ -1,10 +1,10 @@".to_string()));
+        assert_eq!(
+            lines
+                .iter()
+                .filter(|line| matches!(line, DiffLine::Header(_)))
+                .count(),
+            1
+        );
+    }
+}
@@ -839,3 +839,110 @@ async fn test_move_file_when_given_dir_only() -> anyhow::Result<()> {
 
     Ok(())
 }
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_create_copy_and_delete_file() -> anyhow::Result<()> {
+    let dir = tempfile::tempdir()?;
+    let created_file = dir.path().join("created.ext");
+    let created_dir = dir.path().join("created_dir");
+    let copied_file = created_dir.join("created.ext");
+
+    let create = format!(
+        ":create-file {}<ret>:create-file {}/<ret>",
+        created_file.to_string_lossy(),
+        created_dir.to_string_lossy()
+    );
+    let copy = format!(
+        ":copy-file {} {}<ret>",
+        created_file.to_string_lossy(),
+        created_dir.to_string_lossy()
+    );
+    // Directories are only deleted with `:delete-file!`.
+    let delete_dir = format!(":delete-file {}<ret>", created_dir.to_string_lossy());
+    let force_delete_dir = format!(":delete-file! {}<ret>", created_dir.to_string_lossy());
+
+    test_key_sequences(
+        &mut helpers::AppBuilder::new().build()?,
+        vec![
+            (
+                Some(&create),
+                Some(&|_| {
+                    assert!(created_file.is_file());
+                    assert!(created_dir.is_dir());
+                }),
+            ),
+            (
+                Some(&copy),
+                Some(&|_| {
+                    assert!(copied_file.is_file());
+                    assert!(created_file.is_file());
+                }),
+            ),
+            (
+                Some(&delete_dir),
+                Some(&|app| {
+                    assert!(copied_file.is_file());
+                    assert!(app.editor.is_err());
+                }),
+            ),
+            (
+                Some(&force_delete_dir),
+                Some(&|_| {
+                    assert!(!created_dir.exists());
+                    assert!(created_file.is_file());
+                }),
+            ),
+        ],
+        false,
+    )
+    .await?;
+
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_copy_file_overwrites_only_when_forced() -> anyhow::Result<()> {
+    let dir = tempfile::tempdir()?;
+    let source = dir.path().join("source.ext");
+    let destination = dir.path().join("destination.ext");
+    std::fs::write(&source, "source")?;
+    std::fs::write(&destination, "destination")?;
+
+    // An existing file is only overwritten with `:copy-file!`.
+    let copy = format!(
+        ":copy-file {} {}<ret>",
+        source.to_string_lossy(),
+        destination.to_string_lossy()
+    );
+    let force_copy = format!(
+        ":copy-file! {} {}<ret>",
+        source.to_string_lossy(),
+        destination.to_string_lossy()
+    );
+
+    test_key_sequences(
+        &mut helpers::AppBuilder::new().build()?,
+        vec![
+            (
+                Some(&copy),
+                Some(&|app| {
+                    assert!(app.editor.is_err());
+                    assert_eq!(
+                        std::fs::read_to_string(&destination).unwrap(),
+                        "destination"
+                    );
+                }),
+            ),
+            (
+                Some(&force_copy),
+                Some(&|_| {
+                    assert_eq!(std::fs::read_to_string(&destination).unwrap(), "source");
+                }),
+            ),
+        ],
+        false,
+    )
+    .await?;
+
+    Ok(())
+}
@@ -1 +1,3 @@
+pub mod code_lens;
 pub mod diagnostics;
+pub mod inline_completion;
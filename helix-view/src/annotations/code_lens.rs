@@ -0,0 +1,71 @@
+use helix_core::text_annotations::LineAnnotation;
+use helix_core::{Position, RopeSlice};
+
+use crate::{Document, ViewId};
+
+/// The virtual lines used to display the code lenses of a document.
+///
+/// Code lenses are displayed in a virtual line above the line they belong to. Virtual lines can
+/// only be inserted at the end of a line, so the lenses of a line are anchored to the end of the
+/// previous line. Lenses on the first line are displayed below it instead as there is no line
+/// to anchor them to.
+///
+/// Lenses of lines that are folded in `view_id` are not displayed.
+///
+/// Returns `(anchor_line, lens_line)` pairs, sorted and deduplicated.
+pub fn code_lens_rows(doc: &Document, view_id: ViewId) -> Vec<(usize, usize)> {
+    let text = doc.text().slice(..);
+    let folds = doc.folds(view_id);
+    let mut rows: Vec<_> = doc
+        .code_lenses
+        .iter()
+        .filter(|lens| lens.lens.command.is_some())
+        .filter_map(|lens| {
+            let line = text.char_to_line(lens.char_idx.min(text.len_chars()));
+            let line_start = text.line_to_char(line);
+            let folded = folds
+                .iter()
+                .any(|fold| fold.start <= line_start && line_start < fold.end);
+            (!folded).then_some((line.saturating_sub(1), line))
+        })
+        .collect();
+    rows.sort_unstable();
+    rows.dedup();
+    rows
+}
+
+/// Reserves the virtual lines for code lenses, see [`code_lens_rows`].
+pub struct CodeLenses<'a> {
+    text: RopeSlice<'a>,
+    rows: Vec<(usize, usize)>,
+}
+
+impl<'a> CodeLenses<'a> {
+    #[allow(clippy::new_ret_no_self)]
+    pub(crate) fn new(doc: &'a Document, view_id: ViewId) -> Box<dyn LineAnnotation + 'a> {
+        Box::new(CodeLenses {
+            text: doc.text().slice(..),
+            rows: code_lens_rows(doc, view_id),
+        })
+    }
+}
+
+impl LineAnnotation for CodeLenses<'_> {
+    fn insert_virtual_lines(
+        &mut self,
+        line_end_char_idx: usize,
+        _line_end_visual_pos: Position,
+        doc_line: usize,
+    ) -> Position {
+        // This is also called at the end of softwrapped visual lines, only the end of the
+        // document line is relevant.
+        if doc_line + 1 >= self.text.len_lines()
+            || line_end_char_idx != self.text.line_to_char(doc_line + 1)
+        {
+            return Position::new(0, 0);
+        }
+        let start = self.rows.partition_point(|&(anchor, _)| anchor < doc_line);
+        let end = self.rows.partition_point(|&(anchor, _)| anchor <= doc_line);
+        Position::new(end - start, 0)
+    }
+}
@@ -0,0 +1,64 @@
+use helix_core::text_annotations::LineAnnotation;
+use helix_core::{Position, RopeSlice};
+
+use crate::{Document, ViewId};
+
+/// Returns the cursor and the text of the inline completion displayed in `view_id` which has
+/// not been typed yet, see [`crate::document::InlineCompletion::ghost_text`].
+pub fn inline_completion_ghost_text(doc: &Document, view_id: ViewId) -> Option<(usize, &str)> {
+    let inline_completion = doc
+        .inline_completion
+        .as_ref()
+        .filter(|inline_completion| inline_completion.view_id == view_id)?;
+    let text = doc.text().slice(..);
+    let cursor = doc.selections().get(&view_id)?.primary().cursor(text);
+    Some((cursor, inline_completion.ghost_text(text, cursor)?))
+}
+
+/// Reserves the virtual lines for an inline completion spanning multiple lines.
+///
+/// The first line of the completion is displayed after the cursor, the other lines are
+/// displayed in virtual lines below the line of the cursor.
+pub struct InlineCompletionLines<'a> {
+    text: RopeSlice<'a>,
+    line: usize,
+    height: usize,
+}
+
+impl<'a> InlineCompletionLines<'a> {
+    #[allow(clippy::new_ret_no_self)]
+    pub(crate) fn new(doc: &'a Document, view_id: ViewId) -> Option<Box<dyn LineAnnotation + 'a>> {
+        let (cursor, ghost_text) = inline_completion_ghost_text(doc, view_id)?;
+        let height = ghost_text.lines().count().saturating_sub(1);
+        if height == 0 {
+            return None;
+        }
+        let text = doc.text().slice(..);
+        Some(Box::new(InlineCompletionLines {
+            text,
+            line: text.char_to_line(cursor),
+            height,
+        }))
+    }
+}
+
+impl LineAnnotation for InlineCompletionLines<'_> {
+    fn insert_virtual_lines(
+        &mut self,
+        line_end_char_idx: usize,
+        _line_end_visual_pos: Position,
+        doc_line: usize,
+    ) -> Position {
+        // This is also called at the end of softwrapped visual lines, only the end of the
+        // document line is relevant.
+        let line_end = if doc_line + 1 < self.text.len_lines() {
+            self.text.line_to_char(doc_line + 1)
+        } else {
+            self.text.len_chars()
+        };
+        if doc_line != self.line || line_end_char_idx != line_end {
+            return Position::new(0, 0);
+        }
+        Position::new(self.height, 0)
+    }
+}
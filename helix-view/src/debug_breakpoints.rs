@@ -0,0 +1,144 @@
+use std::collections::BTreeMap;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use anyhow::Context;
+use serde::{Deserialize, Serialize};
+
+use crate::editor::{Breakpoint, DataBreakpoint, FunctionBreakpoint};
+use crate::Editor;
+
+/// The breakpoints of a workspace which are kept across sessions. Instruction breakpoints refer
+/// to addresses which change from one run to the next and aren't saved.
+#[derive(Debug, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SavedBreakpoints {
+    pub lines: BTreeMap<PathBuf, Vec<Breakpoint>>,
+    pub functions: Vec<FunctionBreakpoint>,
+    pub data: Vec<DataBreakpoint>,
+}
+
+impl SavedBreakpoints {
+    /// The file the breakpoints of `workspace` are saved in. Like Vim's undo files, it is named
+    /// after the path of the workspace with the separators replaced.
+    pub fn file(workspace: &Path) -> PathBuf {
+        let name: String = workspace
+            .to_string_lossy()
+            .chars()
+            .map(|c| {
+                if matches!(c, '/' | '\\' | ':') {
+                    '%'
+                } else {
+                    c
+                }
+            })
+            .collect();
+        helix_loader::cache_dir()
+            .join("breakpoints")
+            .join(format!("{name}.json"))
+    }
+
+    pub fn load(workspace: &Path) -> anyhow::Result<Self> {
+        let file = Self::file(workspace);
+        match std::fs::read(&file) {
+            Ok(contents) => serde_json::from_slice(&contents)
+                .with_context(|| format!("failed to parse {}", file.display())),
+            Err(err) if err.kind() == io::ErrorKind::NotFound => Ok(Self::default()),
+            Err(err) => Err(err).with_context(|| format!("failed to read {}", file.display())),
+        }
+    }
+
+    pub fn save(&self, workspace: &Path) -> anyhow::Result<()> {
+        let file = Self::file(workspace);
+        if self.is_empty() {
+            return match std::fs::remove_file(&file) {
+                Err(err) if err.kind() != io::ErrorKind::NotFound => Err(err.into()),
+                _ => Ok(()),
+            };
+        }
+        if let Some(dir) = file.parent() {
+            std::fs::create_dir_all(dir)?;
+        }
+        std::fs::write(&file, serde_json::to_vec(self)?)
+            .with_context(|| format!("failed to write {}", file.display()))
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.lines.values().all(Vec::is_empty) && self.functions.is_empty() && self.data.is_empty()
+    }
+}
+
+impl Editor {
+    /// Restores the breakpoints saved for `workspace`. The breakpoints are saved for this
+    /// workspace from then on.
+    pub fn load_breakpoints(&mut self, workspace: PathBuf) {
+        match SavedBreakpoints::load(&workspace) {
+            Ok(saved) => {
+                self.breakpoints.extend(saved.lines);
+                self.function_breakpoints.extend(saved.functions);
+                self.data_breakpoints.extend(saved.data);
+            }
+            Err(err) => log::error!("Failed to load the breakpoints: {err:#}"),
+        }
+        self.breakpoints_workspace = Some(workspace);
+    }
+
+    /// Saves the breakpoints for the workspace they were loaded from. Nothing is saved if no
+    /// breakpoints were loaded.
+    pub fn save_breakpoints(&self) {
+        let Some(workspace) = &self.breakpoints_workspace else {
+            return;
+        };
+        let saved = SavedBreakpoints {
+            lines: self
+                .breakpoints
+                .iter()
+                .filter(|(_, breakpoints)| !breakpoints.is_empty())
+                .map(|(path, breakpoints)| (path.clone(), breakpoints.clone()))
+                .collect(),
+            functions: self.function_breakpoints.clone(),
+            data: self
+                .data_breakpoints
+                .iter()
+                .filter(|breakpoint| breakpoint.can_persist)
+                .cloned()
+                .collect(),
+        };
+        if let Err(err) = saved.save(workspace) {
+            log::error!("Failed to save the breakpoints: {err:#}");
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn saved_state() {
+        let saved = SavedBreakpoints {
+            lines: BTreeMap::from([(
+                PathBuf::from("/src/main.rs"),
+                vec![Breakpoint {
+                    id: Some(1),
+                    verified: true,
+                    line: 4,
+                    condition: Some("x > 1".to_string()),
+                    ..Default::default()
+                }],
+            )]),
+            ..Default::default()
+        };
+        let json = serde_json::to_value(&saved).unwrap();
+        // The state reported by the debug adapter isn't saved.
+        assert_eq!(json["lines"]["/src/main.rs"][0].get("verified"), None);
+
+        let loaded: SavedBreakpoints = serde_json::from_value(json).unwrap();
+        let breakpoint = &loaded.lines[Path::new("/src/main.rs")][0];
+        assert_eq!(breakpoint.id, None);
+        assert!(!breakpoint.verified);
+        assert_eq!(breakpoint.line, 4);
+        assert_eq!(breakpoint.condition.as_deref(), Some("x > 1"));
+        assert!(loaded.functions.is_empty());
+    }
+}
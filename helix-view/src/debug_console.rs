@@ -0,0 +1,170 @@
+use std::collections::VecDeque;
+use std::path::PathBuf;
+
+use helix_dap::events::OutputBody;
+
+/// The maximum number of entries kept in the debug console. Older entries are dropped first.
+const MAX_ENTRIES: usize = 10_000;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ConsoleCategory {
+    /// Messages from the debug adapter itself.
+    Console,
+    /// Messages the debug adapter wants the user to notice.
+    Important,
+    Stdout,
+    Stderr,
+    /// An expression entered in the REPL.
+    Input,
+    /// The result of evaluating a REPL expression.
+    Result,
+    /// An error from evaluating a REPL expression.
+    Error,
+    Other(String),
+}
+
+impl ConsoleCategory {
+    fn from_output_category(category: Option<&str>) -> Self {
+        match category {
+            None | Some("console") => Self::Console,
+            Some("important") => Self::Important,
+            Some("stdout") => Self::Stdout,
+            Some("stderr") => Self::Stderr,
+            Some(category) => Self::Other(category.to_string()),
+        }
+    }
+}
+
+/// A location in a source file that produced a console entry. Lines and columns are 1-based.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ConsoleSource {
+    pub path: PathBuf,
+    pub line: usize,
+    pub column: Option<usize>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ConsoleEntry {
+    pub category: ConsoleCategory,
+    /// The text of the entry. It may span multiple lines and contain ANSI escape sequences.
+    pub text: String,
+    pub source: Option<ConsoleSource>,
+}
+
+/// The scrollback of the debug console: the output of the debugged program and the debug
+/// adapter along with the expressions evaluated in the REPL.
+#[derive(Debug, Default)]
+pub struct DebugConsole {
+    entries: VecDeque<ConsoleEntry>,
+}
+
+impl DebugConsole {
+    pub fn entries(&self) -> impl DoubleEndedIterator<Item = &ConsoleEntry> + ExactSizeIterator {
+        self.entries.iter()
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    pub fn clear(&mut self) {
+        self.entries.clear();
+    }
+
+    pub fn push(&mut self, category: ConsoleCategory, text: impl Into<String>) {
+        self.push_entry(ConsoleEntry {
+            category,
+            text: text.into(),
+            source: None,
+        });
+    }
+
+    /// Records the body of an `output` event. Telemetry is ignored.
+    pub fn push_output(&mut self, body: &OutputBody) {
+        if body.category.as_deref() == Some("telemetry") {
+            return;
+        }
+        let source = body
+            .source
+            .as_ref()
+            .and_then(|source| source.path.clone())
+            .zip(body.line)
+            .map(|(path, line)| ConsoleSource {
+                path,
+                line,
+                column: body.column,
+            });
+        let category = ConsoleCategory::from_output_category(body.category.as_deref());
+
+        // Programs often write their output in chunks which don't end with a newline. Continue
+        // the previous entry in that case so that a line isn't split across entries.
+        if let Some(last) = self.entries.back_mut() {
+            if source.is_none()
+                && last.source.is_none()
+                && last.category == category
+                && !last.text.ends_with('\n')
+            {
+                last.text.push_str(&body.output);
+                return;
+            }
+        }
+
+        self.push_entry(ConsoleEntry {
+            category,
+            text: body.output.clone(),
+            source,
+        });
+    }
+
+    fn push_entry(&mut self, entry: ConsoleEntry) {
+        if self.entries.len() == MAX_ENTRIES {
+            self.entries.pop_front();
+        }
+        self.entries.push_back(entry);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn output(category: &str, output: &str) -> OutputBody {
+        OutputBody {
+            output: output.to_string(),
+            category: Some(category.to_string()),
+            group: None,
+            line: None,
+            column: None,
+            variables_reference: None,
+            source: None,
+            data: None,
+        }
+    }
+
+    #[test]
+    fn output_chunks() {
+        let mut console = DebugConsole::default();
+        console.push_output(&output("stdout", "hello "));
+        console.push_output(&output("stdout", "world\n"));
+        console.push_output(&output("stderr", "oops\n"));
+        console.push_output(&output("telemetry", "{}"));
+        console.push_output(&output("stdout", "done\n"));
+
+        let entries: Vec<_> = console
+            .entries()
+            .map(|entry| (entry.category.clone(), entry.text.as_str()))
+            .collect();
+        assert_eq!(
+            entries,
+            vec![
+                (ConsoleCategory::Stdout, "hello world\n"),
+                (ConsoleCategory::Stderr, "oops\n"),
+                (ConsoleCategory::Stdout, "done\n"),
+            ]
+        );
+    }
+}
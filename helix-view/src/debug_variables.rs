@@ -0,0 +1,272 @@
+use std::collections::{HashMap, HashSet};
+
+use helix_dap::{requests::EvaluateResponse, Scope, Variable};
+
+/// The top level node a variable belongs to.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum NodeRoot {
+    Watch(String),
+    Scope(String),
+}
+
+/// Identifies a node of the variables tree. Variable references are only valid until the
+/// debuggee resumes, so nodes are identified by the names leading to them instead. This keeps
+/// the same variables expanded across stops.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct NodePath {
+    pub root: NodeRoot,
+    pub names: Vec<String>,
+}
+
+impl NodePath {
+    fn child(&self, name: &str) -> Self {
+        let mut names = self.names.clone();
+        names.push(name.to_string());
+        Self {
+            root: self.root.clone(),
+            names,
+        }
+    }
+}
+
+/// An expression evaluated every time the debuggee stops.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Watch {
+    pub expression: String,
+    /// The result of the last evaluation or the error message of the debug adapter.
+    pub value: Option<Result<EvaluateResponse, String>>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NodeKind {
+    /// The watch at the given index.
+    Watch(usize),
+    Scope,
+    /// A variable of the container with the given `variablesReference`.
+    Variable {
+        container: usize,
+    },
+}
+
+/// A visible node of the variables tree.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct VariableRow<'a> {
+    pub path: NodePath,
+    pub kind: NodeKind,
+    pub depth: usize,
+    pub name: &'a str,
+    /// The value of the node, or the error of a watch that failed to evaluate.
+    pub value: Option<Result<&'a str, &'a str>>,
+    pub ty: Option<&'a str>,
+    /// The expression that evaluates to the variable, used to edit its value.
+    pub evaluate_name: Option<&'a str>,
+    /// The reference of the children of the node, zero if it has none.
+    pub variables_reference: usize,
+    pub expanded: bool,
+}
+
+/// The scopes of the active stack frame with the expanded variables and the watch expressions.
+#[derive(Debug, Default)]
+pub struct DebugVariables {
+    pub watches: Vec<Watch>,
+    pub scopes: Vec<Scope>,
+    /// The children of the expanded nodes by `variablesReference`.
+    pub children: HashMap<usize, Vec<Variable>>,
+    pub expanded: HashSet<NodePath>,
+}
+
+impl DebugVariables {
+    /// Forgets the values of the previous stop.
+    pub fn clear_values(&mut self) {
+        self.scopes.clear();
+        self.children.clear();
+        for watch in &mut self.watches {
+            watch.value = None;
+        }
+    }
+
+    pub fn toggle(&mut self, path: &NodePath) {
+        if !self.expanded.remove(path) {
+            self.expanded.insert(path.clone());
+        }
+    }
+
+    /// The visible rows: the watches followed by the scopes, each followed by their expanded
+    /// children.
+    pub fn rows(&self) -> Vec<VariableRow<'_>> {
+        let mut rows = Vec::new();
+        for (i, watch) in self.watches.iter().enumerate() {
+            let path = NodePath {
+                root: NodeRoot::Watch(watch.expression.clone()),
+                names: Vec::new(),
+            };
+            let (value, ty, variables_reference) = match &watch.value {
+                Some(Ok(response)) => (
+                    Some(Ok(response.result.as_str())),
+                    response.ty.as_deref(),
+                    response.variables_reference,
+                ),
+                Some(Err(err)) => (Some(Err(err.as_str())), None, 0),
+                None => (None, None, 0),
+            };
+            self.push_row(
+                &mut rows,
+                VariableRow {
+                    expanded: self.expanded.contains(&path),
+                    path,
+                    kind: NodeKind::Watch(i),
+                    depth: 0,
+                    name: &watch.expression,
+                    value,
+                    ty,
+                    evaluate_name: Some(&watch.expression),
+                    variables_reference,
+                },
+            );
+        }
+        for scope in &self.scopes {
+            let path = NodePath {
+                root: NodeRoot::Scope(scope.name.clone()),
+                names: Vec::new(),
+            };
+            self.push_row(
+                &mut rows,
+                VariableRow {
+                    expanded: self.expanded.contains(&path),
+                    path,
+                    kind: NodeKind::Scope,
+                    depth: 0,
+                    name: &scope.name,
+                    value: None,
+                    ty: None,
+                    evaluate_name: None,
+                    variables_reference: scope.variables_reference,
+                },
+            );
+        }
+        rows
+    }
+
+    fn push_row<'a>(&'a self, rows: &mut Vec<VariableRow<'a>>, row: VariableRow<'a>) {
+        let children = match self.children.get(&row.variables_reference) {
+            Some(children) if row.expanded && row.variables_reference != 0 => children,
+            _ => {
+                rows.push(row);
+                return;
+            }
+        };
+        let container = row.variables_reference;
+        let depth = row.depth + 1;
+        let parent = row.path.clone();
+        rows.push(row);
+        for variable in children {
+            let path = parent.child(&variable.name);
+            self.push_row(
+                rows,
+                VariableRow {
+                    expanded: self.expanded.contains(&path),
+                    path,
+                    kind: NodeKind::Variable { container },
+                    depth,
+                    name: &variable.name,
+                    value: Some(Ok(&variable.value)),
+                    ty: variable.ty.as_deref(),
+                    evaluate_name: variable.evaluate_name.as_deref(),
+                    variables_reference: variable.variables_reference,
+                },
+            );
+        }
+    }
+
+    /// The references of the expanded nodes whose children haven't been loaded yet.
+    pub fn missing_children(&self) -> Vec<usize> {
+        self.rows()
+            .into_iter()
+            .filter(|row| {
+                row.expanded
+                    && row.variables_reference != 0
+                    && !self.children.contains_key(&row.variables_reference)
+            })
+            .map(|row| row.variables_reference)
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn scope(name: &str, variables_reference: usize) -> Scope {
+        Scope {
+            name: name.to_string(),
+            presentation_hint: None,
+            variables_reference,
+            named_variables: None,
+            indexed_variables: None,
+            expensive: false,
+            source: None,
+            line: None,
+            column: None,
+            end_line: None,
+            end_column: None,
+        }
+    }
+
+    fn variable(name: &str, variables_reference: usize) -> Variable {
+        Variable {
+            name: name.to_string(),
+            value: format!("{name} value"),
+            ty: None,
+            presentation_hint: None,
+            evaluate_name: None,
+            variables_reference,
+            named_variables: None,
+            indexed_variables: None,
+            memory_reference: None,
+        }
+    }
+
+    fn names(variables: &DebugVariables) -> Vec<(usize, &str)> {
+        variables
+            .rows()
+            .into_iter()
+            .map(|row| (row.depth, row.name))
+            .collect()
+    }
+
+    #[test]
+    fn expand_variables() {
+        let mut variables = DebugVariables {
+            scopes: vec![scope("Locals", 1), scope("Globals", 2)],
+            ..Default::default()
+        };
+        assert_eq!(names(&variables), vec![(0, "Locals"), (0, "Globals")]);
+
+        let locals = variables.rows()[0].path.clone();
+        variables.toggle(&locals);
+        assert_eq!(variables.missing_children(), vec![1]);
+        variables
+            .children
+            .insert(1, vec![variable("point", 3), variable("count", 0)]);
+        assert_eq!(
+            names(&variables),
+            vec![(0, "Locals"), (1, "point"), (1, "count"), (0, "Globals")]
+        );
+
+        let point = variables.rows()[1].path.clone();
+        variables.toggle(&point);
+        assert_eq!(variables.missing_children(), vec![3]);
+        variables.children.insert(3, vec![variable("x", 0)]);
+        assert_eq!(
+            variables.rows()[2].kind,
+            NodeKind::Variable { container: 3 }
+        );
+
+        // The expanded nodes are kept across stops even though the references change.
+        variables.clear_values();
+        variables.scopes = vec![scope("Locals", 7)];
+        assert_eq!(variables.missing_children(), vec![7]);
+        variables.children.insert(7, vec![variable("point", 8)]);
+        assert_eq!(variables.missing_children(), vec![8]);
+    }
+}
@@ -28,6 +28,7 @@
 use std::fmt::Display;
 use std::future::Future;
 use std::io;
+use std::ops;
 use std::path::{Path, PathBuf};
 use std::str::FromStr;
 use std::sync::{Arc, Weak};
@@ -40,7 +41,8 @@
     indent::{auto_detect_indent_style, IndentStyle},
     line_ending::auto_detect_line_ending,
     syntax::{self, config::LanguageConfiguration},
-    ChangeSet, Diagnostic, LineEnding, Range, Rope, RopeBuilder, Selection, Syntax, Transaction,
+    ChangeSet, Diagnostic, LineEnding, Range, Rope, RopeBuilder, RopeSlice, Selection, Syntax,
+    Transaction,
 };
 
 use crate::{
@@ -156,6 +158,9 @@ pub struct Document {
 
     path: Option<PathBuf>,
     relative_path: OnceCell<Option<PathBuf>>,
+    /// The URL of a document without a path whose contents were provided by a language server,
+    /// see [`Document::open_virtual`].
+    virtual_url: Option<Url>,
     encoding: &'static encoding::Encoding,
     has_bom: bool,
 
@@ -213,6 +218,30 @@ pub struct Document {
     pub color_swatch_controller: TaskController,
     pub pull_diagnostic_controller: TaskController,
 
+    /// LSP semantic tokens, layered over the tree-sitter highlights when rendering
+    pub semantic_tokens: Option<DocumentSemanticTokens>,
+    pub semantic_tokens_controller: TaskController,
+
+    /// LSP code lenses, sorted by position
+    pub code_lenses: Vec<DocumentCodeLens>,
+    pub code_lens_controller: TaskController,
+
+    /// Ranges of lines that can be folded, sorted by position. Provided by a language
+    /// server or the tree-sitter `folds.scm` queries.
+    pub folding_ranges: Vec<FoldingRange>,
+    pub folding_ranges_controller: TaskController,
+
+    /// Ranges edited in sync while in insert mode, see `textDocument/linkedEditingRange`.
+    pub linked_editing_ranges: Option<LinkedEditingRanges>,
+
+    /// LSP document links, sorted by position
+    pub document_links: Vec<DocumentLink>,
+    pub document_links_controller: TaskController,
+
+    /// Suggestion displayed as virtual text after the cursor while in insert mode, see
+    /// `textDocument/inlineCompletion`.
+    pub inline_completion: Option<InlineCompletion>,
+
     // NOTE: this field should eventually go away - we should use the Editor's syn_loader instead
     // of storing a copy on every doc. Then we can remove the surrounding `Arc` and use the
     // `ArcSwap` directly.
@@ -226,6 +255,144 @@ pub struct DocumentColorSwatches {
     pub color_swatches_padding: Vec<InlineAnnotation>,
 }
 
+/// Semantic tokens provided by a single language server for a document.
+#[derive(Debug, Clone)]
+pub struct DocumentSemanticTokens {
+    /// The language server which provided the tokens.
+    pub language_server_id: LanguageServerId,
+    /// The `resultId` of the last response, used to request deltas.
+    pub result_id: Option<String>,
+    /// The tokens as last sent by the server, kept around to apply deltas to.
+    pub data: Vec<lsp::SemanticToken>,
+    /// The decoded tokens, sorted by position and non-overlapping.
+    pub tokens: Vec<SemanticToken>,
+    /// Theme scopes for the token types in the server's legend, e.g. `lsp.type.variable`.
+    pub token_types: Vec<String>,
+    /// Theme scopes for the token modifiers in the server's legend, e.g. `lsp.mod.readonly`.
+    pub token_modifiers: Vec<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SemanticToken {
+    /// Char range of the token in the document.
+    pub range: std::ops::Range<usize>,
+    /// Index into [`DocumentSemanticTokens::token_types`].
+    pub token_type: u32,
+    /// Bitset of indices into [`DocumentSemanticTokens::token_modifiers`].
+    pub token_modifiers: u32,
+}
+
+/// A code lens provided by a language server.
+#[derive(Debug, Clone)]
+pub struct DocumentCodeLens {
+    /// The start of the lens' range, kept up to date with changes to the document.
+    pub char_idx: usize,
+    pub lens: lsp::CodeLens,
+    pub language_server_id: LanguageServerId,
+    /// Whether `codeLens/resolve` was sent for the lens. Lenses without a command are only
+    /// resolved once they are visible.
+    pub resolve_requested: bool,
+}
+
+/// A link provided by a language server.
+#[derive(Debug, Clone)]
+pub struct DocumentLink {
+    /// Char range of the link, kept up to date with changes to the document.
+    pub range: ops::Range<usize>,
+    pub link: lsp::DocumentLink,
+    pub language_server_id: LanguageServerId,
+}
+
+/// A range of lines that can be folded.
+///
+/// The positions are kept up to date with changes to the document, the lines are computed
+/// on demand.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FoldingRange {
+    /// The start of the first line of the range. This line stays visible when folded.
+    pub start: usize,
+    /// The start of the last line of the range.
+    pub end: usize,
+}
+
+impl FoldingRange {
+    /// Creates a folding range for `start_line..=end_line`.
+    pub fn from_lines(text: RopeSlice, start_line: usize, end_line: usize) -> Self {
+        Self {
+            start: text.line_to_char(start_line),
+            end: text.line_to_char(end_line),
+        }
+    }
+
+    /// Returns the first and last line of the range.
+    pub fn lines(&self, text: RopeSlice) -> (usize, usize) {
+        (
+            text.char_to_line(self.start.min(text.len_chars())),
+            text.char_to_line(self.end.min(text.len_chars())),
+        )
+    }
+}
+
+/// Inline completions provided by a language server, of which one is displayed at a time.
+#[derive(Debug, Clone)]
+pub struct InlineCompletion {
+    /// The view the completions are displayed in.
+    pub view_id: ViewId,
+    pub items: Vec<InlineCompletionItem>,
+    /// The index of the displayed item.
+    pub index: usize,
+    pub language_server_id: LanguageServerId,
+}
+
+#[derive(Debug, Clone)]
+pub struct InlineCompletionItem {
+    /// Char range replaced by the item, kept up to date with changes to the document.
+    pub range: ops::Range<usize>,
+    pub text: String,
+    /// Command executed after the item was accepted.
+    pub command: Option<lsp::Command>,
+}
+
+impl InlineCompletion {
+    pub fn item(&self) -> Option<&InlineCompletionItem> {
+        self.items.get(self.index)
+    }
+
+    /// Returns the part of the displayed item which has not been typed yet, see
+    /// [`InlineCompletionItem::ghost_text`].
+    pub fn ghost_text(&self, text: RopeSlice, cursor: usize) -> Option<&str> {
+        self.item()?.ghost_text(text, cursor)
+    }
+}
+
+impl InlineCompletionItem {
+    /// Returns the part of the item which has not been typed yet.
+    ///
+    /// The item is only displayed while the cursor is inside of its range and the text
+    /// typed so far is a prefix of the item.
+    pub fn ghost_text(&self, text: RopeSlice, cursor: usize) -> Option<&str> {
+        if cursor < self.range.start || self.range.end < cursor || text.len_chars() < cursor {
+            return None;
+        }
+        let typed = Cow::from(text.slice(self.range.start..cursor));
+        self.text
+            .strip_prefix(&*typed)
+            .filter(|ghost_text| !ghost_text.is_empty())
+    }
+}
+
+/// Ranges of a document which have the same content and are edited together, like the opening
+/// and closing tags of an HTML element.
+#[derive(Debug, Clone)]
+pub struct LinkedEditingRanges {
+    /// The view the ranges are edited in.
+    pub view_id: ViewId,
+    /// Char ranges of the document, sorted and non-overlapping.
+    pub ranges: Vec<ops::Range<usize>>,
+    /// A pattern the content of the ranges has to match, the linking stops otherwise.
+    pub word_pattern: Option<helix_core::regex::Regex>,
+}
+
 /// Inlay hints for a single `(Document, View)` combo.
 ///
 /// There are `*_inlay_hints` field for each kind of hints an LSP can send since we offer the
@@ -699,6 +866,7 @@ pub fn from(
             active_snippet: None,
             path: None,
             relative_path: OnceCell::new(),
+            virtual_url: None,
             encoding,
             has_bom,
             text,
@@ -733,6 +901,16 @@ pub fn from(
             syn_loader,
             previous_diagnostic_id: None,
             pull_diagnostic_controller: TaskController::new(),
+            semantic_tokens: None,
+            semantic_tokens_controller: TaskController::new(),
+            code_lenses: Vec::new(),
+            code_lens_controller: TaskController::new(),
+            folding_ranges: Vec::new(),
+            folding_ranges_controller: TaskController::new(),
+            linked_editing_ranges: None,
+            document_links: Vec::new(),
+            document_links_controller: TaskController::new(),
+            inline_completion: None,
         }
     }
 
@@ -800,13 +978,103 @@ pub fn auto_format(
         &self,
         editor: &Editor,
     ) -> Option<BoxFuture<'static, Result<Transaction, FormatterError>>> {
-        if self.language_config()?.auto_format {
-            self.format(editor)
-        } else {
+        if !self.language_config()?.auto_format {
             None
+        } else if editor.config().auto_format_modified_lines {
+            self.format_modified_lines(editor)
+        } else {
+            self.format(editor)
         }
     }
 
+    /// Like [`format`], but only formats the lines changed against the diff base with range
+    /// formatting requests. Falls back to [`format`] when a formatter command is configured or
+    /// no language server supports range formatting.
+    pub fn format_modified_lines(
+        &self,
+        editor: &Editor,
+    ) -> Option<BoxFuture<'static, Result<Transaction, FormatterError>>> {
+        let has_formatter_command = self
+            .language_config()
+            .and_then(|config| config.formatter.as_ref())
+            .is_some();
+        let language_server = self
+            .language_servers_with_feature(LanguageServerFeature::Format)
+            .next();
+        let (Some(language_server), Some(diff_handle), false) =
+            (language_server, &self.diff_handle, has_formatter_command)
+        else {
+            return self.format(editor);
+        };
+        if !matches!(
+            language_server
+                .capabilities()
+                .document_range_formatting_provider,
+            Some(lsp::OneOf::Left(true) | lsp::OneOf::Right(_))
+        ) {
+            return self.format(editor);
+        }
+
+        let text = self.text.clone();
+        let offset_encoding = language_server.offset_encoding();
+        let diff = diff_handle.load();
+        let requests: Vec<_> = (0..diff.len())
+            .map(|n| diff.nth_hunk(n).after)
+            // Nothing to format for removed lines.
+            .filter(|lines| !lines.is_empty())
+            .filter_map(|lines| {
+                let line_start = |line: u32| {
+                    let line = line as usize;
+                    if line < text.len_lines() {
+                        text.line_to_char(line)
+                    } else {
+                        text.len_chars()
+                    }
+                };
+                let range = lsp::Range::new(
+                    helix_lsp::util::pos_to_lsp_pos(
+                        &text,
+                        line_start(lines.start),
+                        offset_encoding,
+                    ),
+                    helix_lsp::util::pos_to_lsp_pos(&text, line_start(lines.end), offset_encoding),
+                );
+                language_server.text_document_range_formatting(
+                    self.identifier(),
+                    range,
+                    lsp::FormattingOptions {
+                        tab_size: self.tab_width() as u32,
+                        insert_spaces: matches!(self.indent_style, IndentStyle::Spaces(_)),
+                        ..Default::default()
+                    },
+                    None,
+                )
+            })
+            .collect();
+        if requests.is_empty() {
+            return None;
+        }
+
+        let fut = async move {
+            let edits = futures_util::future::join_all(requests)
+                .await
+                .into_iter()
+                .filter_map(|res| {
+                    res.unwrap_or_else(|e| {
+                        log::warn!("LSP range formatting failed: {}", e);
+                        None
+                    })
+                })
+                .collect();
+            Ok(helix_lsp::util::generate_transaction_from_edits(
+                &text,
+                merge_range_formatting_edits(edits),
+                offset_encoding,
+            ))
+        };
+        Some(fut.boxed())
+    }
+
     /// If supported, returns the changes that should be applied to this document in order
     /// to format it nicely.
     // We can't use anyhow::Result here since the output of the future has to be
@@ -1139,6 +1407,30 @@ impl Future<Output = Result<DocumentSavedEvent, anyhow::Error>> + 'static + Send
         Ok(future)
     }
 
+    /// Creates a read-only document for a URL that doesn't point to a file on disk, e.g.
+    /// `jdt://...`, with `text` read from a language server.
+    ///
+    /// The language is detected from the URL's path and falls back to `language`.
+    pub fn open_virtual(
+        url: Url,
+        text: Rope,
+        language: Option<Arc<syntax::config::LanguageConfiguration>>,
+        config: Arc<dyn DynAccess<Config>>,
+        syn_loader: Arc<ArcSwap<syntax::Loader>>,
+    ) -> Self {
+        let loader = syn_loader.load_full();
+        let mut doc = Self::from(text, None, config, syn_loader.clone());
+        let language = loader
+            .language_for_filename(Path::new(url.path()))
+            .map(|language| loader.language(language).config().clone())
+            .or(language);
+        doc.virtual_url = Some(url);
+        doc.readonly = true;
+        doc.set_language(language, &loader);
+        doc.detect_indent_and_line_ending();
+        doc
+    }
+
     /// Detect the programming language based on the file type.
     pub fn detect_language(&mut self, loader: &syntax::Loader) {
         self.set_language(self.detect_language_config(loader), loader);
@@ -1421,6 +1713,16 @@ fn apply_impl(
             view_data.view_position.anchor = transaction
                 .changes()
                 .map_pos(view_data.view_position.anchor, Assoc::Before);
+            if !view_data.folds.is_empty() {
+                // text inserted at the edges of a fold stays visible
+                changes.update_positions(view_data.folds.iter_mut().flat_map(|fold| {
+                    [
+                        (&mut fold.start, Assoc::After),
+                        (&mut fold.end, Assoc::Before),
+                    ]
+                }));
+                normalize_folds(self.text.slice(..), &mut view_data.folds);
+            }
         }
 
         // generate revert to savepoint
@@ -1943,13 +2245,26 @@ pub fn path(&self) -> Option<&PathBuf> {
         self.path.as_ref()
     }
 
-    /// File path as a URL.
+    /// File path as a URL, or the URL of a virtual document.
     pub fn url(&self) -> Option<Url> {
-        Url::from_file_path(self.path()?).ok()
+        match self.path() {
+            Some(path) => Url::from_file_path(path).ok(),
+            None => self.virtual_url.clone(),
+        }
     }
 
     pub fn uri(&self) -> Option<helix_core::Uri> {
-        Some(self.path()?.clone().into())
+        match self.path() {
+            Some(path) => Some(path.clone().into()),
+            None => Some(helix_core::Uri::Virtual(Arc::new(
+                self.virtual_url.clone()?,
+            ))),
+        }
+    }
+
+    /// Whether the document was provided by a language server, see [`Document::open_virtual`].
+    pub fn is_virtual(&self) -> bool {
+        self.path.is_none() && self.virtual_url.is_some()
     }
 
     #[inline]
@@ -1989,6 +2304,60 @@ pub fn set_view_offset(&mut self, view_id: ViewId, new_offset: ViewPosition) {
         self.view_data_mut(view_id).view_position = new_offset;
     }
 
+    /// The folded text of the given view, sorted and non-overlapping.
+    pub fn folds(&self, view_id: ViewId) -> &[ops::Range<usize>] {
+        self.view_data
+            .get(&view_id)
+            .map_or(&[], |view_data| &view_data.folds)
+    }
+
+    /// Folds `start_line + 1..=end_line` into `start_line` in the given view.
+    ///
+    /// Folds within the range are replaced by the new fold, folds that partially overlap
+    /// it are unfolded.
+    pub fn fold(&mut self, view_id: ViewId, start_line: usize, end_line: usize) {
+        let text = self.text.slice(..);
+        let end_line = end_line.min(text.len_lines() - 1);
+        if start_line >= end_line {
+            return;
+        }
+        let fold = text.line_to_char(start_line + 1)..text.line_to_char(end_line + 1);
+        let folds = &mut self.view_data_mut(view_id).folds;
+        folds.retain(|other| other.end <= fold.start || fold.end <= other.start);
+        let idx = folds.partition_point(|other| other.start < fold.start);
+        folds.insert(idx, fold);
+    }
+
+    /// Unfolds the fold collapsed into or hiding `line` in the given view.
+    ///
+    /// Returns whether there was such a fold.
+    pub fn unfold(&mut self, view_id: ViewId, line: usize) -> bool {
+        let text = self.text.slice(..);
+        let start = text.line_to_char(line);
+        let next_line = text.line_to_char((line + 1).min(text.len_lines()));
+        let folds = &mut self.view_data_mut(view_id).folds;
+        let len = folds.len();
+        // folds start at a line start so a fold collapsed into `line` starts at `next_line`
+        folds.retain(|fold| !(fold.start <= start && start < fold.end) && fold.start != next_line);
+        folds.len() != len
+    }
+
+    /// Removes all folds of the given view.
+    pub fn unfold_all(&mut self, view_id: ViewId) {
+        self.view_data_mut(view_id).folds.clear();
+    }
+
+    /// Returns the document link at `char_idx`, if any.
+    pub fn document_link_at(&self, char_idx: usize) -> Option<&DocumentLink> {
+        let idx = self
+            .document_links
+            .partition_point(|link| link.range.start <= char_idx);
+        self.document_links[..idx]
+            .iter()
+            .rev()
+            .find(|link| char_idx < link.range.end)
+    }
+
     pub fn relative_path(&self) -> Option<&Path> {
         self.relative_path
             .get_or_init(|| {
@@ -2000,8 +2369,11 @@ pub fn relative_path(&self) -> Option<&Path> {
     }
 
     pub fn display_name(&self) -> Cow<'_, str> {
-        self.relative_path()
-            .map_or_else(|| SCRATCH_BUFFER_NAME.into(), |path| path.to_string_lossy())
+        match (self.relative_path(), &self.virtual_url) {
+            (Some(path), _) => path.to_string_lossy(),
+            (None, Some(url)) => url.as_str().into(),
+            (None, None) => SCRATCH_BUFFER_NAME.into(),
+        }
     }
 
     // transact(Fn) ?
@@ -2298,6 +2670,38 @@ pub fn has_language_server_with_feature(&self, feature: LanguageServerFeature) -
 #[derive(Debug, Default)]
 pub struct ViewData {
     view_position: ViewPosition,
+    /// The folded (hidden) text, see [`TextAnnotations::set_folds`].
+    ///
+    /// [`TextAnnotations::set_folds`]: helix_core::text_annotations::TextAnnotations::set_folds
+    folds: Vec<ops::Range<usize>>,
+}
+
+/// Moves `pos` to the start of the next line unless it already is at the start of a line.
+fn next_line_start(text: RopeSlice, pos: usize) -> usize {
+    let line = text.char_to_line(pos);
+    if text.line_to_char(line) == pos {
+        pos
+    } else {
+        text.line_to_char(line + 1)
+    }
+}
+
+/// Aligns the folds with line boundaries after they were mapped through changes and
+/// drops folds that became empty or overlap the previous fold.
+fn normalize_folds(text: RopeSlice, folds: &mut Vec<ops::Range<usize>>) {
+    for fold in folds.iter_mut() {
+        fold.start = next_line_start(text, fold.start);
+        fold.end = next_line_start(text, fold.end);
+    }
+    folds.sort_by_key(|fold| fold.start);
+    let mut prev_end = 0;
+    folds.retain(|fold| {
+        let keep = fold.start > 0 && fold.start < fold.end && fold.start >= prev_end;
+        if keep {
+            prev_end = fold.end;
+        }
+        keep
+    });
 }
 
 #[derive(Clone, Debug)]
@@ -2331,12 +2735,56 @@ fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
     }
 }
 
+/// Merges the edits of several range formatting responses for the same document version into
+/// one list of edits. Edits overlapping an earlier edit, such as edits returned for more than one
+/// of the ranges, are dropped.
+fn merge_range_formatting_edits(responses: Vec<Vec<lsp::TextEdit>>) -> Vec<lsp::TextEdit> {
+    let mut edits: Vec<_> = responses.into_iter().flatten().collect();
+    // The sort is stable so the order of insertions at the same position is kept.
+    edits.sort_by_key(|edit| edit.range.start);
+    let mut merged: Vec<lsp::TextEdit> = Vec::with_capacity(edits.len());
+    for edit in edits {
+        if let Some(prev) = merged.last() {
+            if edit == *prev || edit.range.start < prev.range.end {
+                continue;
+            }
+        }
+        merged.push(edit);
+    }
+    merged
+}
+
 #[cfg(test)]
 mod test {
     use arc_swap::ArcSwap;
 
     use super::*;
 
+    #[test]
+    fn merge_range_formatting_responses() {
+        let edit = |start: (u32, u32), end: (u32, u32), new_text: &str| lsp::TextEdit {
+            range: lsp::Range::new(
+                lsp::Position::new(start.0, start.1),
+                lsp::Position::new(end.0, end.1),
+            ),
+            new_text: new_text.to_string(),
+        };
+        let merged = merge_range_formatting_edits(vec![
+            vec![edit((5, 0), (5, 4), "  "), edit((1, 0), (1, 2), "")],
+            // A duplicate insertion and an edit overlapping the first response.
+            vec![edit((8, 0), (8, 0), "\n"), edit((5, 2), (6, 0), "")],
+            vec![edit((8, 0), (8, 0), "\n")],
+        ]);
+        assert_eq!(
+            merged,
+            vec![
+                edit((1, 0), (1, 2), ""),
+                edit((5, 0), (5, 4), "  "),
+                edit((8, 0), (8, 0), "\n"),
+            ]
+        );
+    }
+
     #[test]
     fn changeset_to_changes_ignore_line_endings() {
         use helix_lsp::{lsp, Client, OffsetEncoding};
@@ -2376,6 +2824,27 @@ fn changeset_to_changes_ignore_line_endings() {
         );
     }
 
+    #[test]
+    fn virtual_document() {
+        let url = Url::parse("jdt://contents/rt.jar/java.lang/String.class").unwrap();
+        let doc = Document::open_virtual(
+            url.clone(),
+            Rope::from("class String {}"),
+            None,
+            Arc::new(ArcSwap::new(Arc::new(Config::default()))),
+            Arc::new(ArcSwap::from_pointee(syntax::Loader::default())),
+        );
+        assert!(doc.is_virtual());
+        assert!(doc.readonly);
+        assert_eq!(doc.path(), None);
+        assert_eq!(doc.url(), Some(url.clone()));
+        assert_eq!(doc.uri(), Some(helix_core::Uri::Virtual(Arc::new(url))));
+        assert_eq!(
+            doc.display_name(),
+            "jdt://contents/rt.jar/java.lang/String.class"
+        );
+    }
+
     #[test]
     fn changeset_to_changes() {
         use helix_lsp::{lsp, Client, OffsetEncoding};
@@ -2508,6 +2977,38 @@ fn test_line_ending() {
         );
     }
 
+    #[test]
+    fn inline_completion_ghost_text() {
+        let text = Rope::from("let x = fo\n");
+        let text = text.slice(..);
+        let item = InlineCompletionItem {
+            range: 8..10,
+            text: "foo(bar)".to_string(),
+            command: None,
+        };
+
+        assert_eq!(item.ghost_text(text, 10), Some("o(bar)"));
+        assert_eq!(item.ghost_text(text, 9), Some("oo(bar)"));
+        assert_eq!(item.ghost_text(text, 8), Some("foo(bar)"));
+        // The cursor left the range.
+        assert_eq!(item.ghost_text(text, 7), None);
+        assert_eq!(item.ghost_text(text, 11), None);
+
+        // The typed text doesn't match the completion anymore.
+        let item = InlineCompletionItem {
+            text: "bar".to_string(),
+            ..item
+        };
+        assert_eq!(item.ghost_text(text, 10), None);
+
+        // The completion was typed out entirely.
+        let item = InlineCompletionItem {
+            text: "fo".to_string(),
+            ..item
+        };
+        assert_eq!(item.ghost_text(text, 10), None);
+    }
+
     macro_rules! decode {
         ($name:ident, $label:expr, $label_override:expr) => {
             #[test]
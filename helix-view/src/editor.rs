@@ -1,12 +1,14 @@
 use crate::{
     annotations::diagnostics::{DiagnosticFilter, InlineDiagnosticsConfig},
     clipboard::ClipboardProvider,
+    debug_console::DebugConsole,
+    debug_variables::DebugVariables,
     document::{
         DocumentOpenError, DocumentSavedEventFuture, DocumentSavedEventResult, Mode, SavePoint,
     },
     events::{DocumentDidClose, DocumentDidOpen, DocumentFocusLost},
     graphics::{CursorKind, Rect},
-    handlers::Handlers,
+    handlers::{lsp::WorkspaceDiagnostics, Handlers},
     info::Info,
     input::KeyEvent,
     register::Registers,
@@ -18,7 +20,10 @@
 use helix_vcs::DiffProviderRegistry;
 
 use futures_util::stream::select_all::SelectAll;
-use futures_util::{future, StreamExt};
+use futures_util::{
+    future::{self, BoxFuture},
+    StreamExt,
+};
 use helix_lsp::{Call, LanguageServerId};
 use tokio_stream::wrappers::UnboundedReceiverStream;
 
@@ -27,6 +32,7 @@
     cell::Cell,
     collections::{BTreeMap, HashMap, HashSet},
     fs,
+    future::Future,
     io::{self, stdin},
     num::{NonZeroU8, NonZeroUsize},
     path::{Path, PathBuf},
@@ -327,6 +333,9 @@ pub struct Config {
     pub word_completion: WordCompletion,
     /// Automatic formatting on save. Defaults to true.
     pub auto_format: bool,
+    /// Only format the lines changed against the diff base when formatting on save.
+    /// Defaults to false.
+    pub auto_format_modified_lines: bool,
     /// Default register used for yank/paste. Defaults to '"'
     pub default_yank_register: char,
     /// Automatic save on focus lost and/or after delay.
@@ -527,10 +536,23 @@ pub struct LspConfig {
     pub inlay_hints_length_limit: Option<NonZeroU8>,
     /// Display document color swatches
     pub display_color_swatches: bool,
+    /// Highlight the document with semantic tokens from the language server
+    pub display_semantic_tokens: bool,
+    /// Display code lenses above the lines they belong to
+    pub display_code_lenses: bool,
     /// Whether to enable snippet support
     pub snippets: bool,
     /// Whether to include declaration in the goto reference query
     pub goto_reference_include_declaration: bool,
+    /// Preview workspace edits (such as renames) before applying them. Edits are always
+    /// previewed when the language server asks for confirmation.
+    pub preview_workspace_edits: bool,
+    /// Restart language servers that exit unexpectedly, waiting longer after each crash
+    pub auto_restart: bool,
+    /// Stop restarting a language server once it crashed this many times within `restart_window`
+    pub max_restarts: usize,
+    /// The time window in seconds in which crashes count towards `max_restarts`
+    pub restart_window: u64,
 }
 
 impl Default for LspConfig {
@@ -546,6 +568,12 @@ fn default() -> Self {
             snippets: true,
             goto_reference_include_declaration: true,
             display_color_swatches: true,
+            display_semantic_tokens: true,
+            display_code_lenses: false,
+            preview_workspace_edits: false,
+            auto_restart: true,
+            max_restarts: 5,
+            restart_window: 180,
         }
     }
 }
@@ -791,6 +819,8 @@ pub enum GutterType {
     Spacer,
     /// Highlight local changes
     Diff,
+    /// Show fold markers
+    Folds,
 }
 
 impl std::str::FromStr for GutterType {
@@ -802,8 +832,9 @@ fn from_str(s: &str) -> Result<Self, Self::Err> {
             "spacer" => Ok(Self::Spacer),
             "line-numbers" => Ok(Self::LineNumbers),
             "diff" => Ok(Self::Diff),
+            "folds" => Ok(Self::Folds),
             _ => anyhow::bail!(
-                "Gutter type can only be `diagnostics`, `spacer`, `line-numbers` or `diff`."
+                "Gutter type can only be `diagnostics`, `spacer`, `line-numbers`, `diff` or `folds`."
             ),
         }
     }
@@ -1074,6 +1105,7 @@ fn default() -> Self {
             path_completion: true,
             word_completion: WordCompletion::default(),
             auto_format: true,
+            auto_format_modified_lines: false,
             default_yank_register: '"',
             auto_save: AutoSave::default(),
             idle_timeout: Duration::from_millis(250),
@@ -1131,10 +1163,18 @@ fn default() -> Self {
     }
 }
 
-#[derive(Debug, Clone, Default)]
+/// A breakpoint on a source line. Lines are 0-based.
+///
+/// Breakpoints are saved per workspace, see [crate::debug_breakpoints]. The state reported by
+/// the debug adapter isn't saved.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
 pub struct Breakpoint {
+    #[serde(skip)]
     pub id: Option<usize>,
+    #[serde(skip)]
     pub verified: bool,
+    #[serde(skip)]
     pub message: Option<String>,
 
     pub line: usize,
@@ -1142,6 +1182,68 @@ pub struct Breakpoint {
     pub condition: Option<String>,
     pub hit_condition: Option<String>,
     pub log_message: Option<String>,
+    /// Disabled breakpoints are kept but not sent to the debug adapter.
+    pub disabled: bool,
+}
+
+/// A breakpoint on the entry of a function, see `setFunctionBreakpoints`.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct FunctionBreakpoint {
+    #[serde(skip)]
+    pub id: Option<usize>,
+    #[serde(skip)]
+    pub verified: bool,
+    #[serde(skip)]
+    pub message: Option<String>,
+
+    pub name: String,
+    pub condition: Option<String>,
+    pub hit_condition: Option<String>,
+    pub disabled: bool,
+}
+
+/// A breakpoint on accesses to a variable or a memory location, see `setDataBreakpoints`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DataBreakpoint {
+    #[serde(skip)]
+    pub id: Option<usize>,
+    #[serde(skip)]
+    pub verified: bool,
+    #[serde(skip)]
+    pub message: Option<String>,
+
+    /// The id of the data returned by the debug adapter's `dataBreakpointInfo` response.
+    pub data_id: String,
+    /// The description of the data from the debug adapter, such as the name of the variable.
+    pub description: String,
+    #[serde(default)]
+    pub access_type: Option<dap::DataBreakpointAccessType>,
+    #[serde(default)]
+    pub condition: Option<String>,
+    #[serde(default)]
+    pub hit_condition: Option<String>,
+    /// Whether `data_id` stays valid in later debug sessions. Only these are saved.
+    #[serde(default)]
+    pub can_persist: bool,
+    #[serde(default)]
+    pub disabled: bool,
+}
+
+/// A breakpoint on an instruction address, see `setInstructionBreakpoints`.
+#[derive(Debug, Clone, Default)]
+pub struct InstructionBreakpoint {
+    pub id: Option<usize>,
+    pub verified: bool,
+    pub message: Option<String>,
+
+    /// The memory reference of the instruction, usually its address.
+    pub instruction_reference: String,
+    /// The offset in bytes from `instruction_reference`.
+    pub offset: Option<isize>,
+    pub condition: Option<String>,
+    pub hit_condition: Option<String>,
+    pub disabled: bool,
 }
 
 use futures_util::stream::{Flatten, Once};
@@ -1167,11 +1269,26 @@ pub struct Editor {
     pub macro_recording: Option<(char, Vec<KeyEvent>)>,
     pub macro_replaying: Vec<char>,
     pub language_servers: helix_lsp::Registry,
+    /// The work done progress reported by the language servers.
+    pub lsp_progress: helix_lsp::LspProgressMap,
     pub diagnostics: Diagnostics,
+    /// The state of the `workspace/diagnostic` requests of each language server.
+    pub workspace_diagnostics: HashMap<LanguageServerId, WorkspaceDiagnostics>,
     pub diff_providers: DiffProviderRegistry,
 
     pub debug_adapters: dap::registry::Registry,
     pub breakpoints: HashMap<PathBuf, Vec<Breakpoint>>,
+    pub function_breakpoints: Vec<FunctionBreakpoint>,
+    pub data_breakpoints: Vec<DataBreakpoint>,
+    pub instruction_breakpoints: Vec<InstructionBreakpoint>,
+    /// The workspace the breakpoints are saved for, see [Editor::load_breakpoints].
+    pub breakpoints_workspace: Option<PathBuf>,
+    pub debug_console: DebugConsole,
+    /// The variables and watch expressions shown in the debugger sidebar.
+    pub debug_variables: DebugVariables,
+    /// The number of columns on the right of the screen taken by a sidebar, such as the
+    /// debugger sidebar, which are not available to the views.
+    pub sidebar_width: u16,
 
     pub syn_loader: Arc<ArcSwap<syntax::Loader>>,
     pub theme_loader: Arc<theme::Loader>,
@@ -1229,6 +1346,9 @@ pub enum MotionMode {
 
 pub type Motion = Box<dyn Fn(&mut Editor, MotionMode, Option<Movement>)>;
 
+/// The workspace edits language servers returned for a `workspace/will*Files` request.
+pub type FileOperationEdits = Vec<(LanguageServerId, lsp::WorkspaceEdit)>;
+
 #[derive(Debug)]
 pub enum EditorEvent {
     DocumentSaved(DocumentSavedEventResult),
@@ -1319,10 +1439,19 @@ pub fn new(
             macro_replaying: Vec::new(),
             theme: theme_loader.default(),
             language_servers,
+            lsp_progress: helix_lsp::LspProgressMap::new(),
             diagnostics: Diagnostics::new(),
+            workspace_diagnostics: HashMap::new(),
             diff_providers: DiffProviderRegistry::default(),
             debug_adapters: dap::registry::Registry::new(),
             breakpoints: HashMap::new(),
+            function_breakpoints: Vec::new(),
+            data_breakpoints: Vec::new(),
+            instruction_breakpoints: Vec::new(),
+            breakpoints_workspace: None,
+            debug_console: DebugConsole::default(),
+            debug_variables: DebugVariables::default(),
+            sidebar_width: 0,
             syn_loader,
             theme_loader,
             last_theme: None,
@@ -1528,36 +1657,216 @@ pub fn refresh_language_servers(&mut self, doc_id: DocumentId) {
         self.launch_language_servers(doc_id)
     }
 
-    /// moves/renames a path, invoking any event handlers (currently only lsp)
-    /// and calling `set_doc_path` if the file is open in the editor
-    pub fn move_path(&mut self, old_path: &Path, new_path: &Path) -> io::Result<()> {
-        let new_path = canonicalize(new_path);
-        // sanity check
-        if old_path == new_path {
-            return Ok(());
+    /// Sends a `workspace/will*Files` request to the initialized language servers and applies
+    /// the workspace edits they return before the file operation takes place.
+    fn apply_will_file_operation_edits<F>(
+        &mut self,
+        method: &str,
+        request: impl Fn(&helix_lsp::Client) -> Option<F>,
+    ) where
+        F: Future<Output = helix_lsp::Result<Option<lsp::WorkspaceEdit>>> + Send + 'static,
+    {
+        if let Some(request) = self.will_file_operation(method, request) {
+            let edits = helix_lsp::block_on(request);
+            self.apply_file_operation_edits(method, edits);
         }
-        let is_dir = old_path.is_dir();
-        let language_servers: Vec<_> = self
+    }
+
+    /// Sends a `workspace/will*Files` request to the initialized language servers. Returns
+    /// `None` if none of them is interested in the file operation.
+    ///
+    /// The returned future doesn't borrow the editor so that it can be awaited in a job. The
+    /// edits it resolves to are applied with [`Editor::apply_file_operation_edits`] before the
+    /// file operation takes place.
+    pub fn will_file_operation<F>(
+        &self,
+        method: &str,
+        request: impl Fn(&helix_lsp::Client) -> Option<F>,
+    ) -> Option<BoxFuture<'static, FileOperationEdits>>
+    where
+        F: Future<Output = helix_lsp::Result<Option<lsp::WorkspaceEdit>>> + Send + 'static,
+    {
+        let requests: Vec<_> = self
             .language_servers
             .iter_clients()
             .filter(|client| client.is_initialized())
-            .cloned()
+            .filter_map(|language_server| {
+                let id = language_server.id();
+                let request = request(language_server)?;
+                Some(async move { (id, request.await) })
+            })
             .collect();
-        for language_server in language_servers {
-            let Some(request) = language_server.will_rename(old_path, &new_path, is_dir) else {
-                continue;
-            };
-            let edit = match helix_lsp::block_on(request) {
-                Ok(edit) => edit.unwrap_or_default(),
-                Err(err) => {
-                    log::error!("invalid willRename response: {err:?}");
-                    continue;
+        if requests.is_empty() {
+            return None;
+        }
+        let method = method.to_owned();
+        Some(Box::pin(async move {
+            let mut edits = Vec::new();
+            for (language_server_id, response) in future::join_all(requests).await {
+                match response {
+                    Ok(edit) => edits.extend(edit.map(|edit| (language_server_id, edit))),
+                    Err(err) => log::error!("invalid {method} response: {err:?}"),
                 }
+            }
+            edits
+        }))
+    }
+
+    /// Applies the workspace edits language servers returned for the `workspace/will*Files`
+    /// request `method`, see [`Editor::will_file_operation`].
+    pub fn apply_file_operation_edits(&mut self, method: &str, edits: FileOperationEdits) {
+        for (language_server_id, edit) in edits {
+            let Some(language_server) = self.language_server_by_id(language_server_id) else {
+                continue;
             };
-            if let Err(err) = self.apply_workspace_edit(language_server.offset_encoding(), &edit) {
+            let name = language_server.name().to_owned();
+            let offset_encoding = language_server.offset_encoding();
+            // The file operation can't wait for the user to confirm changes so they are skipped.
+            let items = crate::handlers::lsp::workspace_edit_items(&edit);
+            let skipped = items
+                .iter()
+                .filter(|item| item.needs_confirmation())
+                .count();
+            if skipped > 0 {
+                self.set_warning(format!(
+                    "Skipped {skipped} change(s) from {name} to {method} which need confirmation"
+                ));
+            }
+            let edit = crate::handlers::lsp::select_workspace_edit_items(
+                items.iter().filter(|item| !item.needs_confirmation()),
+            );
+            if let Err(err) = self.apply_workspace_edit(offset_encoding, &edit) {
                 log::error!("failed to apply workspace edit: {err:?}")
             }
         }
+    }
+
+    /// Sends the `workspace/did*Files` notification of a file operation to the initialized
+    /// language servers and notifies the file event handler of the changed paths.
+    fn notify_did_file_operation<'a>(
+        &self,
+        notify: impl Fn(&helix_lsp::Client),
+        changed_paths: impl IntoIterator<Item = &'a Path>,
+    ) {
+        for language_server in self.language_servers.iter_clients() {
+            // A new language server might have been started in `set_doc_path` and won't
+            // be initialized yet. Skip the notification for this server.
+            if language_server.is_initialized() {
+                notify(language_server);
+            }
+        }
+        for path in changed_paths {
+            self.language_servers
+                .file_event_handler
+                .file_changed(path.to_owned());
+        }
+    }
+
+    /// Creates a file, or a directory with its missing parents, invoking any event handlers
+    /// (currently only lsp).
+    ///
+    /// The `workspace/willCreateFiles` request isn't sent: send it with
+    /// [`Editor::will_file_operation`] and apply its edits before creating the path.
+    pub fn create_path(&mut self, path: &Path, is_dir: bool) -> io::Result<()> {
+        let path = canonicalize(path);
+        if is_dir {
+            fs::create_dir_all(&path)?;
+        } else if !path.exists() {
+            // The workspace edit may have created the file already.
+            if let Some(parent) = path.parent() {
+                fs::create_dir_all(parent)?;
+            }
+            fs::File::create_new(&path)?;
+        }
+
+        self.notify_did_file_operation(
+            |language_server| {
+                language_server.did_create(&path, is_dir);
+            },
+            [path.as_path()],
+        );
+        Ok(())
+    }
+
+    /// Copies a file, invoking any event handlers (currently only lsp). An existing
+    /// destination is overwritten.
+    ///
+    /// The `workspace/willCreateFiles` request isn't sent: send it with
+    /// [`Editor::will_file_operation`] and apply its edits before copying.
+    pub fn copy_path(&mut self, old_path: &Path, new_path: &Path) -> io::Result<()> {
+        let new_path = canonicalize(new_path);
+        if old_path.is_dir() {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "copying directories is not supported",
+            ));
+        }
+        // For language servers a copy creates a new file, unless it overwrites one.
+        let created = !new_path.exists();
+
+        fs::copy(old_path, &new_path)?;
+
+        self.notify_did_file_operation(
+            |language_server| {
+                if created {
+                    language_server.did_create(&new_path, false);
+                }
+            },
+            [new_path.as_path()],
+        );
+        Ok(())
+    }
+
+    /// Deletes a file, or a directory and its contents, invoking any event handlers
+    /// (currently only lsp). The unmodified documents of the deleted files are closed.
+    ///
+    /// The `workspace/willDeleteFiles` request isn't sent: send it with
+    /// [`Editor::will_file_operation`] and apply its edits before deleting the path.
+    pub fn delete_path(&mut self, path: &Path) -> io::Result<()> {
+        let path = canonicalize(path);
+        let is_dir = path.is_dir();
+
+        if is_dir {
+            fs::remove_dir_all(&path)?;
+        } else {
+            fs::remove_file(&path)?;
+        }
+
+        // Modified documents are kept so that their changes aren't lost.
+        let deleted_docs: Vec<_> = self
+            .documents()
+            .filter(|doc| !doc.is_modified())
+            .filter(|doc| {
+                doc.path()
+                    .is_some_and(|doc_path| doc_path.starts_with(&path))
+            })
+            .map(|doc| doc.id())
+            .collect();
+        for doc_id in deleted_docs {
+            let _ = self.close_document(doc_id, false);
+        }
+
+        self.notify_did_file_operation(
+            |language_server| {
+                language_server.did_delete(&path, is_dir);
+            },
+            [path.as_path()],
+        );
+        Ok(())
+    }
+
+    /// moves/renames a path, invoking any event handlers (currently only lsp)
+    /// and calling `set_doc_path` if the file is open in the editor
+    pub fn move_path(&mut self, old_path: &Path, new_path: &Path) -> io::Result<()> {
+        let new_path = canonicalize(new_path);
+        // sanity check
+        if old_path == new_path {
+            return Ok(());
+        }
+        let is_dir = old_path.is_dir();
+        self.apply_will_file_operation_edits("willRename", |language_server| {
+            language_server.will_rename(old_path, &new_path, is_dir)
+        });
 
         if old_path.exists() {
             fs::rename(old_path, &new_path)?;
@@ -1567,20 +1876,12 @@ pub fn move_path(&mut self, old_path: &Path, new_path: &Path) -> io::Result<()>
             self.set_doc_path(doc.id(), &new_path);
         }
         let is_dir = new_path.is_dir();
-        for ls in self.language_servers.iter_clients() {
-            // A new language server might have been started in `set_doc_path` and won't
-            // be initialized yet. Skip the `did_rename` notification for this server.
-            if !ls.is_initialized() {
-                continue;
-            }
-            ls.did_rename(old_path, &new_path, is_dir);
-        }
-        self.language_servers
-            .file_event_handler
-            .file_changed(old_path.to_owned());
-        self.language_servers
-            .file_event_handler
-            .file_changed(new_path);
+        self.notify_did_file_operation(
+            |language_server| {
+                language_server.did_rename(old_path, &new_path, is_dir);
+            },
+            [old_path, new_path.as_path()],
+        );
         Ok(())
     }
 
@@ -1634,6 +1935,10 @@ fn launch_language_servers(&mut self, doc_id: DocumentId) {
         let Some(doc_url) = doc.url() else {
             return;
         };
+        // Virtual documents stay attached to the language server that provided them.
+        if doc.is_virtual() {
+            return;
+        }
         let (lang, path) = (doc.language.clone(), doc.path().cloned());
         let config = doc.config.load();
         let root_dirs = &config.workspace_lsp_roots;
@@ -1943,6 +2248,56 @@ pub fn open(&mut self, path: &Path, action: Action) -> Result<DocumentId, Docume
         Ok(id)
     }
 
+    /// Opens a read-only document for a URL that is not a file on disk, with `text` provided by
+    /// the language server `language_server_id`. The document stays attached to that server only.
+    ///
+    /// If a document for `url` is open already it is reused.
+    pub fn open_virtual(
+        &mut self,
+        url: lsp::Url,
+        text: helix_core::Rope,
+        language_server_id: LanguageServerId,
+        action: Action,
+    ) -> DocumentId {
+        let existing = self
+            .document_by_uri(&helix_core::Uri::Virtual(Arc::new(url.clone())))
+            .map(|doc| doc.id());
+        let id = if let Some(id) = existing {
+            id
+        } else {
+            // Virtual URLs often lack a file extension, e.g. `jdt://.../String.class`, so the
+            // language falls back to the one of the current document.
+            let language = doc!(self).language.clone();
+            let mut doc = Document::open_virtual(
+                url.clone(),
+                text,
+                language,
+                self.config.clone(),
+                self.syn_loader.clone(),
+            );
+            let diagnostics =
+                Editor::doc_diagnostics(&self.language_servers, &self.diagnostics, &doc);
+            doc.replace_diagnostics(diagnostics, &[], None);
+
+            if let Some(language_server) = self.language_servers.get_by_id(language_server_id) {
+                let language_id = doc.language_id().map(ToOwned::to_owned).unwrap_or_default();
+                language_server.text_document_did_open(url, doc.version(), doc.text(), language_id);
+                doc.language_servers
+                    .insert(language_server.name().to_string(), language_server.clone());
+            }
+
+            let id = self.new_document(doc);
+            helix_event::dispatch(DocumentDidOpen {
+                editor: self,
+                doc: id,
+            });
+            id
+        };
+
+        self.switch(id, action);
+        id
+    }
+
     pub fn close(&mut self, id: ViewId) {
         // Remove selections for the closed view on all documents.
         for doc in self.documents_mut() {
@@ -2031,6 +2386,23 @@ enum Action {
         Ok(())
     }
 
+    /// Sends the `workspace/willCreateFiles` request of a save which creates the file, see
+    /// [`Editor::will_file_operation`]. The document is written with [`Editor::save`] once the
+    /// edits are applied.
+    pub fn will_create_on_save(
+        &self,
+        doc_id: DocumentId,
+        path: Option<&Path>,
+    ) -> Option<BoxFuture<'static, FileOperationEdits>> {
+        let path = path
+            .or_else(|| self.document(doc_id)?.path().map(PathBuf::as_path))
+            .map(canonicalize)
+            .filter(|path| !path.exists())?;
+        self.will_file_operation("willCreate", |language_server| {
+            language_server.will_create(&path, false)
+        })
+    }
+
     pub fn save<P: Into<PathBuf>>(
         &mut self,
         doc_id: DocumentId,
@@ -2041,6 +2413,21 @@ pub fn save<P: Into<PathBuf>>(
         // via stream.then() ? then push into main future
 
         let path = path.map(|path| path.into());
+
+        // Writing a new file creates it. The `workspace/willCreateFiles` request is sent
+        // beforehand by the caller, see `will_create_on_save`.
+        let created_path = path
+            .as_deref()
+            .or_else(|| doc!(self, &doc_id).path().map(PathBuf::as_path))
+            .map(canonicalize)
+            .filter(|path| !path.exists());
+        let language_servers: Vec<_> = created_path
+            .iter()
+            .flat_map(|_| self.language_servers.iter_clients())
+            .filter(|client| client.is_initialized())
+            .cloned()
+            .collect();
+
         let doc = doc_mut!(self, &doc_id);
         let doc_save_future = doc.save(path, force)?;
 
@@ -2050,6 +2437,11 @@ pub fn save<P: Into<PathBuf>>(
         let future = async move {
             let res = doc_save_future.await;
             if let Ok(event) = &res {
+                if created_path.is_some() {
+                    for language_server in language_servers {
+                        language_server.did_create(&event.path, false);
+                    }
+                }
                 handler.file_changed(event.path.clone());
             }
             res
@@ -2159,6 +2551,16 @@ pub fn document_by_path<P: AsRef<Path>>(&self, path: P) -> Option<&Document> {
             .find(|doc| doc.path().map(|p| p == path.as_ref()).unwrap_or(false))
     }
 
+    /// Returns the document of a file or the open virtual document for `uri`.
+    pub fn document_by_uri(&self, uri: &helix_core::Uri) -> Option<&Document> {
+        match uri.as_path() {
+            Some(path) => self.document_by_path(path),
+            None => self
+                .documents()
+                .find(|doc| doc.is_virtual() && doc.uri().as_ref() == Some(uri)),
+        }
+    }
+
     pub fn document_by_path_mut<P: AsRef<Path>>(&mut self, path: P) -> Option<&mut Document> {
         self.documents_mut()
             .find(|doc| doc.path().map(|p| p == path.as_ref()).unwrap_or(false))
@@ -34,6 +34,9 @@
         server_id: LanguageServerId
     }
 
+    // called after the active debugger stopped and jumped to the stopped stack frame
+    DebuggerDidStop<'a> { editor: &'a mut Editor }
+
     // NOTE: this event is simple for now and is expected to change as the config system evolves.
     // Ideally it would say what changed.
     ConfigDidChange<'a> {
@@ -32,6 +32,7 @@ pub fn style<'doc>(
             GutterType::LineNumbers => line_numbers(editor, doc, view, theme, is_focused),
             GutterType::Spacer => padding(editor, doc, view, theme, is_focused),
             GutterType::Diff => diff(editor, doc, view, theme, is_focused),
+            GutterType::Folds => folds(editor, doc, view, theme, is_focused),
         }
     }
 
@@ -41,6 +42,7 @@ pub fn width(self, view: &View, doc: &Document) -> usize {
             GutterType::LineNumbers => line_numbers_width(view, doc),
             GutterType::Spacer => 1,
             GutterType::Diff => 1,
+            GutterType::Folds => 1,
         }
     }
 }
@@ -139,6 +141,48 @@ pub fn diff<'doc>(
     }
 }
 
+pub fn folds<'doc>(
+    _editor: &'doc Editor,
+    doc: &'doc Document,
+    view: &View,
+    theme: &Theme,
+    _is_focused: bool,
+) -> GutterFn<'doc> {
+    let style = theme.get("ui.gutter.fold");
+    let text = doc.text().slice(..);
+    let folds = doc.folds(view.id);
+    let folding_ranges = &doc.folding_ranges;
+
+    Box::new(
+        move |line: usize, _selected: bool, first_visual_line: bool, out: &mut String| {
+            if !first_visual_line {
+                return None;
+            }
+            let line_start = text.line_to_char(line);
+            let next_line_start = text.line_to_char((line + 1).min(text.len_lines()));
+            if folds
+                .binary_search_by_key(&next_line_start, |fold| fold.start)
+                .is_ok()
+            {
+                write!(out, "▸").unwrap();
+                return Some(style);
+            }
+
+            let idx = folding_ranges.partition_point(|range| range.start < line_start);
+            let foldable = folding_ranges[idx..]
+                .iter()
+                .take_while(|range| range.start < next_line_start)
+                .any(|range| range.lines(text).1 > line);
+            if foldable {
+                write!(out, "▾").unwrap();
+                Some(style)
+            } else {
+                None
+            }
+        },
+    )
+}
+
 pub fn line_numbers<'doc>(
     editor: &'doc Editor,
     doc: &'doc Document,
@@ -237,6 +281,7 @@ pub fn breakpoints<'doc>(
     let error = theme.get("error");
     let info = theme.get("info");
     let breakpoint_style = theme.get("ui.debug.breakpoint");
+    let disabled = theme.get("ui.text.inactive");
 
     let breakpoints = doc.path().and_then(|path| editor.breakpoints.get(path));
 
@@ -254,7 +299,9 @@ pub fn breakpoints<'doc>(
                 .iter()
                 .find(|breakpoint| breakpoint.line == line)?;
 
-            let style = if breakpoint.condition.is_some() && breakpoint.log_message.is_some() {
+            let style = if breakpoint.disabled {
+                disabled
+            } else if breakpoint.condition.is_some() && breakpoint.log_message.is_some() {
                 error.underline_style(UnderlineStyle::Line)
             } else if breakpoint.condition.is_some() {
                 error
@@ -23,9 +23,16 @@ pub struct Handlers {
     pub signature_hints: Sender<lsp::SignatureHelpEvent>,
     pub auto_save: Sender<AutoSaveEvent>,
     pub document_colors: Sender<lsp::DocumentColorsEvent>,
+    pub semantic_tokens: Sender<lsp::SemanticTokensEvent>,
+    pub code_lenses: Sender<lsp::CodeLensEvent>,
+    pub folding_ranges: Sender<lsp::FoldingRangesEvent>,
+    pub document_links: Sender<lsp::DocumentLinksEvent>,
+    pub inline_completions: Sender<lsp::InlineCompletionEvent>,
+    pub linked_editing_ranges: Sender<lsp::LinkedEditingRangeEvent>,
     pub word_index: word_index::Handler,
     pub pull_diagnostics: Sender<lsp::PullDiagnosticsEvent>,
     pub pull_all_documents_diagnostics: Sender<lsp::PullAllDocumentsDiagnosticsEvent>,
+    pub pull_workspace_diagnostics: Sender<lsp::PullWorkspaceDiagnosticsEvent>,
 }
 
 impl Handlers {
@@ -57,6 +64,7 @@ pub fn word_index(&self) -> &word_index::WordIndex {
 }
 
 pub fn register_hooks(handlers: &Handlers) {
+    dap::register_hooks(handlers);
     lsp::register_hooks(handlers);
     word_index::register_hooks(handlers);
 }
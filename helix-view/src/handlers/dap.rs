@@ -1,14 +1,24 @@
-use crate::editor::{Action, Breakpoint};
+use crate::debug_variables::{NodePath, NodeRoot};
+use crate::editor::{
+    Action, Breakpoint, DataBreakpoint, FunctionBreakpoint, InstructionBreakpoint,
+};
+use crate::events::{DebuggerDidStop, DocumentDidOpen};
+use crate::handlers::Handlers;
 use crate::{align_view, Align, Editor};
 use dap::requests::DisconnectArguments;
-use helix_core::Selection;
+use futures_util::future::{join, join_all};
+use helix_core::syntax::config::LanguageServerFeature;
+use helix_core::{Rope, Selection, Syntax};
 use helix_dap::{
     self as dap, registry::DebugAdapterId, Client, ConnectionType, Payload, Request, ThreadId,
 };
-use helix_lsp::block_on;
+use helix_event::register_hook;
+use helix_lsp::{block_on, lsp, util};
 use log::{error, warn};
 use serde_json::{json, Value};
+use std::collections::{BTreeMap, HashMap, HashSet};
 use std::fmt::Write;
+use std::future::Future;
 use std::path::PathBuf;
 
 #[macro_export]
@@ -85,46 +95,494 @@ pub fn jump_to_stack_frame(editor: &mut Editor, frame: &helix_dap::StackFrame) {
     align_view(doc, view, Align::Center);
 }
 
+/// A value to display at the end of a source line of the active stack frame.
+enum InlineValueLookup {
+    Text(String),
+    Variable { name: String, case_sensitive: bool },
+    Expression(String),
+}
+
+/// Stores the responses of the debug adapter in the editor.
+pub type DebugCallback = Box<dyn FnOnce(&mut Editor) + Send>;
+
+/// Whether the stack frame `frame_id` of the debugger `id` is still the active one, so that
+/// the responses of the requests sent for it are current.
+fn is_active_frame(editor: &Editor, id: DebugAdapterId, frame_id: usize) -> bool {
+    editor
+        .debug_adapters
+        .get_active_client()
+        .is_some_and(|debugger| {
+            debugger.id() == id
+                && debugger
+                    .current_stack_frame()
+                    .is_some_and(|frame| frame.id == frame_id)
+        })
+}
+
+/// Requests the scopes of the active stack frame and evaluates the watch expressions, see
+/// [`Editor::debug_variables`]. The values of the previous stop are cleared immediately.
+///
+/// The requests are sent concurrently and the returned future doesn't borrow the editor so
+/// that it can run in a job. It resolves to a callback storing the responses, after which the
+/// children of the expanded variables can be fetched with [`fetch_expanded_variables`].
+pub fn fetch_variables(
+    editor: &mut Editor,
+) -> Option<impl Future<Output = DebugCallback> + Send + 'static> {
+    let variables = &mut editor.debug_variables;
+    variables.clear_values();
+    let debugger = editor.debug_adapters.get_active_client()?;
+    let id = debugger.id();
+    let frame_id = debugger.current_stack_frame()?.id;
+
+    let scopes = debugger.scopes(frame_id);
+    let watches = join_all(variables.watches.iter().map(|watch| {
+        let response = debugger.eval(watch.expression.clone(), Some(frame_id));
+        let expression = watch.expression.clone();
+        async move { (expression, response.await) }
+    }));
+    Some(async move {
+        let (scopes, watches) = join(scopes, watches).await;
+        let callback: DebugCallback = Box::new(move |editor| {
+            if !is_active_frame(editor, id, frame_id) {
+                return;
+            }
+            let variables = &mut editor.debug_variables;
+            match scopes {
+                Ok(scopes) => variables.scopes = scopes,
+                Err(err) => warn!("Failed to fetch the scopes of frame {frame_id}: {err}"),
+            }
+            for (expression, value) in watches {
+                // Watches may have been added or removed meanwhile.
+                if let Some(watch) = variables
+                    .watches
+                    .iter_mut()
+                    .find(|watch| watch.expression == expression)
+                {
+                    watch.value = Some(value.map_err(|err| err.to_string()));
+                }
+            }
+            // Show the first scope's variables unless the user expanded other scopes.
+            if !variables
+                .expanded
+                .iter()
+                .any(|path| matches!(path.root, NodeRoot::Scope(_)))
+            {
+                if let Some(scope) = variables.scopes.iter().find(|scope| !scope.expensive) {
+                    variables.expanded.insert(NodePath {
+                        root: NodeRoot::Scope(scope.name.clone()),
+                        names: Vec::new(),
+                    });
+                }
+            }
+        });
+        callback
+    })
+}
+
+/// Requests the children of the expanded variables which haven't been loaded yet. Returns
+/// `None` if all of them are loaded.
+///
+/// Like [`fetch_variables`] the requests are sent concurrently and the returned future
+/// resolves to a callback storing the responses. The children may be expanded as well, so
+/// this is repeated until it returns `None`.
+pub fn fetch_expanded_variables(
+    editor: &Editor,
+) -> Option<impl Future<Output = DebugCallback> + Send + 'static> {
+    let missing = editor.debug_variables.missing_children();
+    if missing.is_empty() {
+        return None;
+    }
+    let debugger = editor.debug_adapters.get_active_client()?;
+    let id = debugger.id();
+    let frame_id = debugger.current_stack_frame()?.id;
+
+    let children = join_all(
+        missing
+            .iter()
+            .map(|&reference| debugger.variables(reference)),
+    );
+    Some(async move {
+        let children = children.await;
+        let callback: DebugCallback = Box::new(move |editor| {
+            if !is_active_frame(editor, id, frame_id) {
+                return;
+            }
+            for (reference, children) in missing.into_iter().zip(children) {
+                let children = children.unwrap_or_else(|err| {
+                    warn!("Failed to fetch the variables of reference {reference}: {err}");
+                    Vec::new()
+                });
+                editor.debug_variables.children.insert(reference, children);
+            }
+        });
+        callback
+    })
+}
+
+/// Fetches the values displayed at the end of the source lines of the active stack frame,
+/// see [`dap::Client::inline_values`]. The values of the previous stop are cleared immediately.
+///
+/// The lines from the top of the view up to the stopped line are searched for values. The
+/// language servers supporting `textDocument/inlineValue` are asked where values should be
+/// displayed, otherwise the identifiers found by tree-sitter are looked up in the variables of
+/// the frame and the field accesses are evaluated.
+///
+/// Like [`fetch_variables`] the requests are sent concurrently and the returned future resolves
+/// to a callback storing the values.
+pub fn fetch_inline_values(
+    editor: &mut Editor,
+) -> Option<impl Future<Output = DebugCallback> + Send + 'static> {
+    let debugger = editor.debug_adapters.get_active_client_mut()?;
+    debugger.inline_values.clear();
+    let id = debugger.id();
+    let requester = debugger.requester();
+    let frame = debugger.current_stack_frame()?.clone();
+    let path = frame.source.as_ref()?.path.as_ref()?;
+    let doc = editor.document_by_path(path)?;
+    let text = doc.text().clone();
+    let stopped_line = frame.line.saturating_sub(1);
+    if stopped_line >= text.len_lines() {
+        return None;
+    }
+    let first_line = editor
+        .tree
+        .views()
+        .find(|(view, _)| view.doc == doc.id())
+        .map_or(0, |(view, _)| {
+            let anchor = doc.view_offset(view.id).anchor.min(text.len_chars());
+            text.char_to_line(anchor)
+        })
+        .min(stopped_line);
+
+    let inline_value_request = doc
+        .language_servers_with_feature(LanguageServerFeature::InlineValues)
+        .next()
+        .and_then(|language_server| {
+            let offset_encoding = language_server.offset_encoding();
+            let line_range = |start_line: usize| {
+                let start = text.line_to_char(start_line);
+                let end =
+                    helix_core::line_ending::line_end_char_index(&text.slice(..), stopped_line);
+                util::range_to_lsp_range(&text, helix_core::Range::new(start, end), offset_encoding)
+            };
+            let context = lsp::InlineValueContext {
+                frame_id: frame.id as i32,
+                stopped_location: line_range(stopped_line),
+            };
+            let request = language_server.text_document_inline_value(
+                doc.identifier(),
+                line_range(first_line),
+                context,
+            )?;
+            Some((request, offset_encoding))
+        });
+    // The identifiers are only looked up when no language server provides inline values.
+    let identifiers = doc
+        .syntax()
+        .map(|syntax| identifier_lookups(syntax, &text, first_line..stopped_line + 1));
+
+    Some(async move {
+        let mut lookups = None;
+        if let Some((request, offset_encoding)) = inline_value_request {
+            match request.await {
+                Ok(inline_values) => {
+                    lookups = Some(lsp_inline_value_lookups(
+                        &text,
+                        inline_values.unwrap_or_default(),
+                        offset_encoding,
+                    ))
+                }
+                Err(err) => log::error!("inline values request failed: {err}"),
+            }
+        }
+        let inline_values = match lookups.or(identifiers) {
+            Some(lookups) if !lookups.is_empty() => {
+                lookup_inline_values(&requester, frame.id, lookups).await
+            }
+            _ => Vec::new(),
+        };
+        let callback: DebugCallback = Box::new(move |editor| {
+            if !is_active_frame(editor, id, frame.id) {
+                return;
+            }
+            if let Some(debugger) = editor.debug_adapters.get_client_mut(id) {
+                debugger.inline_values = inline_values;
+            }
+        });
+        callback
+    })
+}
+
+/// Looks up the values of `lookups` in the stack frame `frame_id`. The variables of the frame
+/// and the expressions are independent, so they are fetched concurrently.
+async fn lookup_inline_values(
+    requester: &dap::Requester,
+    frame_id: usize,
+    lookups: Vec<(usize, InlineValueLookup)>,
+) -> Vec<dap::InlineValue> {
+    let fetch_variables = async {
+        let mut variables = HashMap::new();
+        if !lookups
+            .iter()
+            .any(|(_, lookup)| matches!(lookup, InlineValueLookup::Variable { .. }))
+        {
+            return variables;
+        }
+        let scopes = requester.scopes(frame_id).await.unwrap_or_default();
+        let scope_variables = join_all(
+            scopes
+                .iter()
+                .filter(|scope| !scope.expensive)
+                .map(|scope| requester.variables(scope.variables_reference)),
+        )
+        .await;
+        // Variables of the innermost scopes shadow the ones of the outer scopes.
+        for variable in scope_variables
+            .into_iter()
+            .flat_map(Result::unwrap_or_default)
+        {
+            variables.entry(variable.name).or_insert(variable.value);
+        }
+        variables
+    };
+    let expressions: HashSet<_> = lookups
+        .iter()
+        .filter_map(|(_, lookup)| match lookup {
+            InlineValueLookup::Expression(expression) => Some(expression),
+            _ => None,
+        })
+        .collect();
+    let evaluate_expressions = join_all(expressions.into_iter().map(|expression| async {
+        let response = requester.eval(expression.clone(), Some(frame_id)).await;
+        (expression.clone(), response.ok())
+    }));
+    let (variables, expressions) = join(fetch_variables, evaluate_expressions).await;
+    let expressions: HashMap<_, _> = expressions.into_iter().collect();
+
+    let mut lines: BTreeMap<usize, Vec<String>> = BTreeMap::new();
+    for (line, lookup) in &lookups {
+        let value = match lookup {
+            InlineValueLookup::Text(text) => text.clone(),
+            InlineValueLookup::Variable {
+                name,
+                case_sensitive,
+            } => {
+                let value = if *case_sensitive {
+                    variables.get(name)
+                } else {
+                    variables
+                        .iter()
+                        .find(|(variable, _)| variable.eq_ignore_ascii_case(name))
+                        .map(|(_, value)| value)
+                };
+                match value {
+                    Some(value) => format!("{name} = {value}"),
+                    None => continue,
+                }
+            }
+            InlineValueLookup::Expression(expression) => match expressions.get(expression) {
+                Some(Some(response)) => format!("{expression} = {}", response.result),
+                _ => continue,
+            },
+        };
+        // Multiline values are displayed on a single line.
+        let value = value.split_whitespace().collect::<Vec<_>>().join(" ");
+        let values = lines.entry(*line).or_default();
+        if !values.contains(&value) {
+            values.push(value);
+        }
+    }
+
+    lines
+        .into_iter()
+        .map(|(line, values)| dap::InlineValue {
+            line,
+            text: values.join(", "),
+        })
+        .collect()
+}
+
+fn lsp_inline_value_lookups(
+    text: &Rope,
+    inline_values: Vec<lsp::InlineValue>,
+    offset_encoding: helix_lsp::OffsetEncoding,
+) -> Vec<(usize, InlineValueLookup)> {
+    inline_values
+        .into_iter()
+        .filter_map(|inline_value| {
+            let (range, lookup) = match inline_value {
+                lsp::InlineValue::Text(value) => (value.range, InlineValueLookup::Text(value.text)),
+                lsp::InlineValue::VariableLookup(lookup) => {
+                    let range = util::lsp_range_to_range(text, lookup.range, offset_encoding)?;
+                    let name = lookup
+                        .variable_name
+                        .unwrap_or_else(|| text.slice(range.from()..range.to()).to_string());
+                    (
+                        lookup.range,
+                        InlineValueLookup::Variable {
+                            name,
+                            case_sensitive: lookup.case_sensitive_lookup,
+                        },
+                    )
+                }
+                lsp::InlineValue::EvaluatableExpression(expression) => {
+                    let range = util::lsp_range_to_range(text, expression.range, offset_encoding)?;
+                    let lookup = InlineValueLookup::Expression(
+                        expression
+                            .expression
+                            .unwrap_or_else(|| text.slice(range.from()..range.to()).to_string()),
+                    );
+                    (expression.range, lookup)
+                }
+            };
+            Some((range.start.line as usize, lookup))
+        })
+        .collect()
+}
+
+/// Finds the identifiers on `lines` which are looked up in the variables of the stack frame when
+/// no language server provides inline values.
+fn identifier_lookups(
+    syntax: &Syntax,
+    text: &Rope,
+    lines: std::ops::Range<usize>,
+) -> Vec<(usize, InlineValueLookup)> {
+    let start = text.line_to_byte(lines.start) as u32;
+    let end = text.line_to_byte(lines.end.min(text.len_lines())) as u32;
+
+    let mut lookups = Vec::new();
+    let mut nodes = vec![syntax.tree().root_node()];
+    while let Some(node) = nodes.pop() {
+        let range = node.byte_range();
+        if range.end < start || range.start > end {
+            continue;
+        }
+        if node.child_count() == 0 {
+            if range.start < start {
+                continue;
+            }
+            let line = text.byte_to_line(range.start as usize);
+            let node_text = |node: &helix_core::tree_sitter::Node| {
+                let range = node.byte_range();
+                text.byte_slice(range.start as usize..range.end as usize)
+                    .to_string()
+            };
+            let lookup = match node.kind() {
+                "identifier"
+                | "shorthand_property_identifier"
+                | "shorthand_property_identifier_pattern"
+                | "shorthand_field_identifier" => InlineValueLookup::Variable {
+                    name: node_text(&node),
+                    case_sensitive: true,
+                },
+                // Fields are evaluated along with the expression they are accessed on, like
+                // `self.len`. Evaluating calls like `items.pop().len` would run them in the
+                // debuggee, so only accesses on fields of variables are evaluated.
+                "field_identifier" | "property_identifier" => match node.parent() {
+                    Some(parent)
+                        if matches!(parent.kind(), "field_expression" | "member_expression")
+                            && parent.byte_range().end == range.end
+                            && is_field_access(&parent) =>
+                    {
+                        let expression = node_text(&parent);
+                        if expression.contains('\n') {
+                            continue;
+                        }
+                        InlineValueLookup::Expression(expression)
+                    }
+                    _ => continue,
+                },
+                _ => continue,
+            };
+            lookups.push((line, lookup));
+            continue;
+        }
+        nodes.extend(node.children());
+    }
+    lookups.sort_by_key(|(line, _)| *line);
+    lookups
+}
+
+/// Whether `node` only reads a variable or the fields of a variable, like `self.items.len`,
+/// so that evaluating it has no side effects.
+fn is_field_access(node: &helix_core::tree_sitter::Node) -> bool {
+    match node.kind() {
+        "identifier" | "self" | "this" | "field_identifier" | "property_identifier" => true,
+        "field_expression" | "member_expression" => node
+            .children()
+            .filter(|child| child.is_named())
+            .all(|child| is_field_access(&child)),
+        _ => false,
+    }
+}
+
+/// Whether the debugger advertises the capability selected by `capability`.
+pub fn supports(
+    debugger: &dap::Client,
+    capability: impl Fn(&dap::DebuggerCapabilities) -> Option<bool>,
+) -> bool {
+    debugger
+        .caps
+        .as_ref()
+        .and_then(capability)
+        .unwrap_or_default()
+}
+
+/// Drops the conditions the debugger doesn't support: it would reject the breakpoints or ignore
+/// the conditions otherwise.
+fn supported_conditions(
+    debugger: &dap::Client,
+    condition: &Option<String>,
+    hit_condition: &Option<String>,
+) -> (Option<String>, Option<String>) {
+    (
+        condition
+            .clone()
+            .filter(|_| supports(debugger, |caps| caps.supports_conditional_breakpoints)),
+        hit_condition
+            .clone()
+            .filter(|_| supports(debugger, |caps| caps.supports_hit_conditional_breakpoints)),
+    )
+}
+
 pub fn breakpoints_changed(
     debugger: &mut dap::Client,
     path: PathBuf,
     breakpoints: &mut [Breakpoint],
 ) -> Result<(), anyhow::Error> {
-    // TODO: handle capabilities correctly again, by filtering breakpoints when emitting
-    // if breakpoint.condition.is_some()
-    //     && !debugger
-    //         .caps
-    //         .as_ref()
-    //         .unwrap()
-    //         .supports_conditional_breakpoints
-    //         .unwrap_or_default()
-    // {
-    //     bail!(
-    //         "Can't edit breakpoint: debugger does not support conditional breakpoints"
-    //     )
-    // }
-    // if breakpoint.log_message.is_some()
-    //     && !debugger
-    //         .caps
-    //         .as_ref()
-    //         .unwrap()
-    //         .supports_log_points
-    //         .unwrap_or_default()
-    // {
-    //     bail!("Can't edit breakpoint: debugger does not support logpoints")
-    // }
+    // Disabled breakpoints aren't sent to the debugger.
+    for breakpoint in breakpoints.iter_mut().filter(|b| b.disabled) {
+        breakpoint.id = None;
+        breakpoint.verified = false;
+        breakpoint.message = None;
+    }
     let source_breakpoints = breakpoints
         .iter()
-        .map(|breakpoint| helix_dap::SourceBreakpoint {
-            line: breakpoint.line + 1, // convert from 0-indexing to 1-indexing (TODO: could set debugger to 0-indexing on init)
-            ..Default::default()
+        .filter(|breakpoint| !breakpoint.disabled)
+        .map(|breakpoint| {
+            let (condition, hit_condition) =
+                supported_conditions(debugger, &breakpoint.condition, &breakpoint.hit_condition);
+            helix_dap::SourceBreakpoint {
+                line: breakpoint.line + 1, // convert from 0-indexing to 1-indexing (TODO: could set debugger to 0-indexing on init)
+                condition,
+                hit_condition,
+                log_message: breakpoint
+                    .log_message
+                    .clone()
+                    .filter(|_| supports(debugger, |caps| caps.supports_log_points)),
+                ..Default::default()
+            }
         })
         .collect::<Vec<_>>();
 
     let request = debugger.set_breakpoints(path, source_breakpoints);
     match block_on(request) {
         Ok(Some(dap_breakpoints)) => {
-            for (breakpoint, dap_breakpoint) in breakpoints.iter_mut().zip(dap_breakpoints) {
+            for (breakpoint, dap_breakpoint) in breakpoints
+                .iter_mut()
+                .filter(|b| !b.disabled)
+                .zip(dap_breakpoints)
+            {
                 breakpoint.id = dap_breakpoint.id;
                 breakpoint.verified = dap_breakpoint.verified;
                 breakpoint.message = dap_breakpoint.message;
@@ -142,7 +600,220 @@ pub fn breakpoints_changed(
     Ok(())
 }
 
+/// A function, data or instruction breakpoint. Unlike the source breakpoints, all the
+/// breakpoints of a kind are sent to the debugger at once.
+trait ListBreakpoint {
+    /// The name of the kind of breakpoint in messages.
+    const KIND: &'static str;
+    /// The breakpoint sent to the debugger.
+    type Request;
+
+    fn supported(caps: &dap::DebuggerCapabilities) -> Option<bool>;
+    fn disabled(&self) -> bool;
+    fn conditions(&self) -> (&Option<String>, &Option<String>);
+    fn request(&self, condition: Option<String>, hit_condition: Option<String>) -> Self::Request;
+    fn state_mut(&mut self) -> (&mut Option<usize>, &mut bool, &mut Option<String>);
+}
+
+impl ListBreakpoint for FunctionBreakpoint {
+    const KIND: &'static str = "function";
+    type Request = dap::FunctionBreakpoint;
+
+    fn supported(caps: &dap::DebuggerCapabilities) -> Option<bool> {
+        caps.supports_function_breakpoints
+    }
+
+    fn disabled(&self) -> bool {
+        self.disabled
+    }
+
+    fn conditions(&self) -> (&Option<String>, &Option<String>) {
+        (&self.condition, &self.hit_condition)
+    }
+
+    fn request(&self, condition: Option<String>, hit_condition: Option<String>) -> Self::Request {
+        dap::FunctionBreakpoint {
+            name: self.name.clone(),
+            condition,
+            hit_condition,
+        }
+    }
+
+    fn state_mut(&mut self) -> (&mut Option<usize>, &mut bool, &mut Option<String>) {
+        (&mut self.id, &mut self.verified, &mut self.message)
+    }
+}
+
+impl ListBreakpoint for DataBreakpoint {
+    const KIND: &'static str = "data";
+    type Request = dap::DataBreakpoint;
+
+    fn supported(caps: &dap::DebuggerCapabilities) -> Option<bool> {
+        caps.supports_data_breakpoints
+    }
+
+    fn disabled(&self) -> bool {
+        self.disabled
+    }
+
+    fn conditions(&self) -> (&Option<String>, &Option<String>) {
+        (&self.condition, &self.hit_condition)
+    }
+
+    fn request(&self, condition: Option<String>, hit_condition: Option<String>) -> Self::Request {
+        dap::DataBreakpoint {
+            data_id: self.data_id.clone(),
+            access_type: self.access_type,
+            condition,
+            hit_condition,
+        }
+    }
+
+    fn state_mut(&mut self) -> (&mut Option<usize>, &mut bool, &mut Option<String>) {
+        (&mut self.id, &mut self.verified, &mut self.message)
+    }
+}
+
+impl ListBreakpoint for InstructionBreakpoint {
+    const KIND: &'static str = "instruction";
+    type Request = dap::InstructionBreakpoint;
+
+    fn supported(caps: &dap::DebuggerCapabilities) -> Option<bool> {
+        caps.supports_instruction_breakpoints
+    }
+
+    fn disabled(&self) -> bool {
+        self.disabled
+    }
+
+    fn conditions(&self) -> (&Option<String>, &Option<String>) {
+        (&self.condition, &self.hit_condition)
+    }
+
+    fn request(&self, condition: Option<String>, hit_condition: Option<String>) -> Self::Request {
+        dap::InstructionBreakpoint {
+            instruction_reference: self.instruction_reference.clone(),
+            offset: self.offset,
+            condition,
+            hit_condition,
+        }
+    }
+
+    fn state_mut(&mut self) -> (&mut Option<usize>, &mut bool, &mut Option<String>) {
+        (&mut self.id, &mut self.verified, &mut self.message)
+    }
+}
+
+/// Sends the enabled `breakpoints` with `request`, the `set*Breakpoints` request of their kind,
+/// and stores the response in them.
+fn list_breakpoints_changed<B: ListBreakpoint, F>(
+    debugger: &dap::Client,
+    breakpoints: &mut [B],
+    request: impl FnOnce(Vec<B::Request>) -> F,
+) -> Result<(), anyhow::Error>
+where
+    F: Future<Output = dap::Result<Vec<dap::Breakpoint>>>,
+{
+    // Disabled breakpoints aren't sent to the debugger.
+    for breakpoint in breakpoints.iter_mut().filter(|b| b.disabled()) {
+        let (id, verified, message) = breakpoint.state_mut();
+        *id = None;
+        *verified = false;
+        *message = None;
+    }
+    if !supports(debugger, B::supported) {
+        anyhow::bail!("Debugger does not support {} breakpoints", B::KIND);
+    }
+    let requested = breakpoints
+        .iter()
+        .filter(|breakpoint| !breakpoint.disabled())
+        .map(|breakpoint| {
+            let (condition, hit_condition) = breakpoint.conditions();
+            let (condition, hit_condition) =
+                supported_conditions(debugger, condition, hit_condition);
+            breakpoint.request(condition, hit_condition)
+        })
+        .collect();
+
+    let dap_breakpoints = block_on(request(requested))
+        .map_err(|err| anyhow::anyhow!("Failed to set {} breakpoints: {err}", B::KIND))?;
+    update_list_breakpoints(breakpoints, dap_breakpoints);
+    Ok(())
+}
+
+/// Stores whether the debugger verified the enabled `breakpoints`. The response has one
+/// breakpoint for each of them, in the same order.
+fn update_list_breakpoints<B: ListBreakpoint>(
+    breakpoints: &mut [B],
+    dap_breakpoints: Vec<dap::Breakpoint>,
+) {
+    for (breakpoint, dap_breakpoint) in breakpoints
+        .iter_mut()
+        .filter(|b| !b.disabled())
+        .zip(dap_breakpoints)
+    {
+        let (id, verified, message) = breakpoint.state_mut();
+        *id = dap_breakpoint.id;
+        *verified = dap_breakpoint.verified;
+        *message = dap_breakpoint.message;
+    }
+}
+
+pub fn function_breakpoints_changed(
+    debugger: &mut dap::Client,
+    breakpoints: &mut [FunctionBreakpoint],
+) -> Result<(), anyhow::Error> {
+    list_breakpoints_changed(debugger, breakpoints, |breakpoints| {
+        debugger.set_function_breakpoints(breakpoints)
+    })
+}
+
+pub fn data_breakpoints_changed(
+    debugger: &mut dap::Client,
+    breakpoints: &mut [DataBreakpoint],
+) -> Result<(), anyhow::Error> {
+    list_breakpoints_changed(debugger, breakpoints, |breakpoints| {
+        debugger.set_data_breakpoints(breakpoints)
+    })
+}
+
+pub fn instruction_breakpoints_changed(
+    debugger: &mut dap::Client,
+    breakpoints: &mut [InstructionBreakpoint],
+) -> Result<(), anyhow::Error> {
+    list_breakpoints_changed(debugger, breakpoints, |breakpoints| {
+        debugger.set_instruction_breakpoints(breakpoints)
+    })
+}
+
 impl Editor {
+    /// Updates the state of the function, data or instruction breakpoint with the id of
+    /// `breakpoint`.
+    fn breakpoint_changed(&mut self, breakpoint: &dap::Breakpoint) {
+        let states = self
+            .function_breakpoints
+            .iter_mut()
+            .map(|b| (b.id, &mut b.verified, &mut b.message))
+            .chain(
+                self.data_breakpoints
+                    .iter_mut()
+                    .map(|b| (b.id, &mut b.verified, &mut b.message)),
+            )
+            .chain(
+                self.instruction_breakpoints
+                    .iter_mut()
+                    .map(|b| (b.id, &mut b.verified, &mut b.message)),
+            );
+        for (id, verified, message) in states {
+            if id == breakpoint.id {
+                *verified = breakpoint.verified;
+                if breakpoint.message.is_some() {
+                    message.clone_from(&breakpoint.message);
+                }
+            }
+        }
+    }
+
     pub async fn handle_debugger_message(
         &mut self,
         id: DebugAdapterId,
@@ -214,6 +885,7 @@ pub async fn handle_debugger_message(
 
                         self.set_status(status);
                         self.debug_adapters.set_active_client(id);
+                        helix_event::dispatch(DebuggerDidStop { editor: self });
                     }
                     Event::Continued(events::ContinuedBody { thread_id, .. }) => {
                         let debugger = match self.debug_adapters.get_client_mut(id) {
@@ -257,8 +929,9 @@ pub async fn handle_debugger_message(
                             }
                             "changed" => {
                                 for breakpoints in self.breakpoints.values_mut() {
-                                    if let Some(i) =
-                                        breakpoints.iter().position(|b| b.id == breakpoint.id)
+                                    if let Some(i) = breakpoints
+                                        .iter()
+                                        .position(|b| b.id.is_some() && b.id == breakpoint.id)
                                     {
                                         breakpoints[i].verified = breakpoint.verified;
                                         breakpoints[i].message = breakpoint
@@ -273,24 +946,36 @@ pub async fn handle_debugger_message(
                                             breakpoint.column.or(breakpoints[i].column);
                                     }
                                 }
+                                if breakpoint.id.is_some() {
+                                    self.breakpoint_changed(&breakpoint);
+                                }
                             }
                             "removed" => {
                                 for breakpoints in self.breakpoints.values_mut() {
-                                    if let Some(i) =
-                                        breakpoints.iter().position(|b| b.id == breakpoint.id)
+                                    if let Some(i) = breakpoints
+                                        .iter()
+                                        .position(|b| b.id.is_some() && b.id == breakpoint.id)
                                     {
                                         breakpoints.remove(i);
                                     }
                                 }
+                                if breakpoint.id.is_some() {
+                                    self.function_breakpoints.retain(|b| b.id != breakpoint.id);
+                                    self.data_breakpoints.retain(|b| b.id != breakpoint.id);
+                                    self.instruction_breakpoints
+                                        .retain(|b| b.id != breakpoint.id);
+                                }
                             }
                             reason => {
                                 warn!("Unknown breakpoint event: {}", reason);
                             }
                         }
                     }
-                    Event::Output(events::OutputBody {
-                        category, output, ..
-                    }) => {
+                    Event::Output(output_body) => {
+                        self.debug_console.push_output(&output_body);
+                        let events::OutputBody {
+                            category, output, ..
+                        } = output_body;
                         let prefix = match category {
                             Some(category) => {
                                 if &category == "telemetry" {
@@ -316,6 +1001,33 @@ pub async fn handle_debugger_message(
                             // TODO: call futures in parallel, await all
                             let _ = breakpoints_changed(debugger, path.clone(), breakpoints);
                         }
+                        // The ids of most data breakpoints are only valid in the session which
+                        // returned them.
+                        self.data_breakpoints
+                            .retain(|breakpoint| breakpoint.can_persist);
+                        if !self.function_breakpoints.is_empty() {
+                            if let Err(err) = function_breakpoints_changed(
+                                debugger,
+                                &mut self.function_breakpoints,
+                            ) {
+                                warn!("{err}");
+                            }
+                        }
+                        if !self.data_breakpoints.is_empty() {
+                            if let Err(err) =
+                                data_breakpoints_changed(debugger, &mut self.data_breakpoints)
+                            {
+                                warn!("{err}");
+                            }
+                        }
+                        if !self.instruction_breakpoints.is_empty() {
+                            if let Err(err) = instruction_breakpoints_changed(
+                                debugger,
+                                &mut self.instruction_breakpoints,
+                            ) {
+                                warn!("{err}");
+                            }
+                        }
                         // TODO: fetch breakpoints (in case we're attaching)
 
                         if debugger.configuration_done().await.is_ok() {
@@ -512,3 +1224,24 @@ pub async fn handle_debugger_message(
         true
     }
 }
+
+pub fn register_hooks(_handlers: &Handlers) {
+    register_hook!(move |event: &mut DocumentDidOpen<'_>| {
+        // Breakpoints are restored from an earlier session: the file may have become shorter
+        // since they were saved.
+        let doc = doc!(event.editor, &event.doc);
+        let Some(path) = doc.path() else {
+            return Ok(());
+        };
+        let len_lines = doc.text().len_lines();
+        let Some(breakpoints) = event.editor.breakpoints.get_mut(path) else {
+            return Ok(());
+        };
+        let len = breakpoints.len();
+        breakpoints.retain(|breakpoint| breakpoint.line < len_lines);
+        if breakpoints.len() != len {
+            event.editor.save_breakpoints();
+        }
+        Ok(())
+    });
+}
@@ -1,14 +1,14 @@
 use std::collections::btree_map::Entry;
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
 use std::fmt::Display;
 
 use crate::editor::Action;
 use crate::events::{
     DiagnosticsDidChange, DocumentDidChange, DocumentDidClose, LanguageServerInitialized,
 };
-use crate::{DocumentId, Editor};
+use crate::{DocumentId, Editor, ViewId};
 use helix_core::diagnostic::DiagnosticProvider;
-use helix_core::Uri;
+use helix_core::{Rope, Uri};
 use helix_event::register_hook;
 use helix_lsp::util::generate_transaction_from_edits;
 use helix_lsp::{lsp, LanguageServerId, OffsetEncoding};
@@ -17,6 +17,36 @@
 
 pub struct DocumentColorsEvent(pub DocumentId);
 
+pub struct SemanticTokensEvent(pub DocumentId);
+
+pub struct CodeLensEvent(pub DocumentId);
+
+pub struct FoldingRangesEvent(pub DocumentId);
+
+pub struct DocumentLinksEvent(pub DocumentId);
+
+pub enum InlineCompletionEvent {
+    /// Requests inline completions at the cursor once the debounce timeout elapsed.
+    /// Invoked requests are sent immediately and ask the server for alternatives.
+    Trigger {
+        doc: DocumentId,
+        view: ViewId,
+        invoked: bool,
+    },
+    Cancel,
+}
+
+pub enum LinkedEditingRangeEvent {
+    /// Requests the linked editing ranges at `cursor` once the debounce timeout elapsed.
+    /// Triggers for the position which was requested last are ignored.
+    Trigger {
+        doc: DocumentId,
+        view: ViewId,
+        cursor: usize,
+    },
+    Cancel,
+}
+
 #[derive(Debug, PartialEq, Eq, Clone, Copy)]
 pub enum SignatureHelpInvoked {
     Automatic,
@@ -39,6 +69,164 @@ pub struct PullAllDocumentsDiagnosticsEvent {
     pub language_servers: HashSet<LanguageServerId>,
 }
 
+pub struct PullWorkspaceDiagnosticsEvent {
+    pub language_servers: HashSet<LanguageServerId>,
+}
+
+/// The state of the `workspace/diagnostic` requests of a language server.
+#[derive(Debug, Default)]
+pub struct WorkspaceDiagnostics {
+    /// The result ids of the latest reports of each document, sent along with the next request
+    /// so that the server only reports the documents whose diagnostics changed.
+    pub previous_result_ids: HashMap<lsp::Url, String>,
+    /// Whether a request is in flight. Only a single request is sent at a time.
+    pub in_flight: bool,
+    /// Whether the diagnostics need to be requested again once the request in flight finished.
+    pub outdated: bool,
+}
+
+/// A part of a workspace edit which is previewed and selected on its own: a resource operation
+/// or the text edits of a document sharing the same change annotation.
+#[derive(Debug, Clone)]
+pub struct WorkspaceEditItem {
+    /// The index of the document change the item was split from.
+    change_idx: usize,
+    pub change: lsp::DocumentChangeOperation,
+    pub annotation: Option<lsp::ChangeAnnotation>,
+}
+
+impl WorkspaceEditItem {
+    /// Whether the language server asked to confirm the change before applying it.
+    pub fn needs_confirmation(&self) -> bool {
+        self.annotation
+            .as_ref()
+            .is_some_and(|annotation| annotation.needs_confirmation == Some(true))
+    }
+}
+
+/// Splits a workspace edit into the items which can be selected in a preview.
+pub fn workspace_edit_items(workspace_edit: &lsp::WorkspaceEdit) -> Vec<WorkspaceEditItem> {
+    let annotation = |id: Option<&lsp::ChangeAnnotationIdentifier>| {
+        workspace_edit
+            .change_annotations
+            .as_ref()
+            .zip(id)
+            .and_then(|(annotations, id)| annotations.get(id))
+            .cloned()
+    };
+
+    let changes: Vec<lsp::DocumentChangeOperation> = match &workspace_edit.document_changes {
+        Some(lsp::DocumentChanges::Edits(edits)) => edits
+            .iter()
+            .cloned()
+            .map(lsp::DocumentChangeOperation::Edit)
+            .collect(),
+        Some(lsp::DocumentChanges::Operations(operations)) => operations.clone(),
+        None => {
+            let mut changes: Vec<_> = workspace_edit.changes.iter().flatten().collect();
+            changes.sort_by(|(a, _), (b, _)| a.as_str().cmp(b.as_str()));
+            changes
+                .into_iter()
+                .map(|(uri, edits)| {
+                    lsp::DocumentChangeOperation::Edit(lsp::TextDocumentEdit {
+                        text_document: lsp::OptionalVersionedTextDocumentIdentifier {
+                            uri: uri.clone(),
+                            version: None,
+                        },
+                        edits: edits.iter().cloned().map(lsp::OneOf::Left).collect(),
+                    })
+                })
+                .collect()
+        }
+    };
+
+    let mut items = Vec::new();
+    for (change_idx, change) in changes.into_iter().enumerate() {
+        match change {
+            lsp::DocumentChangeOperation::Op(op) => {
+                let annotation_id = match &op {
+                    lsp::ResourceOp::Create(op) => op.annotation_id.as_ref(),
+                    lsp::ResourceOp::Rename(op) => op.annotation_id.as_ref(),
+                    lsp::ResourceOp::Delete(op) => op
+                        .options
+                        .as_ref()
+                        .and_then(|options| options.annotation_id.as_ref()),
+                };
+                items.push(WorkspaceEditItem {
+                    change_idx,
+                    annotation: annotation(annotation_id),
+                    change: lsp::DocumentChangeOperation::Op(op),
+                });
+            }
+            lsp::DocumentChangeOperation::Edit(document_edit) => {
+                // Group the edits by annotation, in the order the annotations first appear.
+                let mut groups: Vec<(Option<String>, Vec<_>)> = Vec::new();
+                for edit in document_edit.edits {
+                    let annotation_id = match &edit {
+                        lsp::OneOf::Left(_) => None,
+                        lsp::OneOf::Right(edit) => Some(edit.annotation_id.clone()),
+                    };
+                    match groups.iter_mut().find(|(id, _)| *id == annotation_id) {
+                        Some((_, edits)) => edits.push(edit),
+                        None => groups.push((annotation_id, vec![edit])),
+                    }
+                }
+                items.extend(
+                    groups
+                        .into_iter()
+                        .map(|(annotation_id, edits)| WorkspaceEditItem {
+                            change_idx,
+                            annotation: annotation(annotation_id.as_ref()),
+                            change: lsp::DocumentChangeOperation::Edit(lsp::TextDocumentEdit {
+                                text_document: document_edit.text_document.clone(),
+                                edits,
+                            }),
+                        }),
+                );
+            }
+        }
+    }
+    items
+}
+
+/// Builds the workspace edit applying only the given items of [`workspace_edit_items`].
+pub fn select_workspace_edit_items<'a>(
+    items: impl IntoIterator<Item = &'a WorkspaceEditItem>,
+) -> lsp::WorkspaceEdit {
+    let mut operations: Vec<(usize, lsp::DocumentChangeOperation)> = Vec::new();
+    for item in items {
+        // The edits of a document have to be applied at once as they refer to the same version.
+        if let (
+            Some((change_idx, lsp::DocumentChangeOperation::Edit(document_edit))),
+            lsp::DocumentChangeOperation::Edit(item_edit),
+        ) = (operations.last_mut(), &item.change)
+        {
+            if *change_idx == item.change_idx {
+                document_edit.edits.extend(item_edit.edits.iter().cloned());
+                continue;
+            }
+        }
+        operations.push((item.change_idx, item.change.clone()));
+    }
+
+    lsp::WorkspaceEdit {
+        document_changes: Some(lsp::DocumentChanges::Operations(
+            operations
+                .into_iter()
+                .map(|(_, operation)| operation)
+                .collect(),
+        )),
+        ..Default::default()
+    }
+}
+
+/// Whether the language server asked to confirm some of the changes of a workspace edit.
+pub fn workspace_edit_needs_confirmation(workspace_edit: &lsp::WorkspaceEdit) -> bool {
+    workspace_edit_items(workspace_edit)
+        .iter()
+        .any(WorkspaceEditItem::needs_confirmation)
+}
+
 #[derive(Debug)]
 pub struct ApplyEditError {
     pub kind: ApplyEditErrorKind,
@@ -50,6 +238,8 @@ pub enum ApplyEditErrorKind {
     DocumentChanged,
     FileNotFound,
     InvalidUrl(helix_core::uri::UrlConversionError),
+    /// A resource operation on a URI that is not a file on disk.
+    NotAFile(Uri),
     IoError(std::io::Error),
     // TODO: check edits before applying and propagate failure
     // InvalidEdit,
@@ -73,12 +263,41 @@ fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
             ApplyEditErrorKind::DocumentChanged => f.write_str("document has changed"),
             ApplyEditErrorKind::FileNotFound => f.write_str("file not found"),
             ApplyEditErrorKind::InvalidUrl(err) => f.write_str(&format!("{err}")),
+            ApplyEditErrorKind::NotAFile(uri) => write!(f, "{uri} is not a file"),
             ApplyEditErrorKind::IoError(err) => f.write_str(&format!("{err}")),
         }
     }
 }
 
 impl Editor {
+    /// Returns the text of the document before and after applying the text edits of a
+    /// workspace edit item, or `None` for resource operations and unreadable documents.
+    pub fn workspace_edit_item_preview(
+        &self,
+        item: &WorkspaceEditItem,
+        offset_encoding: OffsetEncoding,
+    ) -> Option<(Rope, Rope)> {
+        let lsp::DocumentChangeOperation::Edit(document_edit) = &item.change else {
+            return None;
+        };
+        let uri = Uri::try_from(&document_edit.text_document.uri).ok()?;
+        let old = match self.document_by_uri(&uri) {
+            Some(doc) => doc.text().clone(),
+            None => Rope::from(std::fs::read_to_string(uri.as_path()?).ok()?),
+        };
+        let edits = document_edit
+            .edits
+            .iter()
+            .map(|edit| match edit {
+                lsp::OneOf::Left(text_edit) => text_edit.clone(),
+                lsp::OneOf::Right(annotated_text_edit) => annotated_text_edit.text_edit.clone(),
+            })
+            .collect();
+        let transaction = generate_transaction_from_edits(&old, edits, offset_encoding);
+        let mut new = old.clone();
+        transaction.apply(&mut new).then_some((old, new))
+    }
+
     fn apply_text_edits(
         &mut self,
         url: &helix_lsp::Url,
@@ -93,16 +312,18 @@ fn apply_text_edits(
                 return Err(err.into());
             }
         };
-        let path = uri.as_path().expect("URIs are valid paths");
-
-        let doc_id = match self.open(path, Action::Load) {
+        let doc_id = match uri.as_path() {
+            Some(path) => self.open(path, Action::Load).map_err(|err| err.to_string()),
+            // Virtual documents can only be edited while they are open.
+            None => self
+                .document_by_uri(&uri)
+                .map(|doc| doc.id())
+                .ok_or_else(|| "virtual document is not open".to_string()),
+        };
+        let doc_id = match doc_id {
             Ok(doc_id) => doc_id,
             Err(err) => {
-                let err = format!(
-                    "failed to open document: {}: {}",
-                    path.to_string_lossy(),
-                    err
-                );
+                let err = format!("failed to open document: {uri}: {err}");
                 log::error!("{}", err);
                 self.set_error(err);
                 return Err(ApplyEditErrorKind::FileNotFound);
@@ -112,7 +333,7 @@ fn apply_text_edits(
         let doc = doc_mut!(self, &doc_id);
         if let Some(version) = version {
             if version != doc.version() {
-                let err = format!("outdated workspace edit for {path:?}");
+                let err = format!("outdated workspace edit for {uri}");
                 log::error!("{err}, expected {} but got {version}", doc.version());
                 self.set_error(err);
                 return Err(ApplyEditErrorKind::DocumentChanged);
@@ -230,12 +451,15 @@ fn apply_document_resource_op(
     ) -> Result<(), ApplyEditErrorKind> {
         use lsp::ResourceOp;
         use std::fs;
-        // NOTE: If `Uri` gets another variant than `Path`, the below `expect`s
-        // may no longer be valid.
+        fn file_path(uri: &Uri) -> Result<&std::path::Path, ApplyEditErrorKind> {
+            uri.as_path()
+                .ok_or_else(|| ApplyEditErrorKind::NotAFile(uri.clone()))
+        }
+
         match op {
             ResourceOp::Create(op) => {
                 let uri = Uri::try_from(&op.uri)?;
-                let path = uri.as_path().expect("URIs are valid paths");
+                let path = file_path(&uri)?;
                 let ignore_if_exists = op.options.as_ref().is_some_and(|options| {
                     !options.overwrite.unwrap_or(false) && options.ignore_if_exists.unwrap_or(false)
                 });
@@ -255,7 +479,7 @@ fn apply_document_resource_op(
             }
             ResourceOp::Delete(op) => {
                 let uri = Uri::try_from(&op.uri)?;
-                let path = uri.as_path().expect("URIs are valid paths");
+                let path = file_path(&uri)?;
                 if path.is_dir() {
                     let recursive = op
                         .options
@@ -277,9 +501,9 @@ fn apply_document_resource_op(
             }
             ResourceOp::Rename(op) => {
                 let from_uri = Uri::try_from(&op.old_uri)?;
-                let from = from_uri.as_path().expect("URIs are valid paths");
+                let from = file_path(&from_uri)?;
                 let to_uri = Uri::try_from(&op.new_uri)?;
-                let to = to_uri.as_path().expect("URIs are valid paths");
+                let to = file_path(&to_uri)?;
                 let ignore_if_exists = op.options.as_ref().is_some_and(|options| {
                     !options.overwrite.unwrap_or(false) && options.ignore_if_exists.unwrap_or(false)
                 });
@@ -444,3 +668,116 @@ pub fn register_hooks(_handlers: &Handlers) {
         Ok(())
     });
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn text_edit(line: u32, new_text: &str) -> lsp::TextEdit {
+        lsp::TextEdit {
+            range: lsp::Range::new(lsp::Position::new(line, 0), lsp::Position::new(line, 1)),
+            new_text: new_text.to_string(),
+        }
+    }
+
+    fn annotated(line: u32, new_text: &str, annotation_id: &str) -> lsp::AnnotatedTextEdit {
+        lsp::AnnotatedTextEdit {
+            text_edit: text_edit(line, new_text),
+            annotation_id: annotation_id.to_string(),
+        }
+    }
+
+    #[test]
+    fn split_and_select_workspace_edit_items() {
+        let uri: lsp::Url = "file:///tmp/a.rs".parse().unwrap();
+        let workspace_edit = lsp::WorkspaceEdit {
+            document_changes: Some(lsp::DocumentChanges::Operations(vec![
+                lsp::DocumentChangeOperation::Edit(lsp::TextDocumentEdit {
+                    text_document: lsp::OptionalVersionedTextDocumentIdentifier {
+                        uri: uri.clone(),
+                        version: Some(1),
+                    },
+                    edits: vec![
+                        lsp::OneOf::Left(text_edit(0, "a")),
+                        lsp::OneOf::Right(annotated(1, "b", "comments")),
+                        lsp::OneOf::Left(text_edit(2, "c")),
+                        lsp::OneOf::Right(annotated(3, "d", "comments")),
+                    ],
+                }),
+                lsp::DocumentChangeOperation::Op(lsp::ResourceOp::Delete(lsp::DeleteFile {
+                    uri: "file:///tmp/b.rs".parse().unwrap(),
+                    options: None,
+                })),
+            ])),
+            change_annotations: Some(
+                [(
+                    "comments".to_string(),
+                    lsp::ChangeAnnotation {
+                        label: "Rename in comments".to_string(),
+                        needs_confirmation: Some(true),
+                        description: None,
+                    },
+                )]
+                .into_iter()
+                .collect(),
+            ),
+            ..Default::default()
+        };
+
+        assert!(workspace_edit_needs_confirmation(&workspace_edit));
+        let items = workspace_edit_items(&workspace_edit);
+        assert_eq!(items.len(), 3);
+        assert!(!items[0].needs_confirmation());
+        assert!(items[1].needs_confirmation());
+        assert_eq!(
+            items[1].annotation.as_ref().unwrap().label,
+            "Rename in comments"
+        );
+        assert!(matches!(
+            items[2].change,
+            lsp::DocumentChangeOperation::Op(lsp::ResourceOp::Delete(_))
+        ));
+
+        // Selected edits of the same document are merged back into a single change.
+        let selected = select_workspace_edit_items(&items[..2]);
+        let Some(lsp::DocumentChanges::Operations(operations)) = selected.document_changes else {
+            panic!("expected document operations");
+        };
+        assert_eq!(operations.len(), 1);
+        let lsp::DocumentChangeOperation::Edit(document_edit) = &operations[0] else {
+            panic!("expected a text document edit");
+        };
+        assert_eq!(document_edit.text_document.version, Some(1));
+        assert_eq!(document_edit.edits.len(), 4);
+
+        let selected = select_workspace_edit_items([&items[0], &items[2]]);
+        let Some(lsp::DocumentChanges::Operations(operations)) = selected.document_changes else {
+            panic!("expected document operations");
+        };
+        assert_eq!(operations.len(), 2);
+    }
+
+    #[test]
+    fn workspace_edit_items_from_changes() {
+        let workspace_edit = lsp::WorkspaceEdit {
+            changes: Some(
+                [
+                    ("file:///b".parse().unwrap(), vec![text_edit(0, "b")]),
+                    ("file:///a".parse().unwrap(), vec![text_edit(0, "a")]),
+                ]
+                .into_iter()
+                .collect(),
+            ),
+            ..Default::default()
+        };
+        assert!(!workspace_edit_needs_confirmation(&workspace_edit));
+        let uris: Vec<_> = workspace_edit_items(&workspace_edit)
+            .into_iter()
+            .map(|item| match item.change {
+                lsp::DocumentChangeOperation::Edit(edit) => edit.text_document.uri.to_string(),
+                lsp::DocumentChangeOperation::Op(_) => unreachable!(),
+            })
+            .collect();
+        assert_eq!(uris, ["file:///a", "file:///b"]);
+    }
+}
@@ -3,6 +3,9 @@
 
 pub mod annotations;
 pub mod clipboard;
+pub mod debug_breakpoints;
+pub mod debug_console;
+pub mod debug_variables;
 pub mod document;
 pub mod editor;
 pub mod events;
@@ -1,6 +1,9 @@
 use crate::{
     align_view,
-    annotations::diagnostics::InlineDiagnostics,
+    annotations::{
+        code_lens::CodeLenses, diagnostics::InlineDiagnostics,
+        inline_completion::InlineCompletionLines,
+    },
     document::{DocumentColorSwatches, DocumentInlayHints},
     editor::{GutterConfig, GutterType},
     graphics::Rect,
@@ -443,6 +446,7 @@ pub fn text_annotations<'a>(
         theme: Option<&Theme>,
     ) -> TextAnnotations<'a> {
         let mut text_annotations = TextAnnotations::default();
+        text_annotations.set_folds(doc.folds(self.id));
 
         if let Some(labels) = doc.jump_labels.get(&self.id) {
             let style = theme.and_then(|t| t.find_highlight("ui.virtual.jump-label"));
@@ -491,6 +495,10 @@ pub fn text_annotations<'a>(
             }
         }
 
+        if let Some(inline_completion_lines) = InlineCompletionLines::new(doc, self.id) {
+            text_annotations.add_line_annotation(inline_completion_lines);
+        }
+
         let width = self.inner_width(doc);
         let enable_cursor_line = self
             .diagnostics_handler
@@ -510,6 +518,10 @@ pub fn text_annotations<'a>(
             ));
         }
 
+        if doc.config.load().lsp.display_code_lenses && !doc.code_lenses.is_empty() {
+            text_annotations.add_line_annotation(CodeLenses::new(doc, self.id));
+        }
+
         text_annotations
     }
 
@@ -0,0 +1,16 @@
+[
+  (function_definition)
+  (struct_specifier)
+  (union_specifier)
+  (enum_specifier)
+  (if_statement)
+  (for_statement)
+  (while_statement)
+  (do_statement)
+  (switch_statement)
+  (initializer_list)
+] @fold
+
+(preproc_include)+ @fold
+
+(comment)+ @fold
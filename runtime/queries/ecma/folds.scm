@@ -0,0 +1,16 @@
+[
+  (function_declaration)
+  (generator_function_declaration)
+  (arrow_function)
+  (class_declaration)
+  (method_definition)
+  (switch_statement)
+  (for_statement)
+  (for_in_statement)
+  (object)
+  (array)
+] @fold
+
+(import_statement)+ @fold
+
+(comment)+ @fold
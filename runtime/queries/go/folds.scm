@@ -0,0 +1,15 @@
+[
+  (function_declaration)
+  (method_declaration)
+  (func_literal)
+  (type_declaration)
+  (const_declaration)
+  (var_declaration)
+  (import_declaration)
+  (expression_switch_statement)
+  (type_switch_statement)
+  (select_statement)
+  (literal_value)
+] @fold
+
+(comment)+ @fold
@@ -0,0 +1,18 @@
+[
+  (function_definition)
+  (class_definition)
+  (if_statement)
+  (for_statement)
+  (while_statement)
+  (try_statement)
+  (with_statement)
+  (match_statement)
+  (dictionary)
+] @fold
+
+[
+  (import_statement)
+  (import_from_statement)
+]+ @fold
+
+(comment)+ @fold
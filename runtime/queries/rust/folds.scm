@@ -0,0 +1,16 @@
+[
+  (function_item)
+  (impl_item)
+  (trait_item)
+  (struct_item)
+  (enum_item)
+  (union_item)
+  (mod_item)
+  (macro_definition)
+  (match_expression)
+  (block_comment)
+] @fold
+
+(use_declaration)+ @fold
+
+(line_comment)+ @fold
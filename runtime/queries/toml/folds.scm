@@ -0,0 +1,7 @@
+[
+  (table)
+  (table_array_element)
+  (array)
+] @fold
+
+(comment)+ @fold
@@ -0,0 +1,3 @@
+; See runtime/queries/ecma/README.md for more info.
+
+; inherits: ecma